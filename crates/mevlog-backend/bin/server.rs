@@ -2,7 +2,7 @@ use axum::http::StatusCode;
 use axum::middleware::from_fn;
 use eyre::Result;
 use mevlog_backend::config::{cors, middleware, routes::app};
-use mevlog_backend::misc::prices::spawn_price_refresh;
+use mevlog_backend::misc::{prices::spawn_price_refresh, utils::sql_rpc_call_budget};
 use std::time::Duration;
 use tokio::net::TcpListener;
 use tower_http::{
@@ -31,6 +31,10 @@ async fn main() -> Result<()> {
 async fn run() -> Result<()> {
     middleware::init_logs("server.log");
 
+    if let Some(budget) = sql_rpc_call_budget()? {
+        info!("eth_call / eth_balance enabled for /api/search, {budget} RPC calls per query");
+    }
+
     // Keep the process-local price cache warm so web queries pass
     // `--native-token-price` and never fall back to the Chainlink oracle RPC.
     spawn_price_refresh().await;
@@ -30,6 +30,10 @@ pub async fn app() -> Router {
         .route("/api/chains", get(json::chains_controller::chains))
         .route("/api/db-info", get(json::db_info_controller::db_info))
         .route("/api/explore", get(json::explore_controller::explore))
+        .route(
+            "/api/saved-queries",
+            get(json::saved_queries_controller::saved_queries),
+        )
         .route("/api/search", get(json::search_controller::search))
         .route("/uptime", get(|| async move { "OK".into_response() }))
         .route("/robots.txt", get(robots_txt))
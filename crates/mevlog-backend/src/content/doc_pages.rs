@@ -65,7 +65,7 @@ pub const DOC_PAGES: &[DocPage] = &[
     DocPage {
         path: "/docs/evm-sqlite-helpers",
         title: "SQLite Functions & Macros for EVM Data | mevlog-rs",
-        description: "Custom SQLite functions for U256 BLOB columns (u256_sum, u256_mul, erc20_to_real), ETH/gwei/USD display helpers, and the LATEST_BLOCK, NATIVE_TOKEN_PRICE, and RESOLVE_ENS macros.",
+        description: "Custom SQLite functions for U256 BLOB columns (u256_sum, u256_mul, erc20_to_real), ETH/gwei/USD display helpers, and the LATEST_BLOCK, BLOCK_AT, NATIVE_TOKEN_PRICE, and RESOLVE_ENS macros.",
         breadcrumb_label: "Functions & Macros",
         last_updated_at: "2026-06-14",
         sitemap_priority: "0.8",
@@ -82,6 +82,16 @@ pub const DOC_PAGES: &[DocPage] = &[
         sitemap_changefreq: "monthly",
         faqs: &[],
     },
+    DocPage {
+        path: "/docs/saved-queries",
+        title: "Saved SQL Query Library | mevlog-rs",
+        description: "Keep parameterized SQL queries as .sql files in ~/.mevlog/queries/ and run them by name from the CLI, the MCP server and the web API.",
+        breadcrumb_label: "Saved Queries",
+        last_updated_at: "2026-10-16",
+        sitemap_priority: "0.7",
+        sitemap_changefreq: "monthly",
+        faqs: &[],
+    },
     DocPage {
         path: "/docs/sqlite-storage",
         title: "Storage Requirements & Disk Sizing | mevlog-rs",
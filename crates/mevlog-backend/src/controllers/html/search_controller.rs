@@ -46,6 +46,9 @@ pub struct SearchParams {
     #[serde(default, deserialize_with = "empty_string_as_none")]
     pub sql: Option<String>,
     pub chain_id: Option<u64>,
+    /// Name of a saved query to run instead of `sql` (`/api/search` only).
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub saved: Option<String>,
 }
 
 #[hotpath::measure]
@@ -3,4 +3,5 @@ pub mod chain_info_controller;
 pub mod chains_controller;
 pub mod db_info_controller;
 pub mod explore_controller;
+pub mod saved_queries_controller;
 pub mod search_controller;
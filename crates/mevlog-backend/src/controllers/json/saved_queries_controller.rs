@@ -0,0 +1,20 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use mevlog::misc::saved_queries::SavedQuery;
+
+/// The server's saved query library, runnable via `/api/search?saved=<name>`.
+#[hotpath::measure(log = true)]
+pub(crate) async fn saved_queries() -> impl IntoResponse {
+    match tokio::task::spawn_blocking(SavedQuery::list).await {
+        Ok(Ok(queries)) => (StatusCode::OK, Json(queries)).into_response(),
+        Ok(Err(error)) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": error.to_string() })),
+        )
+            .into_response(),
+        Err(error) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": error.to_string() })),
+        )
+            .into_response(),
+    }
+}
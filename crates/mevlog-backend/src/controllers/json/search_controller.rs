@@ -1,8 +1,17 @@
-use axum::{Json, extract::Query, http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    extract::{Query, RawQuery},
+    http::StatusCode,
+    response::IntoResponse,
+};
 use mevlog::{
-    cmds::query::query,
-    db::txs::{self, info::db_info},
-    misc::shared_init::{ConnOpts, CryoOpts, SharedOpts},
+    cmds::query::{QueryOpts, query},
+    db::txs::{self, info::db_info, raw_query::SqlParam},
+    misc::{
+        args_parsing::BlockTag,
+        saved_queries::SavedQuery,
+        shared_init::{ConnOpts, CryoOpts, SharedOpts},
+    },
     models::json::query_response::serialize_query_response,
 };
 
@@ -11,7 +20,9 @@ use crate::{
         base_controller::decorate_error_message, html::search_controller::SearchParams,
         json::base_controller::extract_json_query_params,
     },
-    misc::{prices::get_price_for_chain_id, rpc_utils::get_random_rpc_url},
+    misc::{
+        prices::get_price_for_chain_id, rpc_utils::get_random_rpc_url, utils::sql_rpc_call_budget,
+    },
 };
 
 // Kept below the server-wide 10s TimeoutLayer so query()'s decorated timeout
@@ -21,12 +32,18 @@ const QUERY_TIMEOUT_MS: u64 = 8000;
 #[hotpath::measure]
 pub(crate) async fn search(
     query_params: Result<Query<SearchParams>, axum::extract::rejection::QueryRejection>,
+    RawQuery(raw_query): RawQuery,
 ) -> impl IntoResponse {
     let params = match extract_json_query_params(query_params) {
         Ok(params) => params,
         Err(error_response) => return error_response.into_response(),
     };
 
+    let sql_params = match sql_params(raw_query.as_deref()) {
+        Ok(sql_params) => sql_params,
+        Err(error) => return bad_request(&error),
+    };
+
     tracing::debug!("params: {:?}", params);
 
     let chain_id = params.chain_id.unwrap_or(1);
@@ -64,24 +81,54 @@ pub(crate) async fn search(
 
     let cryo_opts = CryoOpts::default();
 
-    let sql = params.sql.unwrap_or_default();
+    // eth_call / eth_balance are off for web queries unless SQL_RPC_CALL_BUDGET
+    // caps how many RPC requests each query may make. The value was validated
+    // at startup.
+    let rpc_calls = sql_rpc_call_budget().ok().flatten();
+
+    // A saved query runs exactly as `mevlog query --saved` would: its SQL,
+    // its declared params (given ones override defaults) and its description.
+    let (sql, sql_params, description) = match &params.saved {
+        Some(name) => {
+            let saved = match SavedQuery::load(name) {
+                Ok(saved) => saved,
+                Err(error) => return bad_request(&error),
+            };
+            let sql_params = match saved.bind(&sql_params) {
+                Ok(sql_params) => sql_params,
+                Err(error) => return bad_request(&error),
+            };
+            (saved.sql, sql_params, saved.description)
+        }
+        None => (params.sql.unwrap_or_default(), sql_params, None),
+    };
 
     // The scheduler keeps the store indexed; web queries read it as-is
     // (skip_index = true => no block range resolution, fetching, or backfill).
     // The timeout is enforced inside query() (shared with the CLI's --timeout-ms).
-    let outcome = match query(
-        None, // blocks
-        None, // latest_offset
-        None, // max_range
-        Some(200),
-        100,  // batch_size (CLI default)
-        true, // skip_index
+    let opts = QueryOpts {
+        blocks: None,
+        latest_offset: None,
+        head: BlockTag::Latest,
+        max_range: None,
+        max_rows: Some(200),
+        batch_size: 100, // CLI default
+        skip_index: true,
+        balance_changes: false,
+        traces: false,
         latest_block,
+        rpc_calls,
+        // Anonymous queries must not grow the unbounded disk cache.
+        rpc_call_cache: false,
+        timeout_ms: Some(QUERY_TIMEOUT_MS),
+    };
+    let outcome = match query(
         &sql,
+        &sql_params,
+        opts,
         &shared_opts,
         &conn_opts,
         &cryo_opts,
-        Some(QUERY_TIMEOUT_MS),
     )
     .await
     {
@@ -104,8 +151,9 @@ pub(crate) async fn search(
         outcome.cached_blocks,
         outcome.new_blocks,
         outcome.latest_block,
+        outcome.latest_block_tag,
         outcome.query,
-        None,
+        description,
     ) {
         Ok(body) => body,
         Err(error) => {
@@ -127,6 +175,28 @@ pub(crate) async fn search(
     }
 }
 
+fn bad_request(error: &eyre::Report) -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error.to_string() })),
+    )
+        .into_response()
+}
+
+/// Bind parameters from repeated `param=name=value` query pairs, e.g.
+/// `?sql=...+WHERE+from_address+%3D+%3Afrom&param=from%3D0x1111...1111`.
+/// Read from the raw query string since `SearchParams` can't hold repeats.
+fn sql_params(raw_query: Option<&str>) -> eyre::Result<Vec<SqlParam>> {
+    let Some(raw_query) = raw_query else {
+        return Ok(vec![]);
+    };
+
+    url::form_urlencoded::parse(raw_query.as_bytes())
+        .filter(|(key, _)| key == "param")
+        .map(|(_, value)| value.parse())
+        .collect()
+}
+
 /// Highest block indexed in the local per-chain txs store, read directly from
 /// SQLite. Returns `None` (and lets the query fall back to an RPC lookup) when
 /// the DB is absent or unreadable.
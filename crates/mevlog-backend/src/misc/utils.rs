@@ -4,6 +4,20 @@ pub(crate) fn deployed_at() -> String {
     std::env::var("DEPLOYED_AT").unwrap_or_else(|_| "unknown".to_string())
 }
 
+/// `SQL_RPC_CALL_BUDGET`, the RPC requests each `/api/search` query may make
+/// through `eth_call` / `eth_balance`. Unset disables the functions; an
+/// unparsable value is an error, checked once at server startup.
+pub fn sql_rpc_call_budget() -> Result<Option<u64>> {
+    match std::env::var("SQL_RPC_CALL_BUDGET") {
+        Ok(budget) => budget.trim().parse::<u64>().map(Some).map_err(|e| {
+            eyre::eyre!(
+                "invalid SQL_RPC_CALL_BUDGET '{budget}', expected a number of RPC calls: {e}"
+            )
+        }),
+        Err(_) => Ok(None),
+    }
+}
+
 #[hotpath::measure]
 pub async fn uptime_ping(uptime_url: &str) -> Result<()> {
     let client = reqwest::Client::new();
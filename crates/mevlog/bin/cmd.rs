@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use eyre::Result;
 use mevlog::{
-    misc::{config::Config, ipfs, shared_init::OutputFormat},
+    misc::{args_parsing::BlockTag, config::Config, ipfs, shared_init::OutputFormat},
     models::json::query_response::{
         HtmlMeta, QueryOutcome, content_hash, format_duration, generated_at_utc, rows_to_csv,
         rows_to_html, rows_to_table, serialize_query_response,
@@ -65,9 +65,12 @@ pub(crate) async fn print_query_outcome(outcome: QueryOutcome, render: &RenderOp
                 Some(desc) => format!("{desc}\n{table}"),
                 None => table,
             };
-            let body = match outcome.latest_block {
-                Some(latest_block) => format!("{body}\nlatest_block: {latest_block}"),
-                None => body,
+            let body = match (outcome.latest_block, outcome.latest_block_tag) {
+                (Some(latest_block), Some(tag)) if tag != BlockTag::Latest => {
+                    format!("{body}\nlatest_block: {latest_block} ({tag})")
+                }
+                (Some(latest_block), _) => format!("{body}\nlatest_block: {latest_block}"),
+                (None, _) => body,
             };
             let body = format!("{body}\ngenerated_at: {}", generated_at_utc());
             (body, "text/plain", "txt")
@@ -80,6 +83,7 @@ pub(crate) async fn print_query_outcome(outcome: QueryOutcome, render: &RenderOp
                 chain_id: outcome.chain.chain_id,
                 blocks: outcome.query.blocks.as_deref(),
                 latest_block: outcome.latest_block,
+                latest_block_tag: outcome.latest_block_tag,
                 sql: outcome.query.sql.as_deref(),
                 description: desc,
                 row_count: outcome.rows.len(),
@@ -102,6 +106,7 @@ pub(crate) async fn print_query_outcome(outcome: QueryOutcome, render: &RenderOp
                 outcome.cached_blocks,
                 outcome.new_blocks,
                 outcome.latest_block,
+                outcome.latest_block_tag,
                 outcome.query,
                 render.desc.clone(),
             )?;
@@ -189,13 +194,17 @@ pub(crate) mod block_txs;
 pub(crate) mod chain_info;
 pub(crate) mod chains;
 pub(crate) mod coinbase_transfer;
+pub(crate) mod contract;
 pub(crate) mod db_info;
 pub(crate) mod debug_available;
 pub(crate) mod ens_lookup;
 pub(crate) mod ens_resolve;
 pub(crate) mod evm_traces;
+pub(crate) mod export;
+pub(crate) mod import_parquet;
 pub(crate) mod index;
 pub(crate) mod purge_db;
+pub(crate) mod queries;
 pub(crate) mod query;
 pub(crate) mod reindex;
 pub(crate) mod state_diff;
@@ -0,0 +1,21 @@
+use alloy::primitives::Address;
+use eyre::Result;
+use mevlog::{cmds, misc::shared_init::ConnOpts};
+
+use crate::cmd::{RenderOpts, print_query_outcome};
+
+#[derive(Debug, clap::Parser)]
+pub struct ContractArgs {
+    #[arg(help = "Contract address to look up")]
+    pub address: Address,
+
+    #[command(flatten)]
+    pub conn_opts: ConnOpts,
+}
+
+impl ContractArgs {
+    pub(crate) async fn run(&self, render: &RenderOpts) -> Result<()> {
+        let outcome = cmds::contract::contract(self.address, &self.conn_opts).await?;
+        print_query_outcome(outcome, render).await
+    }
+}
@@ -0,0 +1,122 @@
+use std::{path::PathBuf, time::Instant};
+
+use eyre::{Result, bail};
+use mevlog::{
+    db::txs::{
+        self,
+        export::{ExportFormat, ExportOpts, export_range},
+        info::db_info,
+    },
+    misc::shared_init::OutputFormat,
+    models::json::export_response::{ExportResponse, serialize_export_response},
+};
+
+#[derive(Debug, clap::Parser)]
+pub struct ExportArgs {
+    #[arg(long, help = "Chain ID of the local transactions DB to export")]
+    chain_id: u64,
+
+    #[arg(
+        long,
+        help = "Directory to write into; each table gets a subdirectory of '<table>__A_to_B.<ext>' files"
+    )]
+    out_dir: PathBuf,
+
+    #[arg(long, help = "First block to export (default: lowest indexed block)")]
+    from: Option<u64>,
+
+    #[arg(long, help = "Last block to export (default: highest indexed block)")]
+    to: Option<u64>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Output file format (default: parquet)",
+        default_value = "parquet"
+    )]
+    file_format: ExportFormat,
+
+    #[arg(
+        long,
+        help = "Blocks per output file; partitions are aligned to multiples of this size (default: 10000)",
+        default_value = "10000"
+    )]
+    partition_blocks: std::num::NonZeroU64,
+
+    #[arg(
+        long,
+        help = "Write u256 columns (value, coinbase_transfer, erc20_amount, uint256 custom columns) as decimal strings instead of 32-byte binary"
+    )]
+    u256_decimal: bool,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Comma-separated tables to export (default: every built-in table and every tracked custom table)"
+    )]
+    tables: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Override the directory holding the per-chain transactions SQLite DB (mainly for tests); filename stays mevlog-txs-v{N}-{chain_id}.db"
+    )]
+    txs_db_dir: Option<String>,
+}
+
+impl ExportArgs {
+    pub(crate) async fn run(&self, format: OutputFormat) -> Result<()> {
+        if matches!(
+            format,
+            OutputFormat::Csv | OutputFormat::Table | OutputFormat::Html
+        ) {
+            bail!("'csv', 'table' and 'html' formats are only supported by the query command");
+        }
+
+        let db_path = txs::resolve_db_path(self.txs_db_dir.as_deref(), self.chain_id);
+        if !db_path.exists() {
+            bail!("Txs DB not found at {}", db_path.display());
+        }
+
+        let conn = txs::conn(
+            Some(db_path.to_string_lossy().into_owned()),
+            self.chain_id,
+            true,
+        )
+        .await?;
+
+        let stats = db_info(&conn).await?;
+        let (Some(from), Some(to)) = (self.from.or(stats.min_block), self.to.or(stats.max_block))
+        else {
+            bail!("Txs DB has no indexed blocks to export");
+        };
+
+        let opts = ExportOpts {
+            out_dir: self.out_dir.clone(),
+            from,
+            to,
+            partition_blocks: self.partition_blocks.get(),
+            format: self.file_format,
+            u256_as_decimal: self.u256_decimal,
+            tables: self.tables.clone(),
+        };
+
+        let start_time = Instant::now();
+        let tables = export_range(&opts, &conn).await?;
+        let duration_ns = start_time.elapsed().as_nanos() as u64;
+
+        let resp = ExportResponse::new(
+            self.out_dir.display().to_string(),
+            self.file_format,
+            self.chain_id,
+            from,
+            to,
+            tables,
+            duration_ns,
+        );
+
+        let pretty = !matches!(format, OutputFormat::Json);
+        println!("{}", serialize_export_response(&resp, pretty)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use std::{path::PathBuf, time::Instant};
+
+use eyre::{Result, bail};
+use mevlog::{
+    ChainInfoNoRpcsJson,
+    db::txs::import::import_parquet_dir,
+    misc::{
+        config::Config,
+        shared_init::{ConnOpts, OutputFormat, init_deps},
+    },
+    models::json::import_response::{ImportResponse, serialize_import_response},
+};
+
+#[derive(Debug, clap::Parser)]
+pub struct ImportParquetArgs {
+    #[arg(
+        long,
+        help = "Directory holding cryo parquet files named '<chain>__{transactions,logs,blocks}__A_to_B.parquet'"
+    )]
+    dir: PathBuf,
+
+    #[command(flatten)]
+    conn_opts: ConnOpts,
+
+    #[arg(
+        long,
+        help = "Number of blocks parsed and written per batch (default: 1000)",
+        default_value = "1000"
+    )]
+    batch_size: std::num::NonZeroUsize,
+
+    #[arg(
+        long,
+        help = "Don't store tx calldata in the transactions.input column (saves disk). Same as `store_input = false` in the config"
+    )]
+    skip_input: bool,
+}
+
+impl ImportParquetArgs {
+    pub(crate) async fn run(&self, format: OutputFormat) -> Result<()> {
+        if matches!(
+            format,
+            OutputFormat::Csv | OutputFormat::Table | OutputFormat::Html
+        ) {
+            bail!("'csv', 'table' and 'html' formats are only supported by the query command");
+        }
+
+        let store_input = !self.skip_input && Config::load()?.store_input();
+        let deps = init_deps(&self.conn_opts).await?;
+
+        let start_time = Instant::now();
+        let stats =
+            import_parquet_dir(&self.dir, self.batch_size.get(), store_input, &deps).await?;
+        let duration_ns = start_time.elapsed().as_nanos() as u64;
+
+        let chain = ChainInfoNoRpcsJson::from_evm_chain(&deps.chain);
+        let resp = ImportResponse::new(self.dir.display().to_string(), stats, duration_ns, chain);
+
+        let pretty = !matches!(format, OutputFormat::Json);
+        println!("{}", serialize_import_response(&resp, pretty)?);
+
+        Ok(())
+    }
+}
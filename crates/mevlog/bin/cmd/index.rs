@@ -1,12 +1,17 @@
 use std::time::{Duration, Instant};
 
-use alloy::providers::Provider;
+use alloy::primitives::{Address, B256};
 use eyre::{Result, bail};
 use mevlog::{
     ChainInfoNoRpcsJson,
-    db::txs::{indexing::index_block_range, purge::purge_old_blocks},
+    db::txs::{
+        indexing::{index_block_range, index_block_selection},
+        logs_indexing::{LogFilter, index_logs_only},
+        purge::purge_old_blocks,
+        reorg::handle_reorgs,
+    },
     misc::{
-        args_parsing::BlocksRange,
+        args_parsing::{BlockSelection, BlockTag, get_head_block},
         shared_init::{ConnOpts, CryoOpts, OutputFormat, init_deps},
     },
     models::json::index_response::{IndexResponse, serialize_index_response},
@@ -18,7 +23,7 @@ pub struct IndexArgs {
     #[arg(
         short = 'b',
         long,
-        help = "Block number or range to index (e.g., '22030899', 'latest', '22030800:22030900', '50:latest', '50:'). Required unless --live is set"
+        help = "Block number or range to index (e.g., '22030899', 'latest', '22030800:22030900', '50:latest', '50:', 'finalized', '50:safe', '1h:latest', '2025-01-01:2025-01-02', '100,200:210,latest'). Required unless --live is set"
     )]
     blocks: Option<String>,
 
@@ -59,6 +64,60 @@ pub struct IndexArgs {
         help = "With --live: after each indexing round, delete data older than this many blocks behind the newest indexed block"
     )]
     keep: Option<u64>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "With --live: chain head to follow, 'safe' or 'finalized' to index only blocks that won't reorg (default: latest)"
+    )]
+    head: Option<BlockTag>,
+
+    #[arg(
+        long,
+        help = "With --live: stay N blocks behind the followed head, only indexing blocks with at least N confirmations"
+    )]
+    confirmations: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Index only the logs matching --address/--topic0..--topic3 (found with eth_getLogs), the txs that emitted them and their blocks' headers. Suited to ranges too large to index block by block; requires --address or --topic0"
+    )]
+    logs_only: bool,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "With --logs-only: emitting contract to match. Repeat or comma-separate to match any of several"
+    )]
+    address: Vec<Address>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "With --logs-only: event signature hash to match. Repeat or comma-separate to match any of several"
+    )]
+    topic0: Vec<B256>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "With --logs-only: first indexed event argument to match, as a 32-byte word"
+    )]
+    topic1: Vec<B256>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "With --logs-only: second indexed event argument to match, as a 32-byte word"
+    )]
+    topic2: Vec<B256>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "With --logs-only: third indexed event argument to match, as a 32-byte word"
+    )]
+    topic3: Vec<B256>,
 }
 
 impl IndexArgs {
@@ -71,10 +130,35 @@ impl IndexArgs {
             bail!("--keep requires --live; use the purge-db command for one-off pruning");
         }
 
+        if (self.head.is_some() || self.confirmations.is_some()) && !self.live {
+            bail!(
+                "--head and --confirmations require --live; use a 'safe' or 'finalized' --blocks range instead"
+            );
+        }
+
         if self.keep == Some(0) {
             bail!("--keep must be at least 1; use 'purge-db --keep 0' to wipe the DB");
         }
 
+        if self.logs_only && self.live {
+            bail!("--logs-only can't be combined with --live");
+        }
+
+        let topics = [
+            self.topic0.clone(),
+            self.topic1.clone(),
+            self.topic2.clone(),
+            self.topic3.clone(),
+        ];
+        let log_filter = if self.logs_only {
+            Some(LogFilter::new(self.address.clone(), topics)?)
+        } else {
+            if !self.address.is_empty() || topics.iter().any(|topic| !topic.is_empty()) {
+                bail!("--address and --topic0..--topic3 require --logs-only");
+            }
+            None
+        };
+
         if matches!(
             format,
             OutputFormat::Csv | OutputFormat::Table | OutputFormat::Html
@@ -87,11 +171,12 @@ impl IndexArgs {
         // Backfill the requested range (in both normal and live mode).
         let backfilled_to = match &self.blocks {
             Some(blocks) => {
-                let range =
-                    BlocksRange::from_str(blocks, &deps.provider, self.latest_offset).await?;
+                let selection =
+                    BlockSelection::from_str(blocks, &deps.provider, &deps.txs, self.latest_offset)
+                        .await?;
 
                 if let Some(max_range) = self.max_range {
-                    let range_size = range.size();
+                    let range_size = selection.size();
                     if range_size > max_range {
                         bail!(
                             "Block range size {} exceeds maximum allowed range of {}",
@@ -101,10 +186,32 @@ impl IndexArgs {
                     }
                 }
 
+                if let Some(log_filter) = &log_filter {
+                    let start_time = Instant::now();
+                    let indexed =
+                        index_logs_only(&selection, log_filter, &deps, &self.cryo_opts).await?;
+                    let duration_ns = start_time.elapsed().as_nanos() as u64;
+
+                    let chain = ChainInfoNoRpcsJson::from_evm_chain(&deps.chain);
+                    let mut resp = IndexResponse::new(
+                        selection.to_string(),
+                        selection.from(),
+                        selection.to(),
+                        indexed.cached_blocks,
+                        indexed.new_blocks,
+                        duration_ns,
+                        chain,
+                        vec![],
+                    );
+                    resp.logs_only = Some(indexed.matches);
+                    let pretty = !matches!(format, OutputFormat::Json);
+                    println!("{}", serialize_index_response(&resp, pretty)?);
+                    return Ok(());
+                }
+
                 let start_time = Instant::now();
-                let (cached_blocks, new_blocks) = index_block_range(
-                    range.from,
-                    range.to,
+                let indexed = index_block_selection(
+                    &selection,
                     self.batch_size.get(),
                     &deps,
                     &self.cryo_opts,
@@ -114,25 +221,26 @@ impl IndexArgs {
 
                 if self.live {
                     info!(
-                        "Backfilled blocks {}..={} ({} new, {} cached)",
-                        range.from, range.to, new_blocks, cached_blocks
+                        "Backfilled blocks {} ({} new, {} cached)",
+                        selection, indexed.new_blocks, indexed.cached_blocks
                     );
                 } else {
                     let chain = ChainInfoNoRpcsJson::from_evm_chain(&deps.chain);
                     let resp = IndexResponse::new(
-                        blocks.clone(),
-                        range.from,
-                        range.to,
-                        cached_blocks,
-                        new_blocks,
+                        selection.to_string(),
+                        selection.from(),
+                        selection.to(),
+                        indexed.cached_blocks,
+                        indexed.new_blocks,
                         duration_ns,
                         chain,
+                        indexed.endpoints,
                     );
                     let pretty = !matches!(format, OutputFormat::Json);
                     println!("{}", serialize_index_response(&resp, pretty)?);
                 }
 
-                Some(range.to)
+                Some(selection.to())
             }
             None => None,
         };
@@ -141,12 +249,17 @@ impl IndexArgs {
             return Ok(());
         }
 
-        // Live mode: poll for new blocks and index them as they arrive.
+        // Live mode: poll for new blocks and index them as they arrive. The
+        // followed head is `--head` minus `--confirmations`, so with either set
+        // the indexer trails the chain tip and never sees blocks that can
+        // still reorg.
+        let head = self.head.unwrap_or_default();
+
         let mut last_indexed = match backfilled_to {
             Some(to) => to,
             None => {
-                // No backfill range given: start from the current latest block.
-                let latest = deps.provider.get_block_number().await?;
+                // No backfill range given: start from the current head block.
+                let latest = get_head_block(&deps.provider, head, self.confirmations).await?;
                 let (cached_blocks, new_blocks) = index_block_range(
                     latest,
                     latest,
@@ -156,8 +269,8 @@ impl IndexArgs {
                 )
                 .await?;
                 info!(
-                    "Indexed latest block {} ({} new, {} cached)",
-                    latest, new_blocks, cached_blocks
+                    "Indexed {} block {} ({} new, {} cached)",
+                    head, latest, new_blocks, cached_blocks
                 );
                 latest
             }
@@ -170,13 +283,15 @@ impl IndexArgs {
         }
 
         info!(
-            "Watching for new blocks (poll every {}ms)",
+            "Watching for new {} blocks with {} confirmations (poll every {}ms)",
+            head,
+            self.confirmations.unwrap_or_default(),
             self.poll_interval_ms
         );
         let poll = Duration::from_millis(self.poll_interval_ms);
 
         loop {
-            let latest = deps.provider.get_block_number().await?;
+            let latest = get_head_block(&deps.provider, head, self.confirmations).await?;
             if latest > last_indexed {
                 let from = last_indexed + 1;
                 let start_time = Instant::now();
@@ -191,6 +306,7 @@ impl IndexArgs {
                     cached_blocks,
                     start_time.elapsed()
                 );
+                handle_reorgs(from, latest, self.batch_size.get(), &deps, &self.cryo_opts).await?;
                 last_indexed = latest;
 
                 if let Some(keep) = self.keep {
@@ -206,11 +322,12 @@ async fn purge_and_log(keep: u64, conn: &sqlx::SqlitePool) -> Result<()> {
     let stats = purge_old_blocks(keep, false, conn).await?;
     if stats.purged_blocks > 0 {
         info!(
-            "Purged {} blocks below {} ({} txs, {} logs)",
+            "Purged {} blocks below {} ({} txs, {} logs, {} input bytes)",
             stats.purged_blocks,
             stats.cutoff_block.unwrap_or_default(),
             stats.purged_transactions,
-            stats.purged_logs
+            stats.purged_logs,
+            stats.purged_input_bytes
         );
     }
     Ok(())
@@ -0,0 +1,38 @@
+use eyre::{Result, bail};
+use mevlog::misc::{saved_queries::SavedQuery, shared_init::OutputFormat};
+
+#[derive(Debug, clap::Parser)]
+pub struct QueriesArgs {
+    #[command(subcommand)]
+    cmd: QueriesSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum QueriesSubcommand {
+    #[command(about = "List the saved queries in ~/.mevlog/queries/")]
+    List,
+}
+
+impl QueriesArgs {
+    pub(crate) async fn run(&self, format: OutputFormat) -> Result<()> {
+        if matches!(
+            format,
+            OutputFormat::Csv | OutputFormat::Table | OutputFormat::Html
+        ) {
+            bail!("'csv', 'table' and 'html' formats are only supported by the query command");
+        }
+
+        match self.cmd {
+            QueriesSubcommand::List => {
+                let queries = SavedQuery::list()?;
+                if matches!(format, OutputFormat::Json) {
+                    println!("{}", serde_json::to_string(&queries)?);
+                } else {
+                    println!("{}", serde_json::to_string_pretty(&queries)?);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,14 +1,19 @@
 use eyre::Result;
 use mevlog::{
-    cmds,
-    misc::shared_init::{ConnOpts, CryoOpts, SharedOpts},
+    cmds::{self, query::QueryOpts},
+    db::txs::raw_query::SqlParam,
+    misc::{
+        args_parsing::BlockTag,
+        saved_queries::SavedQuery,
+        shared_init::{ConnOpts, CryoOpts, SharedOpts},
+    },
 };
 
 use crate::cmd::{RenderOpts, print_query_outcome};
 
 #[derive(Debug, clap::Parser)]
 pub struct QueryArgs {
-    #[arg(short = 'b', long, help_heading = "Block number or range to collect (e.g., '22030899', 'latest', '22030800:22030900' '50:latest', '50:'", num_args(1..), required_unless_present = "skip_index", conflicts_with = "skip_index")]
+    #[arg(short = 'b', long, help_heading = "Block number or range to collect (e.g., '22030899', 'latest', '22030800:22030900' '50:latest', '50:', 'safe', '50:finalized', '1h:latest', '2025-01-01T00:00Z:2025-01-02T00:00Z', '100,200:210,latest'", num_args(1..), required_unless_present_any = ["skip_index", "saved"], conflicts_with = "skip_index")]
     blocks: Option<String>,
 
     #[command(flatten)]
@@ -23,6 +28,14 @@ pub struct QueryArgs {
     #[arg(long, help = "Get N-offset latest block")]
     latest_offset: Option<u64>,
 
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = BlockTag::Latest,
+        help = "Chain head {LATEST_BLOCK()} and the reported latest_block resolve to"
+    )]
+    head: BlockTag,
+
     #[arg(
         long,
         help = "Latest block number used to expand the {LATEST_BLOCK()} SQL macro, \
@@ -54,6 +67,25 @@ pub struct QueryArgs {
     )]
     skip_index: bool,
 
+    #[arg(
+        long,
+        requires = "evm_trace",
+        conflicts_with = "skip_index",
+        help = "Also trace the block range into the balance_changes table (net ERC20 and \
+                native ETH deltas per tx and account). Requires --evm-trace"
+    )]
+    balance_changes: bool,
+
+    #[arg(
+        long,
+        requires = "evm_trace",
+        conflicts_with = "skip_index",
+        help = "Also store every internal call frame of the block range in the traces \
+                table, and internally deployed contracts in the contracts table. Requires \
+                --evm-trace"
+    )]
+    traces: bool,
+
     #[arg(
         long,
         help = "Abort query execution (RPC, indexing and SQL) after this many \
@@ -63,37 +95,104 @@ pub struct QueryArgs {
 
     #[arg(
         long,
+        required_unless_present = "saved",
+        conflicts_with = "saved",
         help = "Read-only SQL to run against the local txs DB \
-                (tables: transactions, logs, blocks). Blob columns (addresses, \
-                hashes) are output as 0x-hex; addresses/hashes in predicates must \
-                be given as blob literals, e.g. WHERE from_address = X'1111...1111'. \
+                (tables: transactions, logs, blocks, access_list_entries, authorizations, \
+                withdrawals, balance_changes, traces, contracts, nft_transfers, tokens). Blob \
+                columns (addresses, hashes) are output as 0x-hex; addresses/hashes in predicates must be given as blob literals, e.g. \
+                WHERE from_address = X'1111...1111', or bound with --param, e.g. \
+                WHERE from_address = :from with --param from=0x1111...1111. \
                 Macros must be wrapped in braces. {LATEST_BLOCK()} expands to the chain's \
-                current latest block number (fetched via RPC), e.g. WHERE block_number > \
-                {LATEST_BLOCK()} - 100. {NATIVE_TOKEN_PRICE()} expands to the native token's \
+                current --head block number (fetched via RPC), e.g. WHERE block_number > \
+                {LATEST_BLOCK()} - 100. {BLOCK_AT(\"2025-01-01T00:00Z\")} expands to the first \
+                block at or after that time. {NATIVE_TOKEN_PRICE()} expands to the native token's \
                 USD price (from --native-token-price or a Chainlink oracle). \
                 {RESOLVE_ENS(\"name.eth\")} expands to the resolved address as a blob literal \
                 (Ethereum mainnet only), e.g. WHERE from_address = {RESOLVE_ENS(\"vitalik.eth\")}"
     )]
-    sql: String,
+    sql: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Run the saved query ~/.mevlog/queries/NAME.sql instead of --sql. Its header \
+                supplies the description and, unless --blocks or --skip-index is given, the \
+                block range; --param overrides its declared param defaults"
+    )]
+    saved: Option<String>,
+
+    #[arg(
+        long = "param",
+        value_name = "NAME=VALUE",
+        value_parser = parse_param,
+        help = "Bind VALUE to the :NAME placeholder of the --sql or --saved query (repeatable). \
+                0x-prefixed 20-byte addresses and 32-byte hashes bind as blobs, plain \
+                integers as integers, anything else as text"
+    )]
+    params: Vec<SqlParam>,
+
+    #[arg(
+        long,
+        value_name = "BUDGET",
+        help = "Enable the eth_call(to, calldata, block) and eth_balance(address, block) SQL \
+                functions, allowing the query at most BUDGET RPC requests. Results at or below \
+                the finalized head are cached on disk, so repeated calls cost nothing"
+    )]
+    rpc_calls: Option<u64>,
+}
+
+fn parse_param(input: &str) -> Result<SqlParam, String> {
+    input.parse().map_err(|e: eyre::Report| e.to_string())
 }
 
 impl QueryArgs {
     pub(crate) async fn run(&self, render: &RenderOpts) -> Result<()> {
+        let (sql, params, blocks, render) = match &self.saved {
+            Some(name) => {
+                let saved = SavedQuery::load(name)?;
+                let params = saved.bind(&self.params)?;
+                let blocks = match (&self.blocks, self.skip_index) {
+                    (Some(blocks), _) => Some(blocks.clone()),
+                    (None, true) => None,
+                    (None, false) => saved.blocks.clone(),
+                };
+                let mut render = render.clone();
+                render.desc = render.desc.or(saved.description);
+                (saved.sql, params, blocks, render)
+            }
+            None => (
+                self.sql.clone().unwrap_or_default(),
+                self.params.clone(),
+                self.blocks.clone(),
+                render.clone(),
+            ),
+        };
+
+        let opts = QueryOpts {
+            blocks: blocks.as_deref(),
+            latest_offset: self.latest_offset,
+            head: self.head,
+            max_range: self.max_range,
+            max_rows: self.max_rows,
+            batch_size: self.batch_size.get(),
+            skip_index: self.skip_index,
+            balance_changes: self.balance_changes,
+            traces: self.traces,
+            latest_block: self.latest_block,
+            rpc_calls: self.rpc_calls,
+            rpc_call_cache: true,
+            timeout_ms: self.timeout_ms,
+        };
         let outcome = cmds::query::query(
-            self.blocks.as_deref(),
-            self.latest_offset,
-            self.max_range,
-            self.max_rows,
-            self.batch_size.get(),
-            self.skip_index,
-            self.latest_block,
-            &self.sql,
+            &sql,
+            &params,
+            opts,
             &self.shared_opts,
             &self.conn_opts,
             &self.cryo_opts,
-            self.timeout_ms,
         )
         .await?;
-        print_query_outcome(outcome, render).await
+        print_query_outcome(outcome, &render).await
     }
 }
@@ -3,8 +3,11 @@ use std::time::Instant;
 use eyre::{Result, bail};
 use mevlog::{
     ChainInfoNoRpcsJson,
-    db::txs::{indexing::index_block_range, info::db_info},
-    misc::shared_init::{ConnOpts, CryoOpts, OutputFormat, init_deps},
+    db::txs::{indexing::index_block_selection, info::db_info},
+    misc::{
+        args_parsing::{BlockSelection, BlocksRange},
+        shared_init::{ConnOpts, CryoOpts, OutputFormat, init_deps},
+    },
     models::json::index_response::{IndexResponse, serialize_index_response},
 };
 
@@ -41,7 +44,7 @@ impl ReindexArgs {
 
         let deps = init_deps(&self.conn_opts).await?;
 
-        // Re-run indexing over the stored range; `index_block_range` only fetches
+        // Re-run indexing over the stored range; `index_block_selection` only fetches
         // blocks absent from the DB, so this backfills the gaps. A contiguous
         // range is a no-op (`new_blocks = 0`), keeping it safe to run on a schedule.
         let stats = db_info(&deps.txs).await?;
@@ -59,8 +62,10 @@ impl ReindexArgs {
         };
 
         let start_time = Instant::now();
-        let (cached_blocks, new_blocks) =
-            index_block_range(from, to, self.batch_size.get(), &deps, &self.cryo_opts).await?;
+        let selection = BlockSelection::from(BlocksRange { from, to });
+        let indexed =
+            index_block_selection(&selection, self.batch_size.get(), &deps, &self.cryo_opts)
+                .await?;
         let duration_ns = start_time.elapsed().as_nanos() as u64;
 
         let chain = ChainInfoNoRpcsJson::from_evm_chain(&deps.chain);
@@ -68,10 +73,11 @@ impl ReindexArgs {
             format!("{from}:{to}"),
             from,
             to,
-            cached_blocks,
-            new_blocks,
+            indexed.cached_blocks,
+            indexed.new_blocks,
             duration_ns,
             chain,
+            indexed.endpoints,
         );
         let pretty = !matches!(format, OutputFormat::Json);
         println!("{}", serialize_index_response(&resp, pretty)?);
@@ -9,9 +9,10 @@ use cmd::tui::TuiArgs;
 use cmd::{
     affected_addresses::AffectedAddressesArgs, block::BlockArgs, block_logs::BlockLogsArgs,
     block_txs::BlockTxsArgs, chain_info::ChainInfoArgs, chains::ChainsArgs,
-    coinbase_transfer::CoinbaseTransferArgs, db_info::DbInfoArgs,
+    coinbase_transfer::CoinbaseTransferArgs, contract::ContractArgs, db_info::DbInfoArgs,
     debug_available::DebugAvailableArgs, ens_lookup::EnsLookupArgs, ens_resolve::EnsResolveArgs,
-    evm_traces::EvmTracesArgs, index::IndexArgs, purge_db::PurgeDBArgs, query::QueryArgs,
+    evm_traces::EvmTracesArgs, export::ExportArgs, import_parquet::ImportParquetArgs,
+    index::IndexArgs, purge_db::PurgeDBArgs, queries::QueriesArgs, query::QueryArgs,
     reindex::ReindexArgs, state_diff::StateDiffArgs, tx::TxArgs, tx_logs::TxLogsArgs,
     update_custom_tables::UpdateCustomTablesArgs, update_sigs_db::UpdateSigsDBArgs,
 };
@@ -95,16 +96,25 @@ pub enum MLSubcommand {
         alias = "q"
     )]
     Query(Box<QueryArgs>),
+    #[command(about = "Manage the saved query library in ~/.mevlog/queries/")]
+    Queries(QueriesArgs),
     #[command(about = "Index a block range into the local txs DB")]
     Index(IndexArgs),
     #[command(about = "Refetch missing blocks within the local txs DB's indexed range")]
     Reindex(ReindexArgs),
+    #[command(
+        name = "import-parquet",
+        about = "Bulk-load a directory of cryo parquet files into the local txs DB"
+    )]
+    ImportParquet(ImportParquetArgs),
     #[command(
         about = "Remove indexed data below a block window ending at the newest indexed block"
     )]
     PurgeDB(PurgeDBArgs),
     #[command(name = "db-info", about = "Show local txs DB stats")]
     DbInfo(DbInfoArgs),
+    #[command(about = "Export the local txs DB to block-partitioned parquet or CSV files")]
+    Export(ExportArgs),
     #[command(about = "Show a single transaction")]
     Tx(TxArgs),
     #[command(name = "tx-logs", about = "Show a transaction's logs")]
@@ -115,6 +125,8 @@ pub enum MLSubcommand {
     BlockTxs(BlockTxsArgs),
     #[command(name = "block-logs", about = "Show all logs in a block")]
     BlockLogs(BlockLogsArgs),
+    #[command(about = "Show where and when a contract was deployed")]
+    Contract(ContractArgs),
     #[command(name = "update-sigs-db", about = "Update the signatures database")]
     UpdateSigsDB(UpdateSigsDBArgs),
     #[command(
@@ -228,18 +240,27 @@ async fn execute(root_args: MLArgs) -> Result<()> {
         ML::Query(args) => {
             args.run(&render).await?;
         }
+        ML::Queries(args) => {
+            args.run(root_args.format).await?;
+        }
         ML::Index(args) => {
             args.run(root_args.format).await?;
         }
         ML::Reindex(args) => {
             args.run(root_args.format).await?;
         }
+        ML::ImportParquet(args) => {
+            args.run(root_args.format).await?;
+        }
         ML::PurgeDB(args) => {
             args.run(root_args.format).await?;
         }
         ML::DbInfo(args) => {
             args.run(root_args.format).await?;
         }
+        ML::Export(args) => {
+            args.run(root_args.format).await?;
+        }
         ML::Tx(args) => {
             args.run(&render).await?;
         }
@@ -255,6 +276,9 @@ async fn execute(root_args: MLArgs) -> Result<()> {
         ML::BlockLogs(args) => {
             args.run(&render).await?;
         }
+        ML::Contract(args) => {
+            args.run(&render).await?;
+        }
         ML::UpdateSigsDB(args) => {
             args.run().await?;
         }
@@ -0,0 +1,6 @@
+ALTER TABLE blocks DROP COLUMN excess_blob_gas;
+ALTER TABLE blocks DROP COLUMN blob_gas_used;
+
+ALTER TABLE transactions DROP COLUMN max_fee_per_blob_gas;
+ALTER TABLE transactions DROP COLUMN blob_gas_price;
+ALTER TABLE transactions DROP COLUMN blob_gas_used;
@@ -0,0 +1,6 @@
+ALTER TABLE transactions ADD COLUMN blob_gas_used BIGINT;
+ALTER TABLE transactions ADD COLUMN blob_gas_price BIGINT;
+ALTER TABLE transactions ADD COLUMN max_fee_per_blob_gas BIGINT;
+
+ALTER TABLE blocks ADD COLUMN blob_gas_used BIGINT;
+ALTER TABLE blocks ADD COLUMN excess_blob_gas BIGINT;
@@ -0,0 +1 @@
+ALTER TABLE blocks ADD COLUMN parent_hash BLOB;
@@ -0,0 +1,2 @@
+DROP TABLE authorizations;
+DROP TABLE access_list_entries;
@@ -0,0 +1,24 @@
+CREATE TABLE access_list_entries (
+    block_number BIGINT NOT NULL,
+    tx_index BIGINT NOT NULL,
+    address BLOB NOT NULL,
+    storage_key BLOB
+);
+
+CREATE UNIQUE INDEX idx_access_list_entries_unique
+    ON access_list_entries (block_number, tx_index, address, IFNULL(storage_key, X''));
+CREATE INDEX idx_access_list_entries_address ON access_list_entries (address);
+
+CREATE TABLE authorizations (
+    block_number BIGINT NOT NULL,
+    tx_index BIGINT NOT NULL,
+    chain_id BIGINT NOT NULL,
+    address BLOB NOT NULL,
+    nonce BIGINT NOT NULL,
+    authority BLOB
+);
+
+CREATE UNIQUE INDEX idx_authorizations_unique
+    ON authorizations (block_number, tx_index, chain_id, address, nonce, IFNULL(authority, X''));
+CREATE INDEX idx_authorizations_address ON authorizations (address);
+CREATE INDEX idx_authorizations_authority ON authorizations (authority);
@@ -0,0 +1,11 @@
+CREATE TABLE withdrawals (
+    block_number BIGINT NOT NULL,
+    withdrawal_index BIGINT NOT NULL,
+    validator_index BIGINT NOT NULL,
+    address BLOB NOT NULL,
+    amount_gwei BIGINT NOT NULL,
+    PRIMARY KEY (block_number, withdrawal_index)
+);
+
+CREATE INDEX idx_withdrawals_address ON withdrawals (address);
+CREATE INDEX idx_withdrawals_validator_index ON withdrawals (validator_index);
@@ -0,0 +1,8 @@
+ALTER TABLE blocks DROP COLUMN parent_beacon_block_root;
+ALTER TABLE blocks DROP COLUMN mix_hash;
+ALTER TABLE blocks DROP COLUMN difficulty;
+ALTER TABLE blocks DROP COLUMN transactions_root;
+ALTER TABLE blocks DROP COLUMN state_root;
+ALTER TABLE blocks DROP COLUMN size;
+ALTER TABLE blocks DROP COLUMN extra_data;
+ALTER TABLE blocks DROP COLUMN gas_limit;
@@ -0,0 +1,8 @@
+ALTER TABLE blocks ADD COLUMN gas_limit BIGINT;
+ALTER TABLE blocks ADD COLUMN extra_data BLOB;
+ALTER TABLE blocks ADD COLUMN size BIGINT;
+ALTER TABLE blocks ADD COLUMN state_root BLOB;
+ALTER TABLE blocks ADD COLUMN transactions_root BLOB;
+ALTER TABLE blocks ADD COLUMN difficulty BLOB;
+ALTER TABLE blocks ADD COLUMN mix_hash BLOB;
+ALTER TABLE blocks ADD COLUMN parent_beacon_block_root BLOB;
@@ -0,0 +1,2 @@
+ALTER TABLE blocks DROP COLUMN balance_changes_traced;
+DROP TABLE balance_changes;
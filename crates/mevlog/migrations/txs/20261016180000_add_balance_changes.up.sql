@@ -0,0 +1,14 @@
+CREATE TABLE balance_changes (
+    block_number BIGINT NOT NULL,
+    tx_index BIGINT NOT NULL,
+    address BLOB NOT NULL,
+    token BLOB,
+    delta BLOB NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_balance_changes_unique
+    ON balance_changes (block_number, tx_index, address, IFNULL(token, X''));
+CREATE INDEX idx_balance_changes_address ON balance_changes (address);
+CREATE INDEX idx_balance_changes_token ON balance_changes (token);
+
+ALTER TABLE blocks ADD COLUMN balance_changes_traced BOOLEAN NOT NULL DEFAULT 0;
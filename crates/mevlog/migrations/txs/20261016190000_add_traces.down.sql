@@ -0,0 +1,2 @@
+ALTER TABLE blocks DROP COLUMN traces_traced;
+DROP TABLE traces;
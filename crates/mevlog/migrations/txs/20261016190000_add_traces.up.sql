@@ -0,0 +1,21 @@
+CREATE TABLE traces (
+    block_number BIGINT NOT NULL,
+    tx_index BIGINT NOT NULL,
+    trace_address TEXT NOT NULL,
+    depth BIGINT NOT NULL,
+    call_type TEXT NOT NULL,
+    from_address BLOB NOT NULL,
+    to_address BLOB,
+    value BLOB,
+    gas_used BIGINT NOT NULL,
+    selector BLOB,
+    signature TEXT,
+    success BOOLEAN NOT NULL,
+    PRIMARY KEY (block_number, tx_index, trace_address)
+);
+
+CREATE INDEX idx_traces_from_address ON traces (from_address);
+CREATE INDEX idx_traces_to_address ON traces (to_address);
+CREATE INDEX idx_traces_selector ON traces (selector);
+
+ALTER TABLE blocks ADD COLUMN traces_traced BOOLEAN NOT NULL DEFAULT 0;
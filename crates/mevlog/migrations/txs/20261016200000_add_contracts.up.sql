@@ -0,0 +1,19 @@
+CREATE TABLE contracts (
+    address BLOB NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_index BIGINT NOT NULL,
+    deployer BLOB NOT NULL,
+    create_type TEXT NOT NULL,
+    code_hash BLOB,
+    PRIMARY KEY (address, block_number, tx_index)
+);
+
+CREATE INDEX idx_contracts_block_number ON contracts (block_number);
+CREATE INDEX idx_contracts_deployer ON contracts (deployer);
+
+-- Top-level creates are already in the transactions table.
+INSERT INTO contracts (address, block_number, tx_index, deployer, create_type)
+SELECT to_address, block_number, tx_index, from_address, 'CREATE'
+FROM transactions
+WHERE signature = 'CREATE()' AND signature_hash IS NULL AND success
+    AND to_address IS NOT NULL;
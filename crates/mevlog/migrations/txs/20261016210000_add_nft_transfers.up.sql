@@ -0,0 +1,17 @@
+CREATE TABLE nft_transfers (
+    block_number BIGINT NOT NULL,
+    tx_index BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    batch_index BIGINT NOT NULL,
+    contract BLOB NOT NULL,
+    standard TEXT NOT NULL,
+    from_address BLOB NOT NULL,
+    to_address BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    amount BLOB NOT NULL,
+    PRIMARY KEY (block_number, log_index, batch_index)
+);
+
+CREATE INDEX idx_nft_transfers_contract_token_id ON nft_transfers (contract, token_id);
+CREATE INDEX idx_nft_transfers_from_address ON nft_transfers (from_address);
+CREATE INDEX idx_nft_transfers_to_address ON nft_transfers (to_address);
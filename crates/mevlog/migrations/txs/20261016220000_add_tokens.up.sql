@@ -0,0 +1,6 @@
+CREATE TABLE tokens (
+    address BLOB PRIMARY KEY NOT NULL,
+    symbol TEXT,
+    name TEXT,
+    decimals INTEGER
+);
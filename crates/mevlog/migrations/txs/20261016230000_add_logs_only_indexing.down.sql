@@ -0,0 +1,2 @@
+DROP TABLE log_filter_coverage;
+ALTER TABLE blocks DROP COLUMN logs_only;
@@ -0,0 +1,9 @@
+ALTER TABLE blocks ADD COLUMN logs_only BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE TABLE log_filter_coverage (
+    filter TEXT NOT NULL,
+    from_block BIGINT NOT NULL,
+    to_block BIGINT NOT NULL
+);
+
+CREATE INDEX idx_log_filter_coverage_filter ON log_filter_coverage (filter, from_block);
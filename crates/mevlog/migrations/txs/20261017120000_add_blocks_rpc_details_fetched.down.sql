@@ -0,0 +1 @@
+ALTER TABLE blocks DROP COLUMN rpc_details_fetched;
@@ -0,0 +1 @@
+ALTER TABLE blocks ADD COLUMN rpc_details_fetched BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,53 @@
+INSERT OR IGNORE INTO nft_transfers (
+    block_number, tx_index, log_index, batch_index, contract, standard,
+    from_address, to_address, token_id, amount
+)
+SELECT block_number, tx_index, log_index, 0, address, 'ERC721',
+    substr(topic1, 13, 20), substr(topic2, 13, 20), topic3,
+    X'0000000000000000000000000000000000000000000000000000000000000001'
+FROM logs
+WHERE topic0 = X'ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef'
+    AND topic3 IS NOT NULL;
+
+INSERT OR IGNORE INTO nft_transfers (
+    block_number, tx_index, log_index, batch_index, contract, standard,
+    from_address, to_address, token_id, amount
+)
+SELECT block_number, tx_index, log_index, 0, address, 'ERC1155',
+    substr(topic2, 13, 20), substr(topic3, 13, 20),
+    substr(data, 1, 32), substr(data, 33, 32)
+FROM logs
+WHERE topic0 = X'c3d58168c5ae7397731d063d5bbf3d657854427343f4c083240f7aacaa2d0f62'
+    AND topic3 IS NOT NULL AND length(data) >= 64;
+
+WITH RECURSIVE batches AS (
+    SELECT block_number, tx_index, log_index, address, topic2, topic3, data,
+        (length(data) / 32 - 4) / 2 AS n
+    FROM logs
+    WHERE topic0 = X'4a39dc06d4c0dbc64b70af90fd698a233a518aa5d07e595d983b8c0526c8f393'
+        AND topic3 IS NOT NULL
+),
+canonical AS (
+    SELECT * FROM batches
+    WHERE n > 0 AND length(data) = 32 * (4 + 2 * n)
+        AND hex(substr(data, 1, 32)) = printf('%064X', 64)
+        AND hex(substr(data, 33, 32)) = printf('%064X', 96 + 32 * n)
+        AND hex(substr(data, 65, 32)) = printf('%064X', n)
+        AND hex(substr(data, 97 + 32 * n, 32)) = printf('%064X', n)
+),
+items (block_number, tx_index, log_index, address, topic2, topic3, data, n, i) AS (
+    SELECT block_number, tx_index, log_index, address, topic2, topic3, data, n, 0
+    FROM canonical
+    UNION ALL
+    SELECT block_number, tx_index, log_index, address, topic2, topic3, data, n, i + 1
+    FROM items
+    WHERE i + 1 < n
+)
+INSERT OR IGNORE INTO nft_transfers (
+    block_number, tx_index, log_index, batch_index, contract, standard,
+    from_address, to_address, token_id, amount
+)
+SELECT block_number, tx_index, log_index, i, address, 'ERC1155',
+    substr(topic2, 13, 20), substr(topic3, 13, 20),
+    substr(data, 97 + 32 * i, 32), substr(data, 129 + 32 * (n + i), 32)
+FROM items;
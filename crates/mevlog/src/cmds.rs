@@ -18,6 +18,7 @@ pub mod block_txs;
 pub mod chain_info;
 pub mod chains;
 pub mod coinbase_transfer;
+pub mod contract;
 pub mod debug_available;
 pub mod ens_lookup;
 pub mod ens_resolve;
@@ -9,7 +9,7 @@ use crate::{
         raw_query::run_raw_query_async,
     },
     misc::{
-        args_parsing::{BlocksRange, get_latest_block},
+        args_parsing::{BlockTag, BlocksRange, get_latest_block},
         shared_init::{ConnOpts, CryoOpts, init_deps},
     },
     models::json::query_response::{QueryOutcome, QueryParams},
@@ -24,7 +24,7 @@ pub async fn block(
 ) -> Result<QueryOutcome> {
     let deps = init_deps(conn_opts).await?;
 
-    let range = BlocksRange::from_str(block, &deps.provider, latest_offset).await?;
+    let range = BlocksRange::from_str(block, &deps.provider, &deps.txs, latest_offset).await?;
     if range.from != range.to {
         bail!("block expects a single block number or 'latest', not a range");
     }
@@ -52,6 +52,8 @@ pub async fn block(
         None,
         None,
         deps.custom_table_names(),
+        vec![],
+        None,
     )
     .await?;
     if result.rows.is_empty() {
@@ -64,12 +66,14 @@ pub async fn block(
         cached_blocks,
         new_blocks,
         latest_block,
+        latest_block_tag: Some(BlockTag::Latest),
         duration_ns,
         chain: chain_info,
         query: QueryParams {
             blocks: Some(block_number.to_string()),
             sql: Some(sql),
             evm_trace: None,
+            params: vec![],
         },
     })
 }
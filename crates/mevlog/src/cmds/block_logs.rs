@@ -9,7 +9,7 @@ use crate::{
         raw_query::run_raw_query_async,
     },
     misc::{
-        args_parsing::{BlocksRange, get_latest_block},
+        args_parsing::{BlockTag, BlocksRange, get_latest_block},
         shared_init::{ConnOpts, CryoOpts, init_deps},
     },
     models::json::{
@@ -27,7 +27,7 @@ pub async fn block_logs(
 ) -> Result<QueryOutcome> {
     let deps = init_deps(conn_opts).await?;
 
-    let range = BlocksRange::from_str(block, &deps.provider, latest_offset).await?;
+    let range = BlocksRange::from_str(block, &deps.provider, &deps.txs, latest_offset).await?;
     if range.from != range.to {
         bail!("block-logs expects a single block number or 'latest', not a range");
     }
@@ -56,6 +56,8 @@ pub async fn block_logs(
         None,
         None,
         deps.custom_table_names(),
+        vec![],
+        None,
     )
     .await?;
 
@@ -65,12 +67,14 @@ pub async fn block_logs(
         cached_blocks,
         new_blocks,
         latest_block,
+        latest_block_tag: Some(BlockTag::Latest),
         duration_ns,
         chain: chain_info,
         query: QueryParams {
             blocks: Some(block_number.to_string()),
             sql: Some(sql),
             evm_trace: None,
+            params: vec![],
         },
     })
 }
@@ -8,7 +8,7 @@ use crate::{
         display_sql::tx_display_query, indexing::index_block_range, raw_query::run_raw_query_async,
     },
     misc::{
-        args_parsing::{BlocksRange, get_latest_block},
+        args_parsing::{BlockTag, BlocksRange, get_latest_block},
         shared_init::{ConnOpts, CryoOpts, init_deps},
         sql_macros::{NATIVE_TOKEN_PRICE_MACRO, substitute_sql_macros},
         utils::get_native_token_price,
@@ -32,7 +32,7 @@ pub async fn block_txs(
     let native_token_price =
         get_native_token_price(&deps.chain, &deps.provider, native_token_price).await?;
 
-    let range = BlocksRange::from_str(block, &deps.provider, latest_offset).await?;
+    let range = BlocksRange::from_str(block, &deps.provider, &deps.txs, latest_offset).await?;
     if range.from != range.to {
         bail!("block-txs expects a single block number or 'latest', not a range");
     }
@@ -64,6 +64,8 @@ pub async fn block_txs(
             deps.chain.chain_id,
             native_token_price,
             None,
+            BlockTag::Latest,
+            &deps.txs,
         )
         .await?
     } else {
@@ -76,6 +78,8 @@ pub async fn block_txs(
         None,
         None,
         deps.custom_table_names(),
+        vec![],
+        None,
     )
     .await?;
 
@@ -85,12 +89,14 @@ pub async fn block_txs(
         cached_blocks,
         new_blocks,
         latest_block,
+        latest_block_tag: Some(BlockTag::Latest),
         duration_ns,
         chain: chain_info,
         query: QueryParams {
             blocks: Some(block_number.to_string()),
             sql: Some(sql),
             evm_trace: None,
+            params: vec![],
         },
     })
 }
@@ -0,0 +1,58 @@
+use std::time::Instant;
+
+use alloy::primitives::Address;
+use eyre::{Result, bail};
+
+use crate::{
+    ChainInfoNoRpcsJson,
+    db::txs::{display_sql::contract_display_query, raw_query::run_raw_query_async},
+    misc::shared_init::{ConnOpts, init_deps},
+    models::json::query_response::{QueryOutcome, QueryParams},
+};
+
+/// Returns where and when `address` was deployed, from the local store's
+/// `contracts` table. Nothing is indexed: the deploying block must already be
+/// in the store (traced with `--traces` if a factory deployed it).
+pub async fn contract(address: Address, conn_opts: &ConnOpts) -> Result<QueryOutcome> {
+    let deps = init_deps(conn_opts).await?;
+
+    let start_time = Instant::now();
+
+    let sql = contract_display_query(&format!("contracts.address = X'{}'", hex::encode(address)));
+    let result = run_raw_query_async(
+        sql.clone(),
+        deps.txs_read_path.clone(),
+        None,
+        None,
+        deps.custom_table_names(),
+        vec![],
+        None,
+    )
+    .await?;
+    if result.rows.is_empty() {
+        bail!(
+            "No deployment of {address} found in local store; index its deployment block \
+             (with 'query --evm-trace <mode> --traces' for factory-deployed contracts)"
+        );
+    }
+
+    let chain_info = ChainInfoNoRpcsJson::from_evm_chain(&deps.chain);
+    let duration_ns = start_time.elapsed().as_nanos() as u64;
+
+    Ok(QueryOutcome {
+        columns: result.columns,
+        rows: result.rows,
+        cached_blocks: 0,
+        new_blocks: 0,
+        latest_block: None,
+        latest_block_tag: None,
+        duration_ns,
+        chain: chain_info,
+        query: QueryParams {
+            blocks: None,
+            sql: Some(sql),
+            evm_trace: None,
+            params: vec![],
+        },
+    })
+}
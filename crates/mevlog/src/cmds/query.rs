@@ -4,34 +4,78 @@ use eyre::{Result, bail, eyre};
 
 use crate::{
     ChainInfoNoRpcsJson,
-    db::txs::{indexing::index_block_range, raw_query::run_raw_query_async},
+    db::txs::{
+        indexing::index_block_selection,
+        models::block::BlockMarker,
+        raw_query::{SqlParam, run_raw_query_async},
+        rpc_functions::RpcFunctions,
+    },
     misc::{
-        args_parsing::{BlocksRange, get_latest_block},
+        args_parsing::{BlockSelection, BlockTag, get_head_block},
         shared_init::{ConnOpts, CryoOpts, SharedOpts, init_deps},
         sql_macros::substitute_sql_macros,
-        tx_tracing::backfill_coinbase_transfers,
+        tx_tracing::{backfill_block_traces, backfill_coinbase_transfers},
         utils::get_native_token_price,
     },
     models::json::query_response::{QueryOutcome, QueryParams},
 };
 
+/// How [`query`] selects, indexes and backfills blocks before running its SQL,
+/// mirroring the `query` CLI flags.
+#[derive(Debug, Clone, Copy)]
+pub struct QueryOpts<'a> {
+    /// Block selection to index; required unless `skip_index` is set.
+    pub blocks: Option<&'a str>,
+    pub latest_offset: Option<u64>,
+    pub head: BlockTag,
+    /// Largest block selection accepted, in blocks.
+    pub max_range: Option<u64>,
+    pub max_rows: Option<usize>,
+    pub batch_size: usize,
+    /// Query the local store as-is, without resolving or indexing blocks.
+    pub skip_index: bool,
+    /// Backfill `balance_changes` over the selection (needs `--evm-trace`).
+    pub balance_changes: bool,
+    /// Backfill `traces` over the selection (needs `--evm-trace`).
+    pub traces: bool,
+    /// Head reported with the results, instead of fetching the chain's.
+    pub latest_block: Option<u64>,
+    /// Enables the `eth_call` / `eth_balance` SQL functions, capped at that
+    /// many RPC requests.
+    pub rpc_calls: Option<u64>,
+    /// Caches their results at or below the finalized head on disk across
+    /// queries. The size-capped cache is shared by every caller, so leave it
+    /// off for untrusted ones.
+    pub rpc_call_cache: bool,
+    pub timeout_ms: Option<u64>,
+}
+
 /// Collects all txs within a block range into the local store and runs the
-/// given read-only SQL against it.
-#[allow(clippy::too_many_arguments)]
+/// given read-only SQL against it, with `params` bound to its `:name`
+/// placeholders.
 pub async fn query(
-    blocks: Option<&str>,
-    latest_offset: Option<u64>,
-    max_range: Option<u64>,
-    max_rows: Option<usize>,
-    batch_size: usize,
-    skip_index: bool,
-    latest_block: Option<u64>,
     sql: &str,
+    params: &[SqlParam],
+    opts: QueryOpts<'_>,
     shared_opts: &SharedOpts,
     conn_opts: &ConnOpts,
     cryo_opts: &CryoOpts,
-    timeout_ms: Option<u64>,
 ) -> Result<QueryOutcome> {
+    let QueryOpts {
+        blocks,
+        latest_offset,
+        head,
+        max_range,
+        max_rows,
+        batch_size,
+        skip_index,
+        balance_changes,
+        traces,
+        latest_block,
+        rpc_calls,
+        rpc_call_cache,
+        timeout_ms,
+    } = opts;
     let start_time = Instant::now();
     let deadline = timeout_ms
         .filter(|&ms| ms > 0)
@@ -47,31 +91,36 @@ pub async fn query(
             get_native_token_price(&deps.chain, &deps.provider, shared_opts.native_token_price)
                 .await?;
 
-        // Chain's latest block, echoed in every output format for context on
-        // how fresh the queried data is. An explicit `latest_block` (e.g. the
-        // backend's indexed head) wins; with --skip-index no RPC is made, so
-        // without one the value stays unresolved.
-        let latest_block = match latest_block {
-            Some(n) => Some(n),
-            None if skip_index => None,
-            None => Some(get_latest_block(&deps.provider, latest_offset).await?),
+        // Chain's `head` block, echoed (with its tag) in every output format
+        // for context on how fresh the queried data is. An explicit
+        // `latest_block` (e.g. the backend's indexed head) wins; with
+        // --skip-index no RPC is made, so without one the value stays
+        // unresolved.
+        let (latest_block, latest_block_tag) = match latest_block {
+            Some(n) => (Some(n), None),
+            None if skip_index => (None, None),
+            None => (
+                Some(get_head_block(&deps.provider, head, latest_offset).await?),
+                Some(head),
+            ),
         };
 
         // With --skip-index the local store is queried as-is: no block range
         // resolution (so no RPC for 'latest'), no fetching, no backfill.
-        let (cached_blocks, new_blocks) = if skip_index {
+        let (cached_blocks, new_blocks, selection) = if skip_index {
             if blocks.is_some() {
                 bail!("'--blocks' and '--skip-index' are mutually exclusive");
             }
-            (0, 0)
+            (0, 0, None)
         } else {
             let Some(blocks) = blocks else {
                 bail!("'--blocks' is required unless --skip-index is enabled");
             };
-            let block_range = BlocksRange::from_str(blocks, &deps.provider, latest_offset).await?;
+            let selection =
+                BlockSelection::from_str(blocks, &deps.provider, &deps.txs, latest_offset).await?;
 
             if let Some(max_range) = max_range {
-                let range_size = block_range.size();
+                let range_size = selection.size();
                 if range_size > max_range {
                     bail!(
                         "Block range size {} exceeds maximum allowed range of {}",
@@ -83,32 +132,50 @@ pub async fn query(
 
             // Only fetch blocks that are not already in the local store. Indexed
             // blocks (including empty ones, tracked by the `blocks` table) are skipped.
-            let counts = index_block_range(
-                block_range.from,
-                block_range.to,
-                batch_size,
-                &deps,
-                cryo_opts,
-            )
-            .await?;
+            let indexed = index_block_selection(&selection, batch_size, &deps, cryo_opts).await?;
 
             // Backfill direct coinbase payments for any untraced txs in range. Runs
             // over the local store, so it also covers blocks indexed earlier without
             // --evm-trace.
             if let Some(mode) = &shared_opts.evm_trace {
-                backfill_coinbase_transfers(
-                    block_range.from,
-                    block_range.to,
-                    mode,
-                    &deps.provider,
-                    &deps.chain,
-                    &deps.rpc_url,
-                    &deps.txs,
-                )
-                .await?;
+                let mut markers = vec![];
+                if balance_changes {
+                    markers.push(BlockMarker::BalanceChanges);
+                }
+                if traces {
+                    markers.push(BlockMarker::Traces);
+                }
+
+                for segment in selection.merged() {
+                    backfill_coinbase_transfers(
+                        segment.from,
+                        segment.to,
+                        mode,
+                        &deps.provider,
+                        &deps.chain,
+                        &deps.rpc_url,
+                        &deps.txs,
+                    )
+                    .await?;
+
+                    // Opt-in: these trace every tx of every untraced block, far heavier
+                    // than the coinbase pass, which skips already-traced txs.
+                    backfill_block_traces(
+                        segment.from,
+                        segment.to,
+                        &markers,
+                        mode,
+                        &deps.provider,
+                        &deps.chain,
+                        &deps.rpc_url,
+                        &deps.txs,
+                        &deps.sqlite,
+                    )
+                    .await?;
+                }
             }
 
-            counts
+            (indexed.cached_blocks, indexed.new_blocks, Some(selection))
         };
 
         let mut chain_info = ChainInfoNoRpcsJson::from_evm_chain(&deps.chain);
@@ -120,27 +187,58 @@ pub async fn query(
             deps.chain.chain_id,
             native_token_price,
             latest_block,
+            head,
+            &deps.txs,
         )
         .await?;
 
+        let rpc_functions = match rpc_calls {
+            Some(budget) => {
+                let mut rpc = RpcFunctions::new(deps.provider.clone(), deps.chain.chain_id, budget);
+                if rpc_call_cache {
+                    // Without a finalized head, results could still be
+                    // reorged, so none are cached.
+                    match get_head_block(&deps.provider, BlockTag::Finalized, None).await {
+                        Ok(finalized_block) => rpc = rpc.with_disk_cache(finalized_block),
+                        Err(e) => tracing::warn!("Not caching RPC call results: {e}"),
+                    }
+                }
+                Some(rpc)
+            }
+            None => None,
+        };
+
         Ok::<_, eyre::Report>((
             deps.txs_read_path.clone(),
             chain_info,
             cached_blocks,
             new_blocks,
             latest_block,
+            latest_block_tag,
             sql,
             deps.custom_table_names(),
+            selection,
+            rpc_functions,
         ))
     };
 
-    let (txs_read_path, chain_info, cached_blocks, new_blocks, latest_block, sql, custom_tables) =
-        match deadline {
-            Some(dl) => tokio::time::timeout_at(tokio::time::Instant::from_std(dl), prep)
-                .await
-                .map_err(|_| eyre!("Query timed out after {}ms", timeout_ms.unwrap()))??,
-            None => prep.await?,
-        };
+    let (
+        txs_read_path,
+        chain_info,
+        cached_blocks,
+        new_blocks,
+        latest_block,
+        latest_block_tag,
+        sql,
+        custom_tables,
+        selection,
+        rpc_functions,
+    ) = match deadline {
+        Some(dl) => tokio::time::timeout_at(tokio::time::Instant::from_std(dl), prep)
+            .await
+            .map_err(|_| eyre!("Query timed out after {}ms", timeout_ms.unwrap()))??,
+        None => prep.await?,
+    };
 
     // The SQL runs in a blocking task a dropped future can't cancel, so the
     // remaining budget is enforced inside SQLite via its progress handler.
@@ -151,6 +249,8 @@ pub async fn query(
         max_rows,
         remaining,
         custom_tables,
+        params.to_vec(),
+        rpc_functions,
     )
     .await?;
 
@@ -162,12 +262,16 @@ pub async fn query(
         cached_blocks,
         new_blocks,
         latest_block,
+        latest_block_tag,
         duration_ns,
         chain: chain_info,
         query: QueryParams {
-            blocks: blocks.map(str::to_string),
+            // Resolved segments, so 'latest' or a time range echoes the
+            // blocks it actually covered.
+            blocks: selection.map(|selection| selection.to_string()),
             sql: Some(sql),
             evm_trace: shared_opts.evm_trace.clone(),
+            params: params.iter().map(ToString::to_string).collect(),
         },
     })
 }
@@ -10,7 +10,7 @@ use crate::{
         models::transaction::Transaction, raw_query::run_raw_query_async,
     },
     misc::{
-        args_parsing::get_latest_block,
+        args_parsing::{BlockTag, get_latest_block},
         shared_init::{ConnOpts, CryoOpts, TraceMode, init_deps},
         sql_macros::{NATIVE_TOKEN_PRICE_MACRO, substitute_sql_macros},
         tx_tracing::coinbase_transfer_for_tx,
@@ -74,6 +74,8 @@ pub async fn tx(
             deps.chain.chain_id,
             native_token_price,
             None,
+            BlockTag::Latest,
+            &deps.txs,
         )
         .await?
     } else {
@@ -86,6 +88,8 @@ pub async fn tx(
         None,
         None,
         deps.custom_table_names(),
+        vec![],
+        None,
     )
     .await?;
     if result.rows.is_empty() {
@@ -98,12 +102,14 @@ pub async fn tx(
         cached_blocks,
         new_blocks,
         latest_block,
+        latest_block_tag: Some(BlockTag::Latest),
         duration_ns,
         chain: chain_info,
         query: QueryParams {
             blocks: Some(block_number.to_string()),
             sql: Some(sql),
             evm_trace: evm_trace.cloned(),
+            params: vec![],
         },
     })
 }
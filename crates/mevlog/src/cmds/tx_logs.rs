@@ -10,7 +10,7 @@ use crate::{
         raw_query::run_raw_query_async,
     },
     misc::{
-        args_parsing::get_latest_block,
+        args_parsing::{BlockTag, get_latest_block},
         shared_init::{ConnOpts, CryoOpts, init_deps},
     },
     models::json::query_response::{QueryOutcome, QueryParams},
@@ -57,6 +57,8 @@ pub async fn tx_logs(
         None,
         None,
         deps.custom_table_names(),
+        vec![],
+        None,
     )
     .await?;
 
@@ -66,12 +68,14 @@ pub async fn tx_logs(
         cached_blocks,
         new_blocks,
         latest_block,
+        latest_block_tag: Some(BlockTag::Latest),
         duration_ns,
         chain: chain_info,
         query: QueryParams {
             blocks: Some(block_number.to_string()),
             sql: Some(sql),
             evm_trace: None,
+            params: vec![],
         },
     })
 }
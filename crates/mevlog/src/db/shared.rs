@@ -50,6 +50,21 @@ pub(crate) async fn init_db(
     Ok(())
 }
 
+/// Applies migrations added after the DB file was created. [`init_db`] only
+/// runs the migrator when it creates the file, so without this an existing DB
+/// would never gain newly added columns or tables.
+pub(crate) async fn migrate(
+    db_url: Option<String>,
+    default_path: PathBuf,
+    migrator: &Migrator,
+) -> Result<()> {
+    let db = conn(db_url, default_path, false).await?;
+    migrator.run(&db).await?;
+    db.close().await;
+
+    Ok(())
+}
+
 pub(crate) async fn conn(
     db_url: Option<String>,
     default_path: PathBuf,
@@ -83,6 +98,31 @@ pub(crate) async fn conn(
     }
 }
 
+/// Whether `table` has `column`. Read-only commands (`db-info`) and bare
+/// writer connections (`purge-db`) never run [`migrate`], so they use this to
+/// tolerate DBs created before a column was added.
+pub(crate) async fn column_exists(table: &str, column: &str, conn: &SqlitePool) -> Result<bool> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+        .bind(table)
+        .bind(column)
+        .fetch_one(conn)
+        .await?;
+
+    Ok(count > 0)
+}
+
+/// Whether `table` exists; the [`column_exists`] counterpart for tables added
+/// by later migrations.
+pub(crate) async fn table_exists(table: &str, conn: &SqlitePool) -> Result<bool> {
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table)
+            .fetch_one(conn)
+            .await?;
+
+    Ok(count > 0)
+}
+
 pub async fn truncate_wal(conn: &SqlitePool) -> Result<()> {
     sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
         .execute(conn)
@@ -1,3 +1,7 @@
 pub mod chain;
 pub mod event;
 pub mod method;
+
+/// Hashes bound per `IN (...)` query by the bulk signature lookups, well below
+/// SQLite's host parameter limit.
+const SIG_LOOKUP_CHUNK: usize = 500;
@@ -4,6 +4,8 @@ use eyre::Result;
 use sqlx::Row;
 use tokio::sync::RwLock;
 
+use super::SIG_LOOKUP_CHUNK;
+
 #[derive(Debug)]
 pub struct Event {
     pub signature_hash_32: Vec<u8>,
@@ -28,6 +30,7 @@ impl Event {
         Ok(count)
     }
 
+    #[allow(dead_code)] // used in tests
     pub(crate) async fn find_by_topic(
         signature_hash: &str,
         conn: &sqlx::SqlitePool,
@@ -54,6 +57,69 @@ impl Event {
         Ok(found)
     }
 
+    /// Bulk counterpart of [`Self::find_by_topic`] for a whole parquet batch:
+    /// cache misses are resolved with one `IN (...)` query per
+    /// `SIG_LOOKUP_CHUNK` hashes and cached like single lookups. Returns only
+    /// the hashes with a known signature; with duplicate rows, the first one
+    /// wins as in [`Self::find_by_topic`].
+    pub(crate) async fn find_by_topics(
+        topics: &[[u8; 32]],
+        conn: &sqlx::SqlitePool,
+    ) -> Result<HashMap<[u8; 32], String>> {
+        let mut found = HashMap::new();
+        let mut misses = vec![];
+
+        {
+            let cache = TOPIC_SIG_MEMORY_CACHE.read().await;
+            for topic in topics {
+                match cache.get(&hex::encode(topic)) {
+                    Some(Some(signature)) => {
+                        found.insert(*topic, signature.clone());
+                    }
+                    Some(None) => {}
+                    None => misses.push(*topic),
+                }
+            }
+        }
+
+        misses.sort_unstable();
+        misses.dedup();
+        if misses.is_empty() {
+            return Ok(found);
+        }
+
+        let mut resolved: HashMap<[u8; 32], String> = HashMap::new();
+        for chunk in misses.chunks(SIG_LOOKUP_CHUNK) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let mut query = sqlx::query(sqlx::AssertSqlSafe(format!(
+                "SELECT signature_hash_32, signature FROM events \
+                 WHERE signature_hash_32 IN ({placeholders}) ORDER BY rowid"
+            )));
+            for topic in chunk {
+                query = query.bind(topic.as_slice());
+            }
+
+            for row in query.fetch_all(conn).await? {
+                let hash: Vec<u8> = row.get(0);
+                let Ok(key) = <[u8; 32]>::try_from(hash.as_slice()) else {
+                    continue;
+                };
+                resolved.entry(key).or_insert_with(|| row.get(1));
+            }
+        }
+
+        let mut cache = TOPIC_SIG_MEMORY_CACHE.write().await;
+        for topic in misses {
+            let signature = resolved.remove(&topic);
+            cache.insert(hex::encode(topic), signature.clone());
+            if let Some(signature) = signature {
+                found.insert(topic, signature);
+            }
+        }
+
+        Ok(found)
+    }
+
     pub async fn save<'c, E>(&self, executor: E) -> Result<()>
     where
         E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
@@ -156,6 +222,29 @@ pub mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn find_by_topics_resolves_known_hashes() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let deposit = "e1fffcc4923d04b559f4d29a8bfc6cda04eb5b0d3c460751c2402c5c5cc9109c";
+        event(deposit, "Deposit(address,uint256)")
+            .save(&conn)
+            .await?;
+
+        let known: [u8; 32] = hex::decode(deposit).unwrap().try_into().unwrap();
+        let unknown = [0xfe; 32];
+        let found = Event::find_by_topics(&[known, unknown, known], &conn).await?;
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[&known], "Deposit(address,uint256)");
+
+        // Bulk results land in the cache shared with single lookups.
+        let cached = Event::find_by_topic(&format!("0x{deposit}"), &conn).await?;
+        assert_eq!(cached.unwrap(), "Deposit(address,uint256)");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn save_with_transaction() -> Result<()> {
         let (conn, _cl) = setup_test_db().await;
@@ -4,6 +4,8 @@ use eyre::Result;
 use sqlx::Row;
 use tokio::sync::RwLock;
 
+use super::SIG_LOOKUP_CHUNK;
+
 #[derive(Debug)]
 pub struct Method {
     pub signature_hash_4: Vec<u8>,
@@ -54,6 +56,69 @@ impl Method {
         Ok(found)
     }
 
+    /// Bulk counterpart of [`Self::find_by_selector`] for a whole parquet batch:
+    /// cache misses are resolved with one `IN (...)` query per
+    /// `SIG_LOOKUP_CHUNK` hashes and cached like single lookups. Returns only
+    /// the hashes with a known signature; with duplicate rows, the first one
+    /// wins as in [`Self::find_by_selector`].
+    pub(crate) async fn find_by_selectors(
+        selectors: &[[u8; 4]],
+        conn: &sqlx::SqlitePool,
+    ) -> Result<HashMap<[u8; 4], String>> {
+        let mut found = HashMap::new();
+        let mut misses = vec![];
+
+        {
+            let cache = SELECTOR_SIG_MEMORY_CACHE.read().await;
+            for selector in selectors {
+                match cache.get(&hex::encode(selector)) {
+                    Some(Some(signature)) => {
+                        found.insert(*selector, signature.clone());
+                    }
+                    Some(None) => {}
+                    None => misses.push(*selector),
+                }
+            }
+        }
+
+        misses.sort_unstable();
+        misses.dedup();
+        if misses.is_empty() {
+            return Ok(found);
+        }
+
+        let mut resolved: HashMap<[u8; 4], String> = HashMap::new();
+        for chunk in misses.chunks(SIG_LOOKUP_CHUNK) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let mut query = sqlx::query(sqlx::AssertSqlSafe(format!(
+                "SELECT signature_hash_4, signature FROM methods \
+                 WHERE signature_hash_4 IN ({placeholders}) ORDER BY rowid"
+            )));
+            for selector in chunk {
+                query = query.bind(selector.as_slice());
+            }
+
+            for row in query.fetch_all(conn).await? {
+                let hash: Vec<u8> = row.get(0);
+                let Ok(key) = <[u8; 4]>::try_from(hash.as_slice()) else {
+                    continue;
+                };
+                resolved.entry(key).or_insert_with(|| row.get(1));
+            }
+        }
+
+        let mut cache = SELECTOR_SIG_MEMORY_CACHE.write().await;
+        for selector in misses {
+            let signature = resolved.remove(&selector);
+            cache.insert(hex::encode(selector), signature.clone());
+            if let Some(signature) = signature {
+                found.insert(selector, signature);
+            }
+        }
+
+        Ok(found)
+    }
+
     pub async fn save<'c, E>(&self, executor: E) -> Result<()>
     where
         E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
@@ -103,6 +168,40 @@ pub mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn find_by_selectors_resolves_known_hashes() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        method("095ea7b3", "approve(address,uint256)")
+            .save(&conn)
+            .await?;
+        method("23b872dd", "transferFrom(address,address,uint256)")
+            .save(&conn)
+            .await?;
+        // Duplicate selector rows: the first one wins, as with LIMIT 1.
+        method("23b872dd", "gasprice_bit_ether(int128)")
+            .save(&conn)
+            .await?;
+
+        let selectors = [
+            [0x09, 0x5e, 0xa7, 0xb3],
+            [0x23, 0xb8, 0x72, 0xdd],
+            [0x23, 0xb8, 0x72, 0xdd],
+            [0xca, 0xfe, 0xba, 0xbe],
+        ];
+        let found = Method::find_by_selectors(&selectors, &conn).await?;
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[&selectors[0]], "approve(address,uint256)");
+        assert_eq!(
+            found[&selectors[1]],
+            "transferFrom(address,address,uint256)"
+        );
+        assert!(!found.contains_key(&selectors[3]));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn save_with_transaction() -> Result<()> {
         let (conn, _cl) = setup_test_db().await;
@@ -1,10 +1,18 @@
 pub mod custom_tables;
 pub mod display_sql;
+pub mod export;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod import;
 pub mod indexing;
 pub mod info;
+pub mod logs_indexing;
 pub mod models;
 pub mod purge;
 pub mod raw_query;
+pub mod reorg;
+pub mod rpc_functions;
+pub mod sql_functions;
 
 use std::path::PathBuf;
 
@@ -18,8 +26,29 @@ use crate::{db::shared, misc::shared_init::config_path};
 static MIGRATOR: Migrator = sqlx::migrate!("migrations/txs");
 pub const SCHEMA_VERSION: u64 = 1;
 
+/// Built-in tables keyed by `block_number`. `export` writes them in block
+/// partitions.
+pub(crate) const BLOCK_TABLES: [&str; 10] = [
+    "blocks",
+    "transactions",
+    "logs",
+    "access_list_entries",
+    "authorizations",
+    "withdrawals",
+    "balance_changes",
+    "traces",
+    "contracts",
+    "nft_transfers",
+];
+
+/// Built-in tables with one row per entity rather than per block. `--sql`
+/// queries read them like [`BLOCK_TABLES`], but `export` leaves them out since
+/// it partitions rows by `block_number`.
+pub(crate) const NON_BLOCK_TABLES: [&str; 1] = ["tokens"];
+
 pub(crate) async fn init_db(db_url: Option<String>, chain_id: u64) -> Result<()> {
-    shared::init_db(db_url, default_db_path(chain_id), &MIGRATOR).await
+    shared::init_db(db_url.clone(), default_db_path(chain_id), &MIGRATOR).await?;
+    shared::migrate(db_url, default_db_path(chain_id), &MIGRATOR).await
 }
 
 pub async fn conn(db_url: Option<String>, chain_id: u64, read_only: bool) -> Result<SqlitePool> {
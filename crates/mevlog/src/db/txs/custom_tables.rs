@@ -349,7 +349,10 @@ mod test {
 
     use super::*;
     use crate::{
-        db::txs::models::{log::Log, transaction::test::setup_test_db},
+        db::txs::{
+            fixtures::sample_log,
+            models::{log::Log, transaction::test::setup_test_db},
+        },
         misc::config::Config,
     };
 
@@ -395,8 +398,6 @@ type = "address"
         };
 
         Log {
-            block_number,
-            tx_index: 0,
             log_index,
             address: Address::from(emitter),
             topics: vec![
@@ -405,8 +406,7 @@ type = "address"
                 pad_address(0xcc),
             ],
             data: U256::from(amount).to_be_bytes::<32>().to_vec(),
-            erc20_amount: None,
-            signature: None,
+            ..sample_log(block_number)
         }
     }
 
@@ -55,7 +55,7 @@ pub(crate) fn tx_display_query(where_sql: &str) -> String {
 
 /// Canonical blocks `SELECT` for the given `WHERE` clause, projecting the columns
 /// of [`BlockJson`]: the `blocks` table metadata, the base fee rendered in gwei,
-/// and the block's indexed transaction count.
+/// the difficulty as a decimal string, and the block's indexed transaction count.
 ///
 /// [`BlockJson`]: crate::models::json::block_json::BlockJson
 pub(crate) fn block_display_query(where_sql: &str) -> String {
@@ -65,9 +65,17 @@ pub(crate) fn block_display_query(where_sql: &str) -> String {
             block_hash, \
             miner, \
             gas_used, \
+            gas_limit, \
             timestamp, \
             base_fee_per_gas, \
             format_gwei(base_fee_per_gas) AS display_base_fee_per_gas, \
+            extra_data, \
+            size, \
+            state_root, \
+            transactions_root, \
+            u256_to_dec(difficulty) AS difficulty, \
+            mix_hash, \
+            parent_beacon_block_root, \
             ( \
                 SELECT COUNT(*) FROM transactions \
                 WHERE transactions.block_number = blocks.block_number \
@@ -78,6 +86,30 @@ pub(crate) fn block_display_query(where_sql: &str) -> String {
     )
 }
 
+/// Canonical contracts `SELECT` for the given `WHERE` clause: each deployment
+/// with its block timestamp and deploying tx hash, oldest first. The joins are
+/// `LEFT` so a deployment still shows if its block or tx row is missing.
+pub(crate) fn contract_display_query(where_sql: &str) -> String {
+    format!(
+        "SELECT \
+            contracts.address, \
+            contracts.block_number, \
+            blocks.timestamp, \
+            contracts.tx_index, \
+            transactions.tx_hash, \
+            contracts.deployer, \
+            contracts.create_type, \
+            contracts.code_hash \
+         FROM contracts \
+         LEFT JOIN blocks ON blocks.block_number = contracts.block_number \
+         LEFT JOIN transactions \
+            ON transactions.block_number = contracts.block_number \
+            AND transactions.tx_index = contracts.tx_index \
+         WHERE {where_sql} \
+         ORDER BY contracts.block_number ASC, contracts.tx_index ASC"
+    )
+}
+
 /// Canonical logs `SELECT` for the given `WHERE` clause, projecting the columns
 /// of [`LogJson`]. `topic0..topic3` are returned as separate columns (`NULL`
 /// when absent), keeping the rows faithful to the echoed SQL.
@@ -0,0 +1,711 @@
+//! Export of the local txs DB into files that DuckDB, Spark or pandas read
+//! directly: one directory per table and one file per block-range partition,
+//! e.g. `logs/logs__00025215000_to_00025215999.parquet`. Column types come
+//! from the SQLite schema, with BLOBs mapped to their known fixed widths so
+//! hashes and addresses stay `FIXED_LEN_BYTE_ARRAY` in parquet.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alloy::primitives::{I256, U256};
+use arrow::{
+    array::{
+        ArrayBuilder, ArrayRef, BinaryBuilder, BooleanBuilder, FixedSizeBinaryBuilder,
+        Int64Builder, StringBuilder,
+    },
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    record_batch::RecordBatch,
+};
+use eyre::{Result, bail, eyre};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+
+use crate::{
+    db::{
+        shared::table_exists,
+        txs::{BLOCK_TABLES, NON_BLOCK_TABLES, custom_tables},
+    },
+    misc::config::{ColumnSource, ColumnType, Config, CustomTable},
+};
+
+/// Blocks read from SQLite per record batch, bounding memory use independently
+/// of the partition size.
+const READ_CHUNK_BLOCKS: u64 = 100;
+
+/// Output file format of [`export_range`].
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Parquet,
+    Csv,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOpts {
+    pub out_dir: PathBuf,
+    pub from: u64,
+    pub to: u64,
+    /// Blocks per output file. Partitions are aligned to multiples of this
+    /// size, so repeated exports of a growing DB produce the same file names.
+    pub partition_blocks: u64,
+    pub format: ExportFormat,
+    /// Write u256 BLOBs (`value`, `erc20_amount`, `uint256` custom columns) and
+    /// signed i256 BLOBs (`balance_changes.delta`) as decimal strings instead
+    /// of 32-byte big-endian binary.
+    pub u256_as_decimal: bool,
+    /// Tables to export; empty means the built-in tables plus every tracked
+    /// custom table.
+    pub tables: Vec<String>,
+}
+
+/// Per-table outcome of [`export_range`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExportedTable {
+    pub name: String,
+    pub rows: u64,
+    pub files: u64,
+}
+
+/// Exported type of a column, derived from its declared SQLite type and, for
+/// BLOBs, the width of the value it holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportType {
+    Int,
+    Bool,
+    Text,
+    FixedBinary(i32),
+    Binary,
+    U256,
+    /// Two's-complement signed 256-bit integer.
+    I256,
+}
+
+#[derive(Debug, Clone)]
+struct ExportColumn {
+    name: String,
+    ty: ExportType,
+}
+
+/// Writes `opts.tables` rows in `opts.from..=opts.to` to `opts.out_dir`, one
+/// file per non-empty partition. Existing files with the same name are
+/// overwritten. Custom table column types are taken from the config; tracked
+/// tables no longer configured export their BLOBs as variable-width binary.
+pub async fn export_range(opts: &ExportOpts, conn: &SqlitePool) -> Result<Vec<ExportedTable>> {
+    let custom_defs = Config::load()?.custom_tables()?;
+    export_tables(opts, &custom_defs, conn).await
+}
+
+pub(crate) async fn export_tables(
+    opts: &ExportOpts,
+    custom_defs: &[CustomTable],
+    conn: &SqlitePool,
+) -> Result<Vec<ExportedTable>> {
+    if opts.from > opts.to {
+        bail!("Invalid export range {}..={}", opts.from, opts.to);
+    }
+    if opts.partition_blocks == 0 {
+        bail!("Partition size must be at least 1 block");
+    }
+
+    // The DB is opened read-only, so it may predate tables added by later
+    // migrations.
+    let mut available: Vec<String> = vec![];
+    for name in BLOCK_TABLES {
+        if table_exists(name, conn).await? {
+            available.push(name.to_string());
+        }
+    }
+    available.extend(custom_tables::tracked_table_names(conn).await?);
+
+    let tables = if opts.tables.is_empty() {
+        available
+    } else {
+        for name in &opts.tables {
+            if NON_BLOCK_TABLES.contains(&name.as_str()) {
+                bail!("Table '{name}' is not keyed by block number and can't be exported");
+            }
+            if !available.contains(name) {
+                bail!(
+                    "Unknown table '{}', expected one of: {}",
+                    name,
+                    available.join(", ")
+                );
+            }
+        }
+        opts.tables.clone()
+    };
+
+    let mut exported = vec![];
+    for name in tables {
+        let custom_def = custom_defs.iter().find(|t| t.name == name);
+        let columns = table_columns(&name, custom_def, opts.u256_as_decimal, conn).await?;
+        exported.push(export_table(&name, &columns, opts, conn).await?);
+    }
+
+    Ok(exported)
+}
+
+async fn export_table(
+    table: &str,
+    columns: &[ExportColumn],
+    opts: &ExportOpts,
+    conn: &SqlitePool,
+) -> Result<ExportedTable> {
+    let table_dir = opts.out_dir.join(table);
+    let sql = select_sql(table, columns);
+    let schema = arrow_schema(columns);
+
+    let mut stats = ExportedTable {
+        name: table.to_string(),
+        rows: 0,
+        files: 0,
+    };
+
+    for (part_from, part_to) in partitions(opts.from, opts.to, opts.partition_blocks) {
+        let path = table_dir.join(format!(
+            "{table}__{part_from:011}_to_{part_to:011}.{}",
+            opts.format.extension()
+        ));
+        let mut writer: Option<PartitionWriter> = None;
+
+        let mut chunk_from = part_from;
+        while chunk_from <= part_to {
+            let chunk_to = (chunk_from + READ_CHUNK_BLOCKS - 1).min(part_to);
+            let rows = sqlx::query(sqlx::AssertSqlSafe(sql.clone()))
+                .bind(chunk_from as i64)
+                .bind(chunk_to as i64)
+                .fetch_all(conn)
+                .await?;
+
+            if !rows.is_empty() {
+                // Files are only created once a partition has rows.
+                if writer.is_none() {
+                    fs::create_dir_all(&table_dir)?;
+                    writer = Some(PartitionWriter::create(
+                        &path,
+                        opts.format,
+                        columns,
+                        schema.clone(),
+                    )?);
+                }
+                if let Some(writer) = writer.as_mut() {
+                    writer.write(table, columns, &schema, &rows)?;
+                }
+                stats.rows += rows.len() as u64;
+            }
+
+            chunk_from = chunk_to + 1;
+        }
+
+        if let Some(writer) = writer {
+            writer.finish()?;
+            stats.files += 1;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Splits `from..=to` into ranges aligned to multiples of `size`, clipped to
+/// the requested bounds.
+fn partitions(from: u64, to: u64, size: u64) -> Vec<(u64, u64)> {
+    let mut ranges = vec![];
+    let mut start = from;
+    while start <= to {
+        let end = (start - start % size).saturating_add(size - 1).min(to);
+        ranges.push((start, end));
+        if end == u64::MAX {
+            break;
+        }
+        start = end + 1;
+    }
+    ranges
+}
+
+async fn table_columns(
+    table: &str,
+    custom_def: Option<&CustomTable>,
+    u256_as_decimal: bool,
+    conn: &SqlitePool,
+) -> Result<Vec<ExportColumn>> {
+    let rows = sqlx::query(sqlx::AssertSqlSafe(format!(
+        "SELECT name, type FROM pragma_table_info('{table}')"
+    )))
+    .fetch_all(conn)
+    .await?;
+
+    rows.iter()
+        .map(|row| {
+            let name: String = row.get(0);
+            let declared: String = row.get(1);
+            let ty = match declared.to_ascii_uppercase().as_str() {
+                "BIGINT" | "INTEGER" => ExportType::Int,
+                "BOOLEAN" => ExportType::Bool,
+                "TEXT" => ExportType::Text,
+                "BLOB" => match blob_type(table, &name, custom_def) {
+                    ExportType::U256 | ExportType::I256 if !u256_as_decimal => {
+                        ExportType::FixedBinary(32)
+                    }
+                    ty => ty,
+                },
+                other => bail!("Unsupported column type '{other}' for {table}.{name}"),
+            };
+            Ok(ExportColumn { name, ty })
+        })
+        .collect()
+}
+
+/// Width of the values stored in a BLOB column. Anything not known to be
+/// fixed-size (calldata, log data, untyped custom columns) stays variable.
+fn blob_type(table: &str, column: &str, custom_def: Option<&CustomTable>) -> ExportType {
+    if let Some(def) = custom_def {
+        let Some(custom_column) = def.columns.iter().find(|c| c.name == column) else {
+            return match column {
+                "address" => ExportType::FixedBinary(20),
+                _ => ExportType::Binary,
+            };
+        };
+        return match (custom_column.r#type, &custom_column.source) {
+            (ColumnType::Address, _) => ExportType::FixedBinary(20),
+            (ColumnType::Uint256, _) => ExportType::U256,
+            (ColumnType::Bytes, ColumnSource::Topic(_)) => ExportType::FixedBinary(32),
+            (ColumnType::Bytes, ColumnSource::Data { start, end }) => {
+                ExportType::FixedBinary((end - start) as i32)
+            }
+        };
+    }
+
+    match (table, column) {
+        ("transactions", "tx_hash")
+        | (
+            "blocks",
+            "block_hash"
+            | "parent_hash"
+            | "state_root"
+            | "transactions_root"
+            | "mix_hash"
+            | "parent_beacon_block_root",
+        )
+        | ("logs", "topic0" | "topic1" | "topic2" | "topic3")
+        | ("access_list_entries", "storage_key")
+        | ("contracts", "code_hash") => ExportType::FixedBinary(32),
+        ("transactions" | "traces" | "nft_transfers", "from_address" | "to_address")
+        | ("nft_transfers", "contract")
+        | ("blocks", "miner")
+        | ("authorizations", "authority")
+        | ("balance_changes", "token")
+        | ("contracts", "deployer")
+        | (_, "address") => ExportType::FixedBinary(20),
+        ("transactions", "signature_hash") | ("traces", "selector") => ExportType::FixedBinary(4),
+        ("transactions", "value" | "coinbase_transfer")
+        | ("traces", "value")
+        | ("nft_transfers", "token_id" | "amount")
+        | ("logs", "erc20_amount")
+        | ("blocks", "difficulty") => ExportType::U256,
+        ("balance_changes", "delta") => ExportType::I256,
+        _ => ExportType::Binary,
+    }
+}
+
+fn select_sql(table: &str, columns: &[ExportColumn]) -> String {
+    let names: Vec<String> = columns.iter().map(|c| format!("\"{}\"", c.name)).collect();
+    let order = if columns.iter().any(|c| c.name == "log_index") {
+        "block_number, log_index"
+    } else if columns.iter().any(|c| c.name == "tx_index") {
+        "block_number, tx_index"
+    } else {
+        "block_number"
+    };
+
+    format!(
+        "SELECT {} FROM \"{table}\" WHERE block_number BETWEEN ? AND ? ORDER BY {order}",
+        names.join(", ")
+    )
+}
+
+fn arrow_schema(columns: &[ExportColumn]) -> SchemaRef {
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|c| {
+            let data_type = match c.ty {
+                ExportType::Int => DataType::Int64,
+                ExportType::Bool => DataType::Boolean,
+                ExportType::Text | ExportType::U256 | ExportType::I256 => DataType::Utf8,
+                ExportType::FixedBinary(width) => DataType::FixedSizeBinary(width),
+                ExportType::Binary => DataType::Binary,
+            };
+            Field::new(&c.name, data_type, true)
+        })
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+enum PartitionWriter {
+    Parquet(ArrowWriter<fs::File>),
+    Csv(csv::Writer<fs::File>),
+}
+
+impl PartitionWriter {
+    fn create(
+        path: &Path,
+        format: ExportFormat,
+        columns: &[ExportColumn],
+        schema: SchemaRef,
+    ) -> Result<Self> {
+        let file = fs::File::create(path)?;
+        match format {
+            ExportFormat::Parquet => {
+                let props = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                Ok(Self::Parquet(ArrowWriter::try_new(
+                    file,
+                    schema,
+                    Some(props),
+                )?))
+            }
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(file);
+                writer.write_record(columns.iter().map(|c| c.name.as_str()))?;
+                Ok(Self::Csv(writer))
+            }
+        }
+    }
+
+    fn write(
+        &mut self,
+        table: &str,
+        columns: &[ExportColumn],
+        schema: &SchemaRef,
+        rows: &[SqliteRow],
+    ) -> Result<()> {
+        match self {
+            Self::Parquet(writer) => {
+                writer.write(&record_batch(table, columns, schema, rows)?)?;
+            }
+            Self::Csv(writer) => {
+                for row in rows {
+                    let record = columns
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, column)| csv_cell(row, idx, column.ty))
+                        .collect::<Result<Vec<String>>>()?;
+                    writer.write_record(&record)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Parquet(writer) => {
+                writer.close()?;
+            }
+            Self::Csv(mut writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+}
+
+fn record_batch(
+    table: &str,
+    columns: &[ExportColumn],
+    schema: &SchemaRef,
+    rows: &[SqliteRow],
+) -> Result<RecordBatch> {
+    let arrays = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| column_array(table, column, idx, rows))
+        .collect::<Result<Vec<ArrayRef>>>()?;
+
+    Ok(RecordBatch::try_new(schema.clone(), arrays)?)
+}
+
+fn column_array(
+    table: &str,
+    column: &ExportColumn,
+    idx: usize,
+    rows: &[SqliteRow],
+) -> Result<ArrayRef> {
+    let mut builder: Box<dyn ArrayBuilder> = match column.ty {
+        ExportType::Int => {
+            let mut builder = Int64Builder::with_capacity(rows.len());
+            for row in rows {
+                builder.append_option(row.try_get::<Option<i64>, _>(idx)?);
+            }
+            Box::new(builder)
+        }
+        ExportType::Bool => {
+            let mut builder = BooleanBuilder::with_capacity(rows.len());
+            for row in rows {
+                builder.append_option(row.try_get::<Option<bool>, _>(idx)?);
+            }
+            Box::new(builder)
+        }
+        ExportType::Text => {
+            let mut builder = StringBuilder::new();
+            for row in rows {
+                builder.append_option(row.try_get::<Option<String>, _>(idx)?);
+            }
+            Box::new(builder)
+        }
+        ExportType::U256 => {
+            let mut builder = StringBuilder::new();
+            for row in rows {
+                let blob: Option<Vec<u8>> = row.try_get(idx)?;
+                builder.append_option(blob.map(|b| u256_decimal(&b)).transpose()?);
+            }
+            Box::new(builder)
+        }
+        ExportType::I256 => {
+            let mut builder = StringBuilder::new();
+            for row in rows {
+                let blob: Option<Vec<u8>> = row.try_get(idx)?;
+                builder.append_option(blob.map(|b| i256_decimal(&b)).transpose()?);
+            }
+            Box::new(builder)
+        }
+        ExportType::FixedBinary(width) => {
+            let mut builder = FixedSizeBinaryBuilder::with_capacity(rows.len(), width);
+            for row in rows {
+                match row.try_get::<Option<Vec<u8>>, _>(idx)? {
+                    Some(blob) if blob.len() == width as usize => builder.append_value(&blob)?,
+                    Some(blob) => bail!(
+                        "{}.{} holds a {}-byte value, expected {} bytes",
+                        table,
+                        column.name,
+                        blob.len(),
+                        width
+                    ),
+                    None => builder.append_null(),
+                }
+            }
+            Box::new(builder)
+        }
+        ExportType::Binary => {
+            let mut builder = BinaryBuilder::new();
+            for row in rows {
+                builder.append_option(row.try_get::<Option<Vec<u8>>, _>(idx)?);
+            }
+            Box::new(builder)
+        }
+    };
+
+    Ok(builder.finish())
+}
+
+/// CSV rendering matches the `query` command: BLOBs as `0x`-prefixed hex.
+fn csv_cell(row: &SqliteRow, idx: usize, ty: ExportType) -> Result<String> {
+    let cell = match ty {
+        ExportType::Int => row.try_get::<Option<i64>, _>(idx)?.map(|v| v.to_string()),
+        ExportType::Bool => row.try_get::<Option<bool>, _>(idx)?.map(|v| v.to_string()),
+        ExportType::Text => row.try_get::<Option<String>, _>(idx)?,
+        ExportType::U256 => row
+            .try_get::<Option<Vec<u8>>, _>(idx)?
+            .map(|b| u256_decimal(&b))
+            .transpose()?,
+        ExportType::I256 => row
+            .try_get::<Option<Vec<u8>>, _>(idx)?
+            .map(|b| i256_decimal(&b))
+            .transpose()?,
+        ExportType::FixedBinary(_) | ExportType::Binary => row
+            .try_get::<Option<Vec<u8>>, _>(idx)?
+            .map(|b| format!("0x{}", hex::encode(b))),
+    };
+    Ok(cell.unwrap_or_default())
+}
+
+fn u256_decimal(blob: &[u8]) -> Result<String> {
+    if blob.len() > 32 {
+        bail!("{}-byte value does not fit a u256", blob.len());
+    }
+    Ok(U256::from_be_slice(blob).to_string())
+}
+
+fn i256_decimal(blob: &[u8]) -> Result<String> {
+    let bytes: [u8; 32] = blob
+        .try_into()
+        .map_err(|_| eyre!("{}-byte value is not an i256", blob.len()))?;
+    Ok(I256::from_be_bytes(bytes).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use revm::primitives::{Bytes, FixedBytes};
+
+    use super::*;
+    use crate::db::txs::{
+        fixtures::{sample_block, sample_tx},
+        models::{
+            block::Block,
+            transaction::{Transaction, test::setup_test_db},
+        },
+    };
+
+    /// A contract creation, so the CSV export has an empty `to_address`.
+    fn creation_tx(block_number: u64, value: u64) -> Transaction {
+        Transaction {
+            tx_hash: FixedBytes::<32>::from([block_number as u8; 32]),
+            to_address: None,
+            value: U256::from(value),
+            input: Some(Bytes::from_static(&[0xab, 0xcd])),
+            ..sample_tx(block_number)
+        }
+    }
+
+    fn opts(out_dir: PathBuf, format: ExportFormat, u256_as_decimal: bool) -> ExportOpts {
+        ExportOpts {
+            out_dir,
+            from: 98,
+            to: 105,
+            partition_blocks: 100,
+            format,
+            u256_as_decimal,
+            tables: vec!["blocks".to_string(), "transactions".to_string()],
+        }
+    }
+
+    #[test]
+    fn partitions_align_to_size() {
+        assert_eq!(partitions(98, 105, 100), vec![(98, 99), (100, 105)]);
+        assert_eq!(partitions(100, 199, 100), vec![(100, 199)]);
+        assert_eq!(partitions(5, 5, 1), vec![(5, 5)]);
+    }
+
+    #[tokio::test]
+    async fn exports_parquet_with_fixed_width_and_decimal_columns() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+        let blocks: Vec<Block> = (99..=101).map(sample_block).collect();
+        let txs: Vec<Transaction> = (99..=101).map(|n| creation_tx(n, n * 1000)).collect();
+        Block::save_batch(&blocks, &conn).await?;
+        Transaction::save_batch(&txs, &conn).await?;
+
+        let out_dir = std::env::temp_dir().join(format!("mevlog-export-{}", uuid::Uuid::new_v4()));
+        let exported = export_tables(
+            &opts(out_dir.clone(), ExportFormat::Parquet, true),
+            &[],
+            &conn,
+        )
+        .await?;
+        assert_eq!(
+            exported,
+            vec![
+                ExportedTable {
+                    name: "blocks".to_string(),
+                    rows: 3,
+                    files: 2
+                },
+                ExportedTable {
+                    name: "transactions".to_string(),
+                    rows: 3,
+                    files: 2
+                },
+            ]
+        );
+
+        let path = out_dir.join("transactions/transactions__00000000100_to_00000000105.parquet");
+        let reader = ParquetRecordBatchReaderBuilder::try_new(fs::File::open(&path)?)?.build()?;
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>()?;
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+
+        let schema = batch.schema();
+        assert_eq!(
+            schema.field_with_name("tx_hash")?.data_type(),
+            &DataType::FixedSizeBinary(32)
+        );
+        assert_eq!(
+            schema.field_with_name("from_address")?.data_type(),
+            &DataType::FixedSizeBinary(20)
+        );
+        assert_eq!(
+            schema.field_with_name("input")?.data_type(),
+            &DataType::Binary
+        );
+
+        let values = batch
+            .column_by_name("value")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::StringArray>()
+            .unwrap();
+        assert_eq!(values.value(0), "100000");
+        assert_eq!(values.value(1), "101000");
+
+        fs::remove_dir_all(&out_dir).ok();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejects_tables_not_keyed_by_block() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let out_dir = std::env::temp_dir().join(format!("mevlog-export-{}", uuid::Uuid::new_v4()));
+        let mut opts = opts(out_dir, ExportFormat::Csv, false);
+        opts.tables = vec!["tokens".to_string()];
+        let err = export_tables(&opts, &[], &conn).await.unwrap_err();
+        assert!(err.to_string().contains("not keyed by block number"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn exports_csv_with_hex_blobs() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+        Block::save_batch(&[sample_block(100)], &conn).await?;
+        Transaction::save_batch(&[creation_tx(100, 1)], &conn).await?;
+
+        let out_dir = std::env::temp_dir().join(format!("mevlog-export-{}", uuid::Uuid::new_v4()));
+        let mut opts = opts(out_dir.clone(), ExportFormat::Csv, false);
+        opts.tables = vec!["transactions".to_string()];
+        export_tables(&opts, &[], &conn).await?;
+
+        let csv = fs::read_to_string(
+            out_dir.join("transactions/transactions__00000000100_to_00000000105.csv"),
+        )?;
+        let mut lines = csv.lines();
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        let row: Vec<&str> = lines.next().unwrap().split(',').collect();
+        let cell = |name: &str| row[header.iter().position(|h| *h == name).unwrap()];
+
+        assert_eq!(cell("to_address"), "");
+        assert_eq!(cell("input"), "0xabcd");
+        assert_eq!(cell("value"), format!("0x{}01", "00".repeat(31)));
+        assert_eq!(cell("success"), "true");
+
+        fs::remove_dir_all(&out_dir).ok();
+        Ok(())
+    }
+}
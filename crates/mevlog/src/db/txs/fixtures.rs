@@ -0,0 +1,159 @@
+//! Row fixtures shared by the txs DB tests, one per table. Tests adjust the
+//! fields they care about with struct update syntax, e.g.
+//! `Transaction { tx_index: 1, ..sample_tx(100) }`.
+
+use revm::primitives::{Address, Bytes, FixedBytes, I256, U256};
+
+use crate::db::txs::models::{
+    access_list_entry::AccessListEntry, authorization::Authorization,
+    balance_change::BalanceChange, block::Block, contract::Contract, log::Log,
+    nft_transfer::NftTransfer, trace::Trace, transaction::Transaction, withdrawal::Withdrawal,
+};
+
+pub(crate) fn sample_block(block_number: u64) -> Block {
+    Block {
+        block_number,
+        block_hash: FixedBytes::<32>::from([0xab; 32]),
+        parent_hash: Some(FixedBytes::<32>::from([0xaa; 32])),
+        miner: Address::from([0x11; 20]),
+        gas_used: 16_000_000,
+        timestamp: 1_693_066_895,
+        base_fee_per_gas: Some(21_721_091_641),
+        blob_gas_used: None,
+        excess_blob_gas: None,
+        gas_limit: None,
+        extra_data: None,
+        size: None,
+        state_root: None,
+        transactions_root: None,
+        difficulty: None,
+        mix_hash: None,
+        parent_beacon_block_root: None,
+    }
+}
+
+/// An ERC20 `transfer` call. `tx_hash` is unique, so txs of several blocks
+/// need their own.
+pub(crate) fn sample_tx(block_number: u64) -> Transaction {
+    Transaction {
+        block_number,
+        tx_index: 0,
+        tx_hash: FixedBytes::<32>::from([0xaa; 32]),
+        nonce: 7,
+        from_address: Address::from([0x11; 20]),
+        to_address: Some(Address::from([0x22; 20])),
+        value: U256::from(1_000_000_000_000_000_000u128),
+        gas_limit: 21_000,
+        gas_used: 21_000,
+        effective_gas_price: 30_000_000_000,
+        gas_price: 30_000_000_000,
+        max_fee_per_gas: 40_000_000_000,
+        max_priority_fee_per_gas: 2_000_000_000,
+        transaction_type: Some(2),
+        success: true,
+        signature_hash: Some(FixedBytes::<4>::from([0xa9, 0x05, 0x9c, 0xbb])),
+        signature: Some("transfer(address,uint256)".to_string()),
+        coinbase_transfer: None,
+        blob_gas_used: None,
+        blob_gas_price: None,
+        max_fee_per_blob_gas: None,
+        input: Some(Bytes::from_static(&[0xa9, 0x05, 0x9c, 0xbb])),
+    }
+}
+
+/// A log with topic0 `0xdd..dd` and a single `uint256` word of data.
+pub(crate) fn sample_log(block_number: u64) -> Log {
+    Log {
+        block_number,
+        tx_index: 0,
+        log_index: 0,
+        address: Address::from([0x11; 20]),
+        topics: vec![FixedBytes::<32>::from([0xdd; 32])],
+        data: U256::from(1u64).to_be_bytes::<32>().to_vec(),
+        erc20_amount: None,
+        signature: None,
+    }
+}
+
+pub(crate) fn sample_access_list_entry(block_number: u64) -> AccessListEntry {
+    AccessListEntry {
+        block_number,
+        tx_index: 0,
+        address: Address::from([0x22; 20]),
+        storage_key: Some(FixedBytes::<32>::from([0x01; 32])),
+    }
+}
+
+pub(crate) fn sample_authorization(block_number: u64) -> Authorization {
+    Authorization {
+        block_number,
+        tx_index: 0,
+        chain_id: U256::from(1u64),
+        address: Address::from([0x22; 20]),
+        nonce: 0,
+        authority: Some(Address::from([0x11; 20])),
+    }
+}
+
+pub(crate) fn sample_withdrawal(block_number: u64) -> Withdrawal {
+    Withdrawal {
+        block_number,
+        withdrawal_index: block_number * 16,
+        validator_index: 1_000,
+        address: Address::from([0x44; 20]),
+        amount_gwei: 17_500_000,
+    }
+}
+
+pub(crate) fn sample_balance_change(block_number: u64) -> BalanceChange {
+    BalanceChange {
+        block_number,
+        tx_index: 0,
+        address: Address::from([0x55; 20]),
+        token: None,
+        delta: I256::try_from(-1_000i64).unwrap(),
+    }
+}
+
+pub(crate) fn sample_trace(block_number: u64) -> Trace {
+    Trace {
+        block_number,
+        tx_index: 0,
+        trace_address: String::new(),
+        depth: 0,
+        call_type: "CALL".to_string(),
+        from_address: Address::from([0x11; 20]),
+        to_address: Some(Address::from([0x22; 20])),
+        value: Some(U256::ZERO),
+        gas_used: 21_000,
+        selector: None,
+        signature: None,
+        success: true,
+    }
+}
+
+pub(crate) fn sample_contract(block_number: u64) -> Contract {
+    Contract {
+        address: Address::from([0x33; 20]),
+        block_number,
+        tx_index: 0,
+        deployer: Address::from([0x11; 20]),
+        create_type: "CREATE".to_string(),
+        code_hash: None,
+    }
+}
+
+pub(crate) fn sample_nft_transfer(block_number: u64) -> NftTransfer {
+    NftTransfer {
+        block_number,
+        tx_index: 0,
+        log_index: 1,
+        batch_index: 0,
+        contract: Address::from([0x44; 20]),
+        standard: "ERC721".to_string(),
+        from_address: Address::from([0x11; 20]),
+        to_address: Address::from([0x22; 20]),
+        token_id: U256::from(1),
+        amount: U256::from(1),
+    }
+}
@@ -0,0 +1,145 @@
+use std::path::Path;
+
+use eyre::{Result, bail};
+use tracing::{info, warn};
+
+use crate::{
+    db::txs::{
+        indexing::{contiguous_ranges, persist_chunk},
+        models::block::Block,
+    },
+    misc::{
+        data_fetch::{coverage_mask, parse_parquet_batch, scan_parquet_ranges},
+        shared_init::SharedDeps,
+    },
+};
+
+/// Outcome of [`import_parquet_dir`]. `from`/`to` span the dataset's `blocks`
+/// files.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportStats {
+    /// Matching parquet files found for the chain, across all data types.
+    pub files: u64,
+    pub from: u64,
+    pub to: u64,
+    pub imported_blocks: u64,
+    /// Blocks already present in the txs DB, left untouched.
+    pub skipped_blocks: u64,
+    /// Blocks covered by a `blocks` file but missing a `transactions` or
+    /// `logs` file; not imported, so a later `index` run can fetch them whole.
+    pub incomplete_blocks: u64,
+}
+
+/// Bulk-loads an existing cryo dataset from `dir` into the txs DB, without any
+/// RPC calls. The imported rows are final: the receipt fees, header fields,
+/// access lists, authorizations and withdrawals that cryo datasets lack are
+/// only fetched by a later run with `--rpc-details`. Picks up the `<chain>__{transactions,logs,blocks}__A_to_B.parquet`
+/// files matching `deps.chain` (the same naming the cryo cache uses) and
+/// imports every block covered by all three data types that is not indexed
+/// yet.
+///
+/// Rows go through the regular indexing path: signatures are resolved against
+/// the sigs DB while parsing, and each `batch_size`-block chunk is persisted
+/// with [`persist_chunk`], which also populates the tracked custom tables.
+/// Files must hold cryo's default columns (`blocks` may add extra ones).
+pub async fn import_parquet_dir(
+    dir: &Path,
+    batch_size: usize,
+    store_input: bool,
+    deps: &SharedDeps,
+) -> Result<ImportStats> {
+    if !dir.is_dir() {
+        bail!("{} is not a directory", dir.display());
+    }
+
+    let chain_name = deps.chain.cryo_cache_dir_name();
+    let tx_ranges = scan_parquet_ranges(dir, &chain_name, "transactions");
+    let log_ranges = scan_parquet_ranges(dir, &chain_name, "logs");
+    let block_ranges = scan_parquet_ranges(dir, &chain_name, "blocks");
+
+    let (Some(from), Some(to)) = (
+        block_ranges.iter().map(|r| r.start).min(),
+        block_ranges.iter().map(|r| r.end).max(),
+    ) else {
+        bail!(
+            "No '{}__blocks__A_to_B.parquet' files found in {}",
+            chain_name,
+            dir.display()
+        );
+    };
+
+    let blocks_mask = coverage_mask(&block_ranges, from, to);
+    let txs_mask = coverage_mask(&tx_ranges, from, to);
+    let logs_mask = coverage_mask(&log_ranges, from, to);
+
+    let mut stats = ImportStats {
+        files: (tx_ranges.len() + log_ranges.len() + block_ranges.len()) as u64,
+        from,
+        to,
+        ..Default::default()
+    };
+
+    let mut importable = vec![];
+    for block_number in Block::missing_blocks(from, to, &deps.txs).await? {
+        let idx = (block_number - from) as usize;
+        if !blocks_mask[idx] {
+            continue;
+        }
+
+        if txs_mask[idx] && logs_mask[idx] {
+            importable.push(block_number);
+        } else {
+            stats.incomplete_blocks += 1;
+        }
+    }
+
+    let in_dataset = blocks_mask.iter().filter(|&&covered| covered).count() as u64;
+    stats.skipped_blocks = in_dataset - importable.len() as u64 - stats.incomplete_blocks;
+
+    if stats.incomplete_blocks > 0 {
+        warn!(
+            "{} block(s) lack a transactions or logs file and were not imported",
+            stats.incomplete_blocks
+        );
+    }
+
+    let chunks: Vec<Vec<u64>> = contiguous_ranges(&importable)
+        .into_iter()
+        .flat_map(|(run_start, run_end)| {
+            let run_blocks: Vec<u64> = (run_start..=run_end).collect();
+            run_blocks
+                .chunks(batch_size)
+                .map(<[u64]>::to_vec)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let total_batches = chunks.len();
+    for (batch_idx, chunk) in chunks.iter().enumerate() {
+        let start_block = *chunk.first().unwrap();
+        let end_block = *chunk.last().unwrap();
+
+        info!(
+            "Importing blocks {}-{} (batch {}/{})",
+            start_block,
+            end_block,
+            batch_idx + 1,
+            total_batches
+        );
+
+        let batch_data = parse_parquet_batch(
+            &tx_ranges,
+            &log_ranges,
+            &block_ranges,
+            start_block,
+            end_block,
+            &deps.sqlite,
+        )
+        .await?;
+        persist_chunk(chunk, &batch_data, store_input, false, deps).await?;
+
+        stats.imported_blocks += chunk.len() as u64;
+    }
+
+    Ok(stats)
+}
@@ -1,21 +1,51 @@
-use eyre::Result;
-use futures_util::{StreamExt, stream};
-use tracing::info;
+use std::{collections::VecDeque, time::Instant};
+
+use eyre::{Result, bail};
+use futures_util::{StreamExt, stream::FuturesUnordered};
+use tracing::{info, warn};
 
 use crate::{
     db::txs::{
         custom_tables,
-        models::{block::Block, log::Log, transaction::Transaction},
+        models::{
+            access_list_entry::AccessListEntry,
+            authorization::Authorization,
+            block::{Block, BlockMarker},
+            contract::Contract,
+            log::Log,
+            nft_transfer::NftTransfer,
+            transaction::Transaction,
+            withdrawal::Withdrawal,
+        },
     },
     misc::{
-        data_fetch::{fetch_blocks_batch, prune_indexed_cache},
+        args_parsing::{BlockSelection, BlocksRange},
+        data_fetch::{BatchedBlockData, fetch_blocks_batch, prune_indexed_cache},
+        rpc_details::backfill_rpc_details,
+        rpc_pool::{BENCH_DURATION, RpcPool},
         shared_init::{CryoOpts, SharedDeps},
+        token_metadata::backfill_tokens,
     },
+    models::json::index_response::RpcEndpointJson,
 };
 
-/// Indexes every block in `from..=to` that is not already in the local store,
+/// Indexes every block in `from..=to` that is not already in the local store.
+/// See [`index_block_selection`].
+pub async fn index_block_range(
+    from: u64,
+    to: u64,
+    batch_size: usize,
+    deps: &SharedDeps,
+    cryo_opts: &CryoOpts,
+) -> Result<(u64, u64)> {
+    let selection = BlockSelection::from(BlocksRange { from, to });
+    let indexed = index_block_selection(&selection, batch_size, deps, cryo_opts).await?;
+    Ok((indexed.cached_blocks, indexed.new_blocks))
+}
+
+/// Indexes every selected block that is not already in the local store,
 /// fetching missing blocks in contiguous runs of `batch_size` and persisting
-/// their txs, logs, and block rows. Returns `(cached_blocks, new_blocks)`.
+/// their txs, logs, and block rows. Overlapping segments are indexed once.
 ///
 /// Backfill proceeds newest-block-first, so the most recent blocks become
 /// queryable first and an interrupted backfill leaves the gap at the bottom of
@@ -28,20 +58,31 @@ use crate::{
 /// blocks are still recorded as indexed.
 ///
 /// When `deps.rpc_urls` holds more than one endpoint (multiple `--rpc-url`
-/// flags), chunks are fetched concurrently with one cryo process per endpoint
-/// (round-robin), while persistence stays single-writer. With a single endpoint
-/// the pool collapses to a sequential fetch-then-persist loop, preserving the
-/// original newest-first ordering and behavior.
-pub async fn index_block_range(
-    from: u64,
-    to: u64,
+/// flags), chunks are fetched concurrently with one fetcher (a cryo process or
+/// the native RPC fetcher, see [`CryoOpts::fetcher`]) per endpoint, while
+/// persistence stays single-writer. An endpoint takes the next chunk as soon as
+/// it is done, so faster ones take more. A failed chunk is retried on an
+/// endpoint it hasn't failed on yet, and the run only aborts once it failed on
+/// all of them; an endpoint that keeps failing is benched for a while (see
+/// [`RpcPool`]). With a single endpoint the pool collapses to a sequential
+/// fetch-then-persist loop, preserving the original newest-first ordering and
+/// behavior.
+pub async fn index_block_selection(
+    selection: &BlockSelection,
     batch_size: usize,
     deps: &SharedDeps,
     cryo_opts: &CryoOpts,
-) -> Result<(u64, u64)> {
-    let missing = Block::missing_blocks(from, to, &deps.txs).await?;
+) -> Result<IndexedBlocks> {
+    let segments = selection.merged();
+
+    // Merged segments are disjoint and ascending, so `missing` comes out sorted
+    // and deduplicated, as `contiguous_ranges` expects.
+    let mut missing = vec![];
+    for segment in &segments {
+        missing.extend(Block::missing_blocks(segment.from, segment.to, &deps.txs).await?);
+    }
 
-    let range_size = to.saturating_sub(from) + 1;
+    let range_size = selection.size();
     let new_blocks = missing.len() as u64;
     let cached_blocks = range_size.saturating_sub(new_blocks);
 
@@ -63,84 +104,260 @@ pub async fn index_block_range(
     }
 
     let total_batches = chunks.len();
+    let fetcher = cryo_opts.block_fetcher()?;
+    let store_input = cryo_opts.store_input()?;
+    let rpc_details = cryo_opts.rpc_details()?;
+    let token_metadata = cryo_opts.token_metadata()?;
 
-    // One cryo process per endpoint; a single endpoint yields concurrency 1,
-    // i.e. the original sequential fetch-then-persist behavior.
-    let rpc_urls = &deps.rpc_urls;
-    let workers = rpc_urls.len();
-
-    let mut fetch_stream = stream::iter(chunks.into_iter().enumerate().map(|(i, chunk)| {
-        let rpc_url = rpc_urls[i % workers].clone();
-        async move {
-            let start_block = *chunk.first().unwrap();
-            let end_block = *chunk.last().unwrap();
-            let batch_data = fetch_blocks_batch(
-                start_block,
-                end_block,
-                &rpc_url,
-                &deps.chain,
-                &deps.sqlite,
-                cryo_opts,
-            )
-            .await?;
-            Ok::<_, eyre::Error>((chunk, start_block, end_block, batch_data))
-        }
-    }))
-    .buffer_unordered(workers);
+    // Each endpoint fetches one chunk at a time and is handed the next queued
+    // chunk when it finishes; a single endpoint yields the original
+    // sequential fetch-then-persist behavior.
+    let mut pool = RpcPool::new(&deps.rpc_urls);
+    let mut queue: VecDeque<PendingChunk> = chunks
+        .into_iter()
+        .map(|blocks| PendingChunk {
+            blocks,
+            failed_on: vec![],
+        })
+        .collect();
+    let mut in_flight = FuturesUnordered::new();
 
     let mut batch_idx = 0;
-    while let Some(result) = fetch_stream.next().await {
-        let (chunk, start_block, end_block, batch_data) = result?;
+    loop {
+        let now = Instant::now();
+        for endpoint in pool.idle(now) {
+            // Newest chunk this endpoint hasn't already failed.
+            let Some(pos) = queue
+                .iter()
+                .position(|pending| !pending.failed_on.contains(&endpoint))
+            else {
+                continue;
+            };
+            let pending = queue.remove(pos).expect("position is in bounds");
+            pool.start(endpoint);
+
+            let rpc_url = pool.rpc_url(endpoint).to_string();
+            in_flight.push(async move {
+                let result = fetch_blocks_batch(
+                    pending.start_block(),
+                    pending.end_block(),
+                    &rpc_url,
+                    &deps.chain,
+                    &deps.sqlite,
+                    cryo_opts,
+                    fetcher,
+                )
+                .await;
+                (endpoint, pending, result)
+            });
+        }
+
+        let Some((endpoint, mut pending, result)) = in_flight.next().await else {
+            if queue.is_empty() {
+                break;
+            }
+            // Every endpoint a queued chunk may still go to is benched.
+            let Some(until) = pool.next_unbenched(now) else {
+                bail!("No RPC endpoint left to fetch the remaining blocks");
+            };
+            tokio::time::sleep_until(until.into()).await;
+            continue;
+        };
+
+        let batch_data = match result {
+            Ok(batch_data) => {
+                pool.record_success(endpoint);
+                batch_data
+            }
+            Err(e) => {
+                if pool.record_failure(endpoint, Instant::now()) {
+                    warn!(
+                        "Benching RPC endpoint {} for {}s after repeated failures",
+                        pool.label(endpoint),
+                        BENCH_DURATION.as_secs()
+                    );
+                }
+                pending.failed_on.push(endpoint);
+                if pending.failed_on.len() == pool.len() {
+                    return Err(e.wrap_err(format!(
+                        "Fetching blocks {}-{} failed on every RPC endpoint",
+                        pending.start_block(),
+                        pending.end_block()
+                    )));
+                }
+                warn!(
+                    "Fetching blocks {}-{} from RPC endpoint {} failed, retrying on another: {}",
+                    pending.start_block(),
+                    pending.end_block(),
+                    pool.label(endpoint),
+                    e
+                );
+                queue.push_front(pending);
+                continue;
+            }
+        };
 
         batch_idx += 1;
         info!(
             "Indexing blocks {}-{} (batch {}/{})",
-            start_block, end_block, batch_idx, total_batches
+            pending.start_block(),
+            pending.end_block(),
+            batch_idx,
+            total_batches
         );
 
-        let mut chunk_txs: Vec<Transaction> = vec![];
-        for &block_number in &chunk {
-            if let Some(txs) = batch_data.txs_by_block.get(&block_number) {
-                chunk_txs.extend(txs.iter().cloned());
-            }
-        }
+        persist_chunk(&pending.blocks, &batch_data, store_input, false, deps).await?;
+    }
 
-        let mut chunk_logs: Vec<Log> = vec![];
-        for &block_number in &chunk {
-            if let Some(logs) = batch_data.logs_by_block.get(&block_number) {
-                chunk_logs.extend(logs.iter().cloned());
-            }
+    let mut pruned = 0;
+    for segment in &segments {
+        // Token metadata is opt-in and best-effort: an RPC hiccup must not
+        // fail indexing, and tokens left unresolved are retried on the next
+        // run over their blocks.
+        if token_metadata
+            && let Err(e) = backfill_tokens(segment.from, segment.to, deps, cryo_opts).await
+        {
+            warn!("token metadata backfill failed: {e}");
         }
 
-        let mut chunk_blocks: Vec<Block> = vec![];
-        for &block_number in &chunk {
-            if let Some(block) = batch_data.blocks_by_block.get(&block_number) {
-                chunk_blocks.push(block.clone());
-            }
+        // Blocks from cryo or `import-parquet` lack receipt fees, some header
+        // fields, access lists, authorizations and withdrawals. Fetching them
+        // doubles the RPC cost of a block, so only on request; failed chunks
+        // are retried likewise.
+        if rpc_details
+            && let Err(e) = backfill_rpc_details(segment.from, segment.to, deps, cryo_opts).await
+        {
+            warn!("RPC details backfill failed: {e}");
         }
 
-        Log::save_batch(&chunk_logs, &deps.txs).await?;
-        // Custom tables derive from the logs rows just written; populating
-        // here keeps decoding in SQL with no second decode path.
-        custom_tables::populate_range(&deps.custom_tables, start_block, end_block, &deps.txs)
-            .await?;
-        Transaction::save_batch(&chunk_txs, &deps.txs).await?;
-        Block::save_batch(&chunk_blocks, &deps.txs).await?;
+        // Drop cryo parquet now fully captured in the txs DB; missing_blocks is
+        // the source of truth, so cache for indexed blocks is never read again.
+        pruned += prune_indexed_cache(&deps.chain, &deps.txs, segment.from, segment.to).await?;
     }
-
-    // Drop cryo parquet now fully captured in the txs DB; missing_blocks is the
-    // source of truth, so cache for indexed blocks is never read again.
-    let pruned = prune_indexed_cache(&deps.chain, &deps.txs, from, to).await?;
     if pruned > 0 {
         info!("Pruned {} cached parquet file(s)", pruned);
     }
 
-    Ok((cached_blocks, new_blocks))
+    Ok(IndexedBlocks {
+        cached_blocks,
+        new_blocks,
+        endpoints: pool.report(),
+    })
+}
+
+/// Outcome of [`index_block_selection`].
+#[derive(Debug)]
+pub struct IndexedBlocks {
+    /// Selected blocks that were already in the store.
+    pub cached_blocks: u64,
+    /// Selected blocks fetched by this run.
+    pub new_blocks: u64,
+    /// Chunks each RPC endpoint fetched and failed.
+    pub endpoints: Vec<RpcEndpointJson>,
+}
+
+/// A batch of ascending block numbers waiting to be fetched, with the
+/// endpoints it already failed on so a retry goes elsewhere.
+struct PendingChunk {
+    blocks: Vec<u64>,
+    failed_on: Vec<usize>,
+}
+
+impl PendingChunk {
+    fn start_block(&self) -> u64 {
+        self.blocks[0]
+    }
+
+    fn end_block(&self) -> u64 {
+        self.blocks[self.blocks.len() - 1]
+    }
+}
+
+/// Saves one fetched chunk: logs (plus the custom tables derived from them),
+/// then txs with their access list entries and authorizations, then the
+/// blocks' withdrawals and block rows. Blocks go last since a `blocks` row marks a
+/// block as indexed. `chunk` holds ascending block numbers; with `store_input`
+/// unset, tx calldata is dropped before saving. With `logs_only` set, the
+/// block rows are flagged as holding only the txs matching a log filter.
+/// Blocks fetched with their RPC-only details are marked
+/// [`BlockMarker::RpcDetails`] so [`backfill_rpc_details`] skips them.
+pub(crate) async fn persist_chunk(
+    chunk: &[u64],
+    batch_data: &BatchedBlockData,
+    store_input: bool,
+    logs_only: bool,
+    deps: &SharedDeps,
+) -> Result<()> {
+    let (Some(&start_block), Some(&end_block)) = (chunk.first(), chunk.last()) else {
+        return Ok(());
+    };
+
+    let mut chunk_txs: Vec<Transaction> = vec![];
+    for &block_number in chunk {
+        if let Some(txs) = batch_data.txs_by_block.get(&block_number) {
+            chunk_txs.extend(txs.iter().cloned());
+        }
+    }
+    if !store_input {
+        for tx in &mut chunk_txs {
+            tx.input = None;
+        }
+    }
+
+    let mut chunk_logs: Vec<Log> = vec![];
+    for &block_number in chunk {
+        if let Some(logs) = batch_data.logs_by_block.get(&block_number) {
+            chunk_logs.extend(logs.iter().cloned());
+        }
+    }
+
+    let mut chunk_access_list_entries: Vec<AccessListEntry> = vec![];
+    let mut chunk_authorizations: Vec<Authorization> = vec![];
+    for &block_number in chunk {
+        if let Some(entries) = batch_data.access_list_entries_by_block.get(&block_number) {
+            chunk_access_list_entries.extend(entries.iter().cloned());
+        }
+        if let Some(authorizations) = batch_data.authorizations_by_block.get(&block_number) {
+            chunk_authorizations.extend(authorizations.iter().cloned());
+        }
+    }
+
+    let mut chunk_withdrawals: Vec<Withdrawal> = vec![];
+    let mut chunk_blocks: Vec<Block> = vec![];
+    for &block_number in chunk {
+        if let Some(withdrawals) = batch_data.withdrawals_by_block.get(&block_number) {
+            chunk_withdrawals.extend(withdrawals.iter().cloned());
+        }
+        if let Some(block) = batch_data.blocks_by_block.get(&block_number) {
+            chunk_blocks.push(block.clone());
+        }
+    }
+
+    Log::save_batch(&chunk_logs, &deps.txs).await?;
+    NftTransfer::save_batch(&NftTransfer::from_logs(&chunk_logs), &deps.txs).await?;
+    // Custom tables derive from the logs rows just written; populating
+    // here keeps decoding in SQL with no second decode path.
+    custom_tables::populate_range(&deps.custom_tables, start_block, end_block, &deps.txs).await?;
+    Transaction::save_batch(&chunk_txs, &deps.txs).await?;
+    Contract::save_batch(&Contract::from_transactions(&chunk_txs), &deps.txs).await?;
+    AccessListEntry::save_batch(&chunk_access_list_entries, &deps.txs).await?;
+    Authorization::save_batch(&chunk_authorizations, &deps.txs).await?;
+    Withdrawal::save_batch(&chunk_withdrawals, &deps.txs).await?;
+    if logs_only {
+        Block::save_batch_logs_only(&chunk_blocks, &deps.txs).await?;
+    } else {
+        Block::save_batch(&chunk_blocks, &deps.txs).await?;
+        if batch_data.rpc_details {
+            Block::mark_batch(BlockMarker::RpcDetails, chunk, &deps.txs).await?;
+        }
+    }
+
+    Ok(())
 }
 
 /// Collapses a sorted, deduplicated list of block numbers into contiguous
 /// `(start, end)` inclusive ranges so each gap is fetched as a single batch.
-fn contiguous_ranges(blocks: &[u64]) -> Vec<(u64, u64)> {
+pub(crate) fn contiguous_ranges(blocks: &[u64]) -> Vec<(u64, u64)> {
     let mut ranges: Vec<(u64, u64)> = vec![];
 
     for &block in blocks {
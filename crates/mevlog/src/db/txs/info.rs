@@ -1,13 +1,25 @@
 use eyre::Result;
 use sqlx::{Row, SqlitePool};
 
+use crate::db::shared::{column_exists, table_exists};
+
 /// Summary of the local txs DB contents. Block range fields are `None` when
-/// the DB has no indexed blocks.
+/// the DB has no indexed blocks. Blocks saved by `index --logs-only` are
+/// counted apart and left out of the block range, as only some of their txs
+/// are stored.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct DbInfoStats {
     pub blocks: u64,
+    /// Blocks holding only the txs matching an `index --logs-only` filter.
+    pub logs_only_blocks: u64,
     pub transactions: u64,
     pub logs: u64,
+    /// Beacon withdrawals; `0` for DBs created before the table existed.
+    pub withdrawals: u64,
+    /// Txs with stored calldata (`transactions.input IS NOT NULL`).
+    pub transactions_with_input: u64,
+    /// Total calldata bytes stored in `transactions.input`.
+    pub input_bytes: u64,
     pub min_block: Option<u64>,
     pub max_block: Option<u64>,
     pub min_block_timestamp: Option<u64>,
@@ -18,9 +30,16 @@ pub struct DbInfoStats {
 
 /// Collects row counts and the indexed block range from the local txs DB.
 pub async fn db_info(conn: &SqlitePool) -> Result<DbInfoStats> {
-    let row = sqlx::query(
-        "SELECT COUNT(*), MIN(block_number), MAX(block_number), MIN(timestamp), MAX(timestamp) FROM blocks",
-    )
+    // DBs opened read-only here may predate the `logs_only` column.
+    let has_logs_only = column_exists("blocks", "logs_only", conn).await?;
+    let full_blocks = if has_logs_only {
+        "WHERE logs_only = 0"
+    } else {
+        ""
+    };
+    let row = sqlx::query(sqlx::AssertSqlSafe(format!(
+        "SELECT COUNT(*), MIN(block_number), MAX(block_number), MIN(timestamp), MAX(timestamp) FROM blocks {full_blocks}"
+    )))
     .fetch_one(conn)
     .await?;
 
@@ -36,6 +55,35 @@ pub async fn db_info(conn: &SqlitePool) -> Result<DbInfoStats> {
     let logs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM logs")
         .fetch_one(conn)
         .await?;
+    let logs_only_blocks: i64 = if has_logs_only {
+        sqlx::query_scalar("SELECT COUNT(*) FROM blocks WHERE logs_only = 1")
+            .fetch_one(conn)
+            .await?
+    } else {
+        0
+    };
+
+    // DBs opened read-only here may predate the `withdrawals` table and the
+    // `input` column.
+    let withdrawals: i64 = if table_exists("withdrawals", conn).await? {
+        sqlx::query_scalar("SELECT COUNT(*) FROM withdrawals")
+            .fetch_one(conn)
+            .await?
+    } else {
+        0
+    };
+
+    let (transactions_with_input, input_bytes): (i64, i64) =
+        if column_exists("transactions", "input", conn).await? {
+            let row = sqlx::query(
+                "SELECT COUNT(input), COALESCE(SUM(LENGTH(input)), 0) FROM transactions",
+            )
+            .fetch_one(conn)
+            .await?;
+            (row.get(0), row.get(1))
+        } else {
+            (0, 0)
+        };
 
     let missing_blocks = match (min_block, max_block) {
         (Some(min), Some(max)) => (max - min + 1) as u64 - blocks as u64,
@@ -44,8 +92,12 @@ pub async fn db_info(conn: &SqlitePool) -> Result<DbInfoStats> {
 
     Ok(DbInfoStats {
         blocks: blocks as u64,
+        logs_only_blocks: logs_only_blocks as u64,
         transactions: transactions as u64,
         logs: logs as u64,
+        withdrawals: withdrawals as u64,
+        transactions_with_input: transactions_with_input as u64,
+        input_bytes: input_bytes as u64,
         min_block: min_block.map(|b| b as u64),
         max_block: max_block.map(|b| b as u64),
         min_block_timestamp: min_block_timestamp.map(|t| t as u64),
@@ -0,0 +1,569 @@
+use alloy::{providers::Provider, rpc::types::Filter};
+use eyre::{Result, bail, eyre};
+use revm::primitives::{Address, B256};
+use tracing::{debug, info, warn};
+
+use crate::{
+    db::txs::{indexing::persist_chunk, models::log_filter_coverage::LogFilterCoverage},
+    misc::{
+        args_parsing::BlockSelection,
+        rpc_fetch::{fetch_log_txs_batch, is_too_many_results, logs_provider},
+        shared_init::{CryoOpts, SharedDeps},
+        token_metadata::backfill_tokens,
+    },
+    models::json::index_response::LogsOnlyJson,
+};
+
+/// Blocks covered by the first `eth_getLogs` call of a run.
+const INITIAL_LOGS_SPAN: u64 = 2_000;
+/// Upper bound the span grows to while calls keep succeeding.
+const MAX_LOGS_SPAN: u64 = 100_000;
+
+/// Logs to index with `index --logs-only`, matched as `eth_getLogs` does: a
+/// log matches when its address is one of `addresses` and each topic is one of
+/// the values given for its position. An empty list matches anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFilter {
+    addresses: Vec<Address>,
+    topics: [Vec<B256>; 4],
+}
+
+impl LogFilter {
+    /// Requires an address or a `topic0`, so a scan can't pull every log of
+    /// the range.
+    pub fn new(mut addresses: Vec<Address>, mut topics: [Vec<B256>; 4]) -> Result<Self> {
+        if addresses.is_empty() && topics[0].is_empty() {
+            bail!("--logs-only requires --address or --topic0");
+        }
+
+        addresses.sort();
+        addresses.dedup();
+        for topic in &mut topics {
+            topic.sort();
+            topic.dedup();
+        }
+
+        Ok(Self { addresses, topics })
+    }
+
+    /// Canonical form recorded with the scanned ranges, e.g.
+    /// `address=0xa0b8…;topic0=0xddf2…;topic1=;topic2=;topic3=`. Filters
+    /// matching the same logs share a key regardless of flag order.
+    pub(crate) fn key(&self) -> String {
+        let join = |values: Vec<String>| values.join(",");
+        let mut parts = vec![format!(
+            "address={}",
+            join(self.addresses.iter().map(|a| format!("{a:#x}")).collect())
+        )];
+        for (idx, topic) in self.topics.iter().enumerate() {
+            parts.push(format!(
+                "topic{idx}={}",
+                join(topic.iter().map(|t| format!("{t:#x}")).collect())
+            ));
+        }
+        parts.join(";")
+    }
+
+    fn to_rpc(&self, from: u64, to: u64) -> Filter {
+        let [topic0, topic1, topic2, topic3] = self.topics.clone();
+        Filter::new()
+            .from_block(from)
+            .to_block(to)
+            .address(self.addresses.clone())
+            .event_signature(topic0)
+            .topic1(topic1)
+            .topic2(topic2)
+            .topic3(topic3)
+    }
+}
+
+/// Outcome of [`index_logs_only`].
+#[derive(Debug)]
+pub struct IndexedLogs {
+    /// Selected blocks an earlier run already scanned for the filter.
+    pub cached_blocks: u64,
+    /// Selected blocks scanned by this run.
+    pub new_blocks: u64,
+    pub matches: LogsOnlyJson,
+}
+
+/// Indexes only the logs matching `filter` within the selection, plus the txs
+/// that emitted them (with all their logs) and their blocks' headers. Meant
+/// for ranges far too large to index block by block: blocks without a match
+/// cost nothing beyond their share of an `eth_getLogs` call.
+///
+/// Each gap not yet scanned for the filter (see [`LogFilterCoverage`]) is
+/// walked newest-first in spans that adapt to the provider's limits: a span
+/// doubles (up to [`MAX_LOGS_SPAN`]) after a successful call and is halved when
+/// the provider rejects it for matching too many logs. A single block that
+/// still matches too many fails the run.
+///
+/// Matched blocks are saved flagged `logs_only`, so regular indexing still
+/// treats them as missing and later fills in their other txs. A span is
+/// recorded as scanned only once its matches are saved, so an interrupted run
+/// resumes where it stopped.
+pub async fn index_logs_only(
+    selection: &BlockSelection,
+    filter: &LogFilter,
+    deps: &SharedDeps,
+    cryo_opts: &CryoOpts,
+) -> Result<IndexedLogs> {
+    let key = filter.key();
+    let provider = logs_provider(&deps.rpc_url, cryo_opts)?;
+    let store_input = cryo_opts.store_input()?;
+
+    let segments = selection.merged();
+    let mut gaps = vec![];
+    for segment in &segments {
+        gaps.extend(LogFilterCoverage::uncovered(&key, segment.from, segment.to, &deps.txs).await?);
+    }
+
+    let range_size = selection.size();
+    let new_blocks: u64 = gaps.iter().map(|(from, to)| to - from + 1).sum();
+    let cached_blocks = range_size.saturating_sub(new_blocks);
+    info!(
+        "Blocks: {} already scanned, {} to scan for {} ({} total)",
+        cached_blocks, new_blocks, key, range_size
+    );
+
+    let mut matches = LogsOnlyJson {
+        filter: key.clone(),
+        matched_logs: 0,
+        matched_txs: 0,
+        matched_blocks: 0,
+        get_logs_calls: 0,
+    };
+    let mut span = INITIAL_LOGS_SPAN;
+
+    for &(gap_from, gap_to) in gaps.iter().rev() {
+        let mut to = gap_to;
+        loop {
+            let from = to.saturating_sub(span - 1).max(gap_from);
+
+            matches.get_logs_calls += 1;
+            let logs = match provider.get_logs(&filter.to_rpc(from, to)).await {
+                Ok(logs) => logs,
+                Err(e) if is_too_many_results(&e) && from < to => {
+                    span = (to - from + 1) / 2;
+                    debug!("eth_getLogs rejected blocks {from}-{to}, retrying {span} blocks: {e}");
+                    continue;
+                }
+                Err(e) => {
+                    return Err(
+                        eyre!(e).wrap_err(format!("eth_getLogs for blocks {from}-{to} failed"))
+                    );
+                }
+            };
+
+            let batch_data = fetch_log_txs_batch(&logs, &provider, &deps.sqlite, cryo_opts).await?;
+            let mut matched_blocks: Vec<u64> = batch_data.blocks_by_block.keys().copied().collect();
+            matched_blocks.sort_unstable();
+            persist_chunk(&matched_blocks, &batch_data, store_input, true, deps).await?;
+            LogFilterCoverage {
+                filter: key.clone(),
+                from_block: from,
+                to_block: to,
+            }
+            .save(&deps.txs)
+            .await?;
+
+            matches.matched_logs += logs.len() as u64;
+            matches.matched_txs += batch_data
+                .txs_by_block
+                .values()
+                .map(Vec::len)
+                .sum::<usize>() as u64;
+            matches.matched_blocks += matched_blocks.len() as u64;
+            info!(
+                "Scanned blocks {}-{}: {} matching log(s) in {} block(s)",
+                from,
+                to,
+                logs.len(),
+                matched_blocks.len()
+            );
+
+            span = (span * 2).min(MAX_LOGS_SPAN);
+            if from == gap_from {
+                break;
+            }
+            to = from - 1;
+        }
+    }
+
+    if cryo_opts.token_metadata()? {
+        for segment in &segments {
+            // Best-effort, as in block indexing.
+            if let Err(e) = backfill_tokens(segment.from, segment.to, deps, cryo_opts).await {
+                warn!("token metadata backfill failed: {e}");
+            }
+        }
+    }
+
+    Ok(IndexedLogs {
+        cached_blocks,
+        new_blocks,
+        matches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::{
+        db::{
+            sigs::models::event::test::{SqliteCleaner as SigsCleaner, setup_test_db},
+            txs::models::{
+                block::Block,
+                log::Log,
+                transaction::test::{SqliteCleaner, setup_test_db_rw},
+            },
+        },
+        misc::{
+            args_parsing::BlocksRange,
+            mock_node::{MockResponse, mock_node},
+            shared_init::init_provider,
+        },
+        models::evm_chain::EVMChain,
+    };
+
+    const TRANSFER: &str = "0xdddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd";
+    const EMITTER: &str = "0xa0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0";
+    const SENDER: &str = "0x1111111111111111111111111111111111111111";
+    const TX_HASH: &str = "0x7777777777777777777777777777777777777777777777777777777777777777";
+    const BLOCK_HASH: &str = "0x8888888888888888888888888888888888888888888888888888888888888888";
+    const ZERO_HASH: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+    /// The only block with a log matching [`filter`].
+    const MATCH_BLOCK: u64 = 150;
+
+    /// `eth_getLogs` node recording the ranges it is asked for. Rejects a
+    /// range wider than `max_span` blocks as matching too many logs, fails
+    /// one covering `failing_block`, and otherwise returns the log of
+    /// [`MATCH_BLOCK`] when in range, along with its tx, receipt and block.
+    struct LogsNode {
+        max_span: u64,
+        failing_block: Mutex<Option<u64>>,
+        calls: Mutex<Vec<(u64, u64)>>,
+    }
+
+    impl LogsNode {
+        fn new(max_span: u64, failing_block: Option<u64>) -> Arc<Self> {
+            Arc::new(Self {
+                max_span,
+                failing_block: Mutex::new(failing_block),
+                calls: Mutex::new(vec![]),
+            })
+        }
+
+        fn take_calls(&self) -> Vec<(u64, u64)> {
+            std::mem::take(&mut self.calls.lock().unwrap())
+        }
+
+        fn answer(&self, method: &str, params: &Value) -> MockResponse {
+            let number = |value: &Value| {
+                let hex = value.as_str().unwrap_or_default().trim_start_matches("0x");
+                u64::from_str_radix(hex, 16).unwrap_or_default()
+            };
+            match method {
+                "eth_getLogs" => {
+                    let (from, to) = (
+                        number(&params[0]["fromBlock"]),
+                        number(&params[0]["toBlock"]),
+                    );
+                    self.calls.lock().unwrap().push((from, to));
+                    if to - from + 1 > self.max_span {
+                        return Err(json!({
+                            "code": -32005,
+                            "message": "query returned more than 10000 results",
+                        }));
+                    }
+                    if self
+                        .failing_block
+                        .lock()
+                        .unwrap()
+                        .is_some_and(|block| (from..=to).contains(&block))
+                    {
+                        return Err(json!({ "code": -32000, "message": "missing trie node" }));
+                    }
+                    if (from..=to).contains(&MATCH_BLOCK) {
+                        Ok(json!([matching_log()]))
+                    } else {
+                        Ok(json!([]))
+                    }
+                }
+                "eth_getBlockByNumber" => Ok(json!({
+                    "hash": BLOCK_HASH,
+                    "parentHash": ZERO_HASH,
+                    "sha3Uncles": ZERO_HASH,
+                    "miner": SENDER,
+                    "stateRoot": ZERO_HASH,
+                    "transactionsRoot": ZERO_HASH,
+                    "receiptsRoot": ZERO_HASH,
+                    "logsBloom": format!("0x{}", "00".repeat(256)),
+                    "difficulty": "0x0",
+                    "number": format!("{MATCH_BLOCK:#x}"),
+                    "gasLimit": "0x1c9c380",
+                    "gasUsed": "0x5208",
+                    "timestamp": "0x64",
+                    "extraData": "0x",
+                    "mixHash": ZERO_HASH,
+                    "nonce": "0x0000000000000000",
+                    "baseFeePerGas": "0x7",
+                    "uncles": [],
+                    "transactions": [TX_HASH],
+                })),
+                "eth_getTransactionByHash" => Ok(json!({
+                    "hash": TX_HASH,
+                    "type": "0x2",
+                    "chainId": "0x1",
+                    "nonce": "0x0",
+                    "blockHash": BLOCK_HASH,
+                    "blockNumber": format!("{MATCH_BLOCK:#x}"),
+                    "transactionIndex": "0x0",
+                    "from": SENDER,
+                    "to": EMITTER,
+                    "value": "0x0",
+                    "gas": "0x5208",
+                    "gasPrice": "0x8",
+                    "maxFeePerGas": "0x3b9aca00",
+                    "maxPriorityFeePerGas": "0x1",
+                    "input": "0x",
+                    "accessList": [],
+                    "yParity": "0x0",
+                    "v": "0x0",
+                    "r": "0x1",
+                    "s": "0x1",
+                })),
+                "eth_getTransactionReceipt" => Ok(json!({
+                    "transactionHash": TX_HASH,
+                    "transactionIndex": "0x0",
+                    "blockHash": BLOCK_HASH,
+                    "blockNumber": format!("{MATCH_BLOCK:#x}"),
+                    "from": SENDER,
+                    "to": EMITTER,
+                    "gasUsed": "0x5208",
+                    "cumulativeGasUsed": "0x5208",
+                    "effectiveGasPrice": "0x8",
+                    "contractAddress": null,
+                    "logs": [matching_log()],
+                    "logsBloom": format!("0x{}", "00".repeat(256)),
+                    "type": "0x2",
+                    "status": "0x1",
+                })),
+                _ => Ok(json!("0x1")),
+            }
+        }
+    }
+
+    fn matching_log() -> Value {
+        json!({
+            "address": EMITTER,
+            "topics": [TRANSFER],
+            "data": "0x",
+            "blockNumber": format!("{MATCH_BLOCK:#x}"),
+            "blockHash": BLOCK_HASH,
+            "transactionHash": TX_HASH,
+            "transactionIndex": "0x0",
+            "logIndex": "0x0",
+            "removed": false,
+        })
+    }
+
+    fn filter() -> LogFilter {
+        LogFilter::new(
+            vec![EMITTER.parse().unwrap()],
+            [vec![TRANSFER.parse().unwrap()], vec![], vec![], vec![]],
+        )
+        .unwrap()
+    }
+
+    /// Deps reading from `node`, with fresh txs and sigs DBs.
+    async fn setup(node: Arc<LogsNode>) -> Result<(SharedDeps, SqliteCleaner, SigsCleaner)> {
+        let rpc_url = mock_node(move |method, params| node.answer(method, params)).await?;
+        let (txs, txs_read_path, txs_cl) = setup_test_db_rw().await;
+        let (sqlite, sigs_cl) = setup_test_db().await;
+        let deps = SharedDeps {
+            sqlite,
+            txs,
+            txs_read_path,
+            provider: Arc::new(init_provider(&rpc_url).await?),
+            chain: Arc::new(EVMChain {
+                chain_id: 1,
+                name: "ethereum".to_string(),
+                explorer_url: None,
+                currency_symbol: "ETH".to_string(),
+                chainlink_oracle: None,
+                rpc_url: rpc_url.clone(),
+            }),
+            rpc_urls: vec![rpc_url.clone()],
+            rpc_url,
+            custom_tables: vec![],
+        };
+        Ok((deps, txs_cl, sigs_cl))
+    }
+
+    fn selection(from: u64, to: u64) -> BlockSelection {
+        BlockSelection::from(BlocksRange { from, to })
+    }
+
+    fn cryo_opts() -> CryoOpts {
+        CryoOpts {
+            cryo_max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_log_filter_key_ignores_flag_order_and_duplicates() -> Result<()> {
+        let usdc = Address::new([0xa0; 20]);
+        let weth = Address::new([0xc0; 20]);
+        let transfer = B256::new([0xdd; 32]);
+
+        let filter = LogFilter::new(
+            vec![weth, usdc, weth],
+            [vec![transfer], vec![], vec![], vec![]],
+        )?;
+        let reordered = LogFilter::new(
+            vec![usdc, weth],
+            [vec![transfer, transfer], vec![], vec![], vec![]],
+        )?;
+
+        assert_eq!(filter.key(), reordered.key());
+        assert_eq!(
+            filter.key(),
+            format!("address={usdc:#x},{weth:#x};topic0={transfer:#x};topic1=;topic2=;topic3=")
+        );
+        assert!(LogFilter::new(vec![], [vec![], vec![transfer], vec![], vec![]]).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_logs_only_halves_rejected_spans() -> Result<()> {
+        let node = LogsNode::new(3_000, None);
+        let (deps, _txs_cl, _sigs_cl) = setup(node.clone()).await?;
+
+        let indexed =
+            index_logs_only(&selection(1, 10_000), &filter(), &deps, &cryo_opts()).await?;
+
+        // Each doubled span is rejected and retried at half its size.
+        assert_eq!(
+            node.take_calls(),
+            vec![
+                (8_001, 10_000),
+                (4_001, 8_000),
+                (6_001, 8_000),
+                (2_001, 6_000),
+                (4_001, 6_000),
+                (1, 4_000),
+                (2_001, 4_000),
+                (1, 2_000),
+            ]
+        );
+        assert_eq!(indexed.matches.get_logs_calls, 8);
+        assert!(
+            LogFilterCoverage::uncovered(&filter().key(), 1, 10_000, &deps.txs)
+                .await?
+                .is_empty()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_logs_only_caps_span_growth() -> Result<()> {
+        let node = LogsNode::new(u64::MAX, None);
+        let (deps, _txs_cl, _sigs_cl) = setup(node.clone()).await?;
+
+        index_logs_only(&selection(1, 250_000), &filter(), &deps, &cryo_opts()).await?;
+
+        let spans: Vec<u64> = node
+            .take_calls()
+            .iter()
+            .map(|(from, to)| to - from + 1)
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                INITIAL_LOGS_SPAN,
+                4_000,
+                8_000,
+                16_000,
+                32_000,
+                64_000,
+                MAX_LOGS_SPAN,
+                24_000,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_logs_only_resumes_after_the_last_saved_span() -> Result<()> {
+        let node = LogsNode::new(u64::MAX, Some(5_000));
+        let (deps, _txs_cl, _sigs_cl) = setup(node.clone()).await?;
+        let key = filter().key();
+
+        let err = index_logs_only(&selection(1, 10_000), &filter(), &deps, &cryo_opts())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("blocks 4001-8000"), "got: {err}");
+        assert_eq!(node.take_calls(), vec![(8_001, 10_000), (4_001, 8_000)]);
+        // The span scanned before the failure stays recorded.
+        assert_eq!(
+            LogFilterCoverage::uncovered(&key, 1, 10_000, &deps.txs).await?,
+            vec![(1, 8_000)]
+        );
+
+        *node.failing_block.lock().unwrap() = None;
+        let indexed =
+            index_logs_only(&selection(1, 10_000), &filter(), &deps, &cryo_opts()).await?;
+
+        assert_eq!(
+            node.take_calls(),
+            vec![(6_001, 8_000), (2_001, 6_000), (1, 2_000)]
+        );
+        assert_eq!((indexed.cached_blocks, indexed.new_blocks), (2_000, 8_000));
+        assert_eq!(indexed.matches.matched_logs, 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_logs_only_blocks_stay_missing() -> Result<()> {
+        let node = LogsNode::new(u64::MAX, None);
+        let (deps, _txs_cl, _sigs_cl) = setup(node.clone()).await?;
+
+        let indexed = index_logs_only(&selection(100, 200), &filter(), &deps, &cryo_opts()).await?;
+
+        assert_eq!(node.take_calls(), vec![(100, 200)]);
+        assert_eq!(
+            (
+                indexed.matches.matched_logs,
+                indexed.matches.matched_txs,
+                indexed.matches.matched_blocks
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!(Log::count(&deps.txs).await?, 1);
+        // The matched block's header is saved flagged `logs_only`, so block
+        // indexing still fetches it in full.
+        let logs_only: bool =
+            sqlx::query_scalar("SELECT logs_only FROM blocks WHERE block_number = ?")
+                .bind(MATCH_BLOCK as i64)
+                .fetch_one(&deps.txs)
+                .await?;
+        assert!(logs_only);
+        assert!(
+            Block::missing_blocks(100, 200, &deps.txs)
+                .await?
+                .contains(&MATCH_BLOCK)
+        );
+
+        Ok(())
+    }
+}
@@ -1,3 +1,12 @@
+pub mod access_list_entry;
+pub mod authorization;
+pub mod balance_change;
 pub mod block;
+pub mod contract;
 pub mod log;
+pub mod log_filter_coverage;
+pub mod nft_transfer;
+pub mod token;
+pub mod trace;
 pub mod transaction;
+pub mod withdrawal;
@@ -0,0 +1,143 @@
+use alloy::{
+    consensus::Transaction as TxTrait,
+    network::{AnyRpcTransaction, TransactionResponse},
+};
+use eyre::Result;
+use revm::primitives::{Address, FixedBytes};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+
+/// One EIP-2930 access list item: a storage slot the tx pre-declared, or just
+/// the address when the item lists no slots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessListEntry {
+    pub block_number: u64,
+    pub tx_index: u64,
+    pub address: Address,
+    /// `None` for an address listed without storage keys.
+    pub storage_key: Option<FixedBytes<32>>,
+}
+
+#[hotpath::measure_all(future = true)]
+impl AccessListEntry {
+    /// Flattens the access list of an `eth_getBlockByNumber` tx into rows.
+    /// Empty for legacy txs and txs with an empty access list.
+    pub(crate) fn from_rpc(tx: &AnyRpcTransaction, block_number: u64) -> Vec<AccessListEntry> {
+        let tx_index = tx.transaction_index().unwrap_or_default();
+        let Some(access_list) = TxTrait::access_list(tx) else {
+            return vec![];
+        };
+
+        access_list
+            .iter()
+            .flat_map(|item| {
+                let storage_keys: Vec<Option<FixedBytes<32>>> = if item.storage_keys.is_empty() {
+                    vec![None]
+                } else {
+                    item.storage_keys.iter().copied().map(Some).collect()
+                };
+
+                storage_keys
+                    .into_iter()
+                    .map(move |storage_key| AccessListEntry {
+                        block_number,
+                        tx_index,
+                        address: item.address,
+                        storage_key,
+                    })
+            })
+            .collect()
+    }
+
+    pub(crate) async fn save<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        // No natural key: the unique index over every column makes re-saving a
+        // chunk (e.g. after an interrupted run) a no-op.
+        sqlx::query(
+            r#"
+            INSERT INTO access_list_entries (block_number, tx_index, address, storage_key)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(self.block_number as i64)
+        .bind(self.tx_index as i64)
+        .bind(self.address.as_slice())
+        .bind(self.storage_key.as_ref().map(|k| k.as_slice()))
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn save_batch(entries: &[AccessListEntry], conn: &SqlitePool) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        for entry in entries {
+            entry.save(&mut *db_tx).await?;
+        }
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn query_where(where_sql: &str, conn: &SqlitePool) -> Result<Vec<AccessListEntry>> {
+        let sql = format!(
+            "SELECT * FROM access_list_entries WHERE {where_sql} \
+             ORDER BY block_number DESC, tx_index ASC, rowid ASC"
+        );
+
+        let rows = sqlx::query(sqlx::AssertSqlSafe(sql))
+            .fetch_all(conn)
+            .await?;
+        rows.iter().map(Self::from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<AccessListEntry> {
+        let block_number: i64 = row.try_get("block_number")?;
+        let tx_index: i64 = row.try_get("tx_index")?;
+        let address: Vec<u8> = row.try_get("address")?;
+        let storage_key: Option<Vec<u8>> = row.try_get("storage_key")?;
+
+        Ok(AccessListEntry {
+            block_number: block_number as u64,
+            tx_index: tx_index as u64,
+            address: Address::from_slice(&address),
+            storage_key: storage_key.map(|k| FixedBytes::<32>::from_slice(&k)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::txs::{
+        fixtures::sample_access_list_entry, models::transaction::test::setup_test_db,
+    };
+
+    #[tokio::test]
+    async fn save_batch_roundtrips_and_is_idempotent() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let entries = vec![
+            sample_access_list_entry(100),
+            AccessListEntry {
+                storage_key: Some(FixedBytes::<32>::from([0x02; 32])),
+                ..sample_access_list_entry(100)
+            },
+            AccessListEntry {
+                tx_index: 1,
+                storage_key: None,
+                ..sample_access_list_entry(100)
+            },
+        ];
+        AccessListEntry::save_batch(&entries, &conn).await?;
+        AccessListEntry::save_batch(&entries, &conn).await?;
+
+        let found = AccessListEntry::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, entries);
+
+        Ok(())
+    }
+}
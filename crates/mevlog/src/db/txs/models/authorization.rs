@@ -0,0 +1,177 @@
+use alloy::{
+    consensus::Transaction as TxTrait,
+    network::{AnyRpcTransaction, TransactionResponse},
+};
+use eyre::Result;
+use revm::primitives::{Address, U256};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+
+/// One EIP-7702 authorization tuple from a type-4 (set code) tx.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Authorization {
+    pub block_number: u64,
+    pub tx_index: u64,
+    /// Chain the authorization is valid on; `0` means any chain.
+    pub chain_id: U256,
+    /// Contract whose code the authority delegates to.
+    pub address: Address,
+    pub nonce: u64,
+    /// Signer recovered from the tuple's signature. `None` when the signature
+    /// is invalid, in which case the authorization was skipped on-chain.
+    pub authority: Option<Address>,
+}
+
+#[hotpath::measure_all(future = true)]
+impl Authorization {
+    /// Extracts the authorization list of an `eth_getBlockByNumber` tx,
+    /// recovering each authority. Empty for all but type-4 txs.
+    pub(crate) fn from_rpc(tx: &AnyRpcTransaction, block_number: u64) -> Vec<Authorization> {
+        let tx_index = tx.transaction_index().unwrap_or_default();
+        let Some(authorizations) = TxTrait::authorization_list(tx) else {
+            return vec![];
+        };
+
+        authorizations
+            .iter()
+            .map(|auth| Authorization {
+                block_number,
+                tx_index,
+                chain_id: *auth.chain_id(),
+                address: *auth.address(),
+                nonce: auth.nonce(),
+                authority: auth.recover_authority().ok(),
+            })
+            .collect()
+    }
+
+    pub(crate) async fn save<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        // SQLite INTEGER is signed 64-bit, while the tuple's chain_id is a
+        // U256 and its nonce a u64. Skip tuples that don't fit rather than
+        // persist wrapped values; no valid authorization comes close.
+        let (Ok(chain_id), Ok(nonce)) = (i64::try_from(self.chain_id), i64::try_from(self.nonce))
+        else {
+            tracing::warn!(
+                "Skipping authorization in block {} tx {}: chain_id or nonce exceeds i64::MAX, cannot store",
+                self.block_number,
+                self.tx_index
+            );
+            return Ok(());
+        };
+
+        // No natural key: the unique index over every column makes re-saving a
+        // chunk (e.g. after an interrupted run) a no-op.
+        sqlx::query(
+            r#"
+            INSERT INTO authorizations (
+                block_number, tx_index, chain_id, address, nonce, authority
+            ) VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(self.block_number as i64)
+        .bind(self.tx_index as i64)
+        .bind(chain_id)
+        .bind(self.address.as_slice())
+        .bind(nonce)
+        .bind(self.authority.as_ref().map(|a| a.as_slice()))
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn save_batch(
+        authorizations: &[Authorization],
+        conn: &SqlitePool,
+    ) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        for authorization in authorizations {
+            authorization.save(&mut *db_tx).await?;
+        }
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn query_where(where_sql: &str, conn: &SqlitePool) -> Result<Vec<Authorization>> {
+        let sql = format!(
+            "SELECT * FROM authorizations WHERE {where_sql} \
+             ORDER BY block_number DESC, tx_index ASC, rowid ASC"
+        );
+
+        let rows = sqlx::query(sqlx::AssertSqlSafe(sql))
+            .fetch_all(conn)
+            .await?;
+        rows.iter().map(Self::from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Authorization> {
+        let block_number: i64 = row.try_get("block_number")?;
+        let tx_index: i64 = row.try_get("tx_index")?;
+        let chain_id: i64 = row.try_get("chain_id")?;
+        let address: Vec<u8> = row.try_get("address")?;
+        let nonce: i64 = row.try_get("nonce")?;
+        let authority: Option<Vec<u8>> = row.try_get("authority")?;
+
+        Ok(Authorization {
+            block_number: block_number as u64,
+            tx_index: tx_index as u64,
+            chain_id: U256::from(chain_id as u64),
+            address: Address::from_slice(&address),
+            nonce: nonce as u64,
+            authority: authority.map(|a| Address::from_slice(&a)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::txs::{
+        fixtures::sample_authorization, models::transaction::test::setup_test_db,
+    };
+
+    #[tokio::test]
+    async fn save_batch_roundtrips_and_is_idempotent() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let authorizations = vec![
+            Authorization {
+                tx_index: 3,
+                ..sample_authorization(100)
+            },
+            Authorization {
+                tx_index: 3,
+                nonce: 1,
+                authority: None,
+                ..sample_authorization(100)
+            },
+        ];
+        Authorization::save_batch(&authorizations, &conn).await?;
+        Authorization::save_batch(&authorizations, &conn).await?;
+
+        let found = Authorization::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, authorizations);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn save_skips_unstorable_chain_id() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let authorization = Authorization {
+            chain_id: U256::MAX,
+            ..sample_authorization(100)
+        };
+        Authorization::save_batch(&[authorization], &conn).await?;
+
+        assert!(Authorization::query_where("1 = 1", &conn).await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+
+use eyre::{Result, eyre};
+use revm::primitives::{Address, I256, U256};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+
+use crate::{
+    db::txs::models::{
+        block::{Block, BlockMarker},
+        log::Log,
+    },
+    misc::native_transfers::NativeTransfer,
+};
+
+/// Net change of one account's balance of one asset caused by a single tx.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub block_number: u64,
+    pub tx_index: u64,
+    pub address: Address,
+    /// ERC20 contract address; `None` for native ETH.
+    pub token: Option<Address>,
+    /// Signed amount in the asset's base units, stored as a 32-byte
+    /// two's-complement BLOB.
+    pub delta: I256,
+}
+
+#[hotpath::measure_all(future = true)]
+impl BalanceChange {
+    /// Nets a tx's ERC20 `Transfer` logs and traced native value transfers into
+    /// one row per (address, token). Accounts whose balance ends where it
+    /// started are omitted. Gas fees are not included.
+    pub(crate) fn from_tx(
+        block_number: u64,
+        tx_index: u64,
+        logs: &[Log],
+        native_transfers: &[NativeTransfer],
+    ) -> Vec<BalanceChange> {
+        // `None` marks a delta that left the i256 range; such rows are dropped.
+        let mut deltas: BTreeMap<(Address, Option<Address>), Option<I256>> = BTreeMap::new();
+        let mut apply = |address: Address, token: Option<Address>, amount: U256, credit: bool| {
+            let delta = deltas.entry((address, token)).or_insert(Some(I256::ZERO));
+            *delta = delta.and_then(|acc| {
+                let amount = I256::try_from(amount).ok()?;
+                if credit {
+                    acc.checked_add(amount)
+                } else {
+                    acc.checked_sub(amount)
+                }
+            });
+        };
+
+        for log in logs {
+            let (Some(amount), [_, from, to, ..]) = (log.erc20_amount, log.topics.as_slice())
+            else {
+                continue;
+            };
+            apply(Address::from_word(*from), Some(log.address), amount, false);
+            apply(Address::from_word(*to), Some(log.address), amount, true);
+        }
+
+        for transfer in native_transfers {
+            apply(transfer.from, None, transfer.value, false);
+            apply(transfer.to, None, transfer.value, true);
+        }
+
+        deltas
+            .into_iter()
+            .filter_map(|((address, token), delta)| {
+                let Some(delta) = delta else {
+                    tracing::warn!(
+                        "Skipping balance change in block {block_number} tx {tx_index} for {address}: delta exceeds i256"
+                    );
+                    return None;
+                };
+                (!delta.is_zero()).then_some(BalanceChange {
+                    block_number,
+                    tx_index,
+                    address,
+                    token,
+                    delta,
+                })
+            })
+            .collect()
+    }
+
+    #[allow(dead_code)] // used in tests
+    pub(crate) async fn count(conn: &SqlitePool) -> Result<i64> {
+        let count = sqlx::query("SELECT COUNT(*) FROM balance_changes")
+            .fetch_one(conn)
+            .await?
+            .get::<i64, _>(0);
+
+        Ok(count)
+    }
+
+    pub(crate) async fn save<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        // The unique index over (block_number, tx_index, address, token) makes
+        // re-tracing a block (e.g. after an interrupted backfill) a no-op.
+        sqlx::query(
+            r#"
+            INSERT INTO balance_changes (block_number, tx_index, address, token, delta)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(self.block_number as i64)
+        .bind(self.tx_index as i64)
+        .bind(self.address.as_slice())
+        .bind(self.token.as_ref().map(|t| t.as_slice()))
+        .bind(self.delta.to_be_bytes::<32>().to_vec())
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Saves the balance changes of a fully traced block and marks the block as
+    /// traced in the same transaction, so an interrupted backfill never leaves
+    /// a block half-recorded yet skipped.
+    pub(crate) async fn save_block(
+        block_number: u64,
+        changes: &[BalanceChange],
+        conn: &SqlitePool,
+    ) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        for change in changes {
+            change.save(&mut *db_tx).await?;
+        }
+        Block::mark(BlockMarker::BalanceChanges, block_number, &mut *db_tx).await?;
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn query_where(where_sql: &str, conn: &SqlitePool) -> Result<Vec<BalanceChange>> {
+        let sql = format!(
+            "SELECT * FROM balance_changes WHERE {where_sql} \
+             ORDER BY block_number DESC, tx_index ASC, rowid ASC"
+        );
+
+        let rows = sqlx::query(sqlx::AssertSqlSafe(sql))
+            .fetch_all(conn)
+            .await?;
+        rows.iter().map(Self::from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<BalanceChange> {
+        let block_number: i64 = row.try_get("block_number")?;
+        let tx_index: i64 = row.try_get("tx_index")?;
+        let address: Vec<u8> = row.try_get("address")?;
+        let token: Option<Vec<u8>> = row.try_get("token")?;
+        let delta: Vec<u8> = row.try_get("delta")?;
+        let delta: [u8; 32] = delta
+            .try_into()
+            .map_err(|_| eyre!("balance_changes.delta is not a 32-byte BLOB"))?;
+
+        Ok(BalanceChange {
+            block_number: block_number as u64,
+            tx_index: tx_index as u64,
+            address: Address::from_slice(&address),
+            token: token.map(|t| Address::from_slice(&t)),
+            delta: I256::from_be_bytes(delta),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use revm::primitives::FixedBytes;
+
+    use super::*;
+    use crate::db::txs::{fixtures::sample_log, models::transaction::test::setup_test_db};
+
+    const ALICE: Address = Address::new([0xaa; 20]);
+    const BOB: Address = Address::new([0xbb; 20]);
+    const TOKEN: Address = Address::new([0x70; 20]);
+
+    fn transfer_log(from: Address, to: Address, amount: u64) -> Log {
+        Log {
+            address: TOKEN,
+            topics: vec![FixedBytes::ZERO, from.into_word(), to.into_word()],
+            data: U256::from(amount).to_be_bytes::<32>().to_vec(),
+            erc20_amount: Some(U256::from(amount)),
+            signature: Some("Transfer(address,address,uint256)".to_string()),
+            ..sample_log(100)
+        }
+    }
+
+    fn delta(value: i64) -> I256 {
+        I256::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn from_tx_nets_erc20_and_native_transfers() {
+        let logs = vec![transfer_log(ALICE, BOB, 300), transfer_log(BOB, ALICE, 100)];
+        let native = vec![
+            NativeTransfer {
+                from: ALICE,
+                to: BOB,
+                value: U256::from(5),
+            },
+            NativeTransfer {
+                from: BOB,
+                to: ALICE,
+                value: U256::from(5),
+            },
+        ];
+
+        let changes = BalanceChange::from_tx(100, 0, &logs, &native);
+
+        // The native round trip nets to zero for both accounts and is dropped.
+        assert_eq!(
+            changes,
+            vec![
+                BalanceChange {
+                    block_number: 100,
+                    tx_index: 0,
+                    address: ALICE,
+                    token: Some(TOKEN),
+                    delta: delta(-200),
+                },
+                BalanceChange {
+                    block_number: 100,
+                    tx_index: 0,
+                    address: BOB,
+                    token: Some(TOKEN),
+                    delta: delta(200),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn save_block_roundtrips_and_is_idempotent() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+        let native = vec![NativeTransfer {
+            from: ALICE,
+            to: BOB,
+            value: U256::from(7),
+        }];
+        let changes = BalanceChange::from_tx(100, 2, &[], &native);
+
+        BalanceChange::save_block(100, &changes, &conn).await?;
+        BalanceChange::save_block(100, &changes, &conn).await?;
+
+        assert_eq!(BalanceChange::count(&conn).await?, 2);
+        let found = BalanceChange::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, changes);
+        assert_eq!(found[0].delta, delta(-7));
+
+        Ok(())
+    }
+}
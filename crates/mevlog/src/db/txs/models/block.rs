@@ -1,21 +1,28 @@
 use std::collections::HashSet;
-use std::str::FromStr;
 
-use arrow::record_batch::RecordBatch;
-use eyre::Result;
-use revm::primitives::{Address, FixedBytes};
+use alloy::{consensus::BlockHeader, network::AnyRpcBlock};
+use arrow::{array::LargeBinaryArray, record_batch::RecordBatch};
+use eyre::{Result, eyre};
+use revm::primitives::{Address, Bytes, FixedBytes, U256};
 use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
 
-use crate::misc::parquet_utils::get_parquet_string_value;
+use crate::misc::parquet_utils::{
+    binary_column, bytes_at, required, u64_at, u64_column, u256_column,
+};
 
 /// Per-block metadata, indexed alongside transactions during `query`.
 ///
 /// A row exists for every indexed block (including empty ones), so the presence
 /// of a row also marks the block as indexed (see [`Block::missing_blocks`]).
+/// `index --logs-only` rows are the exception: they are flagged `logs_only` and
+/// hold only the txs matching a log filter, so they still count as missing.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Block {
     pub block_number: u64,
     pub block_hash: FixedBytes<32>,
+    /// `None` for rows indexed before the column existed and for cryo cache
+    /// files written without it. Used by live indexing to detect reorgs.
+    pub parent_hash: Option<FixedBytes<32>>,
     /// Fee recipient (cryo `author`).
     pub miner: Address,
     pub gas_used: u64,
@@ -23,27 +30,137 @@ pub struct Block {
     pub timestamp: u64,
     /// `None` for pre-EIP-1559 blocks.
     pub base_fee_per_gas: Option<u64>,
+    /// Total EIP-4844 blob gas used by the block's txs. `None` for pre-Cancun
+    /// blocks, and for blocks indexed with the `cryo` fetcher until their
+    /// header is backfilled (see [`Self::update_header`]).
+    pub blob_gas_used: Option<u64>,
+    /// Blob gas left over from the parent block, which sets the blob base
+    /// fee. `None` as above.
+    pub excess_blob_gas: Option<u64>,
+    // Header fields below are `None` for rows indexed before the columns
+    // existed and for cryo cache files written without them.
+    pub gas_limit: Option<u64>,
+    /// Free-form header bytes; builders use it to tag their blocks.
+    pub extra_data: Option<Bytes>,
+    /// RLP-encoded block size in bytes.
+    pub size: Option<u64>,
+    pub state_root: Option<FixedBytes<32>>,
+    pub transactions_root: Option<FixedBytes<32>>,
+    /// Proof-of-work difficulty; zero after the merge. `None` for blocks
+    /// indexed with the `cryo` fetcher until their header is backfilled.
+    pub difficulty: Option<U256>,
+    /// `prevrandao` after the merge. `None` as above.
+    pub mix_hash: Option<FixedBytes<32>>,
+    /// EIP-4788 beacon root. `None` for pre-Cancun blocks and as above.
+    pub parent_beacon_block_root: Option<FixedBytes<32>>,
+}
+
+/// Backfills that record per-block rows after a block is indexed. Each has a
+/// `blocks` column set once a block's rows were saved, so a re-run skips it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum BlockMarker {
+    /// Opt-in `--balance-changes` tracing.
+    BalanceChanges,
+    /// Opt-in `--traces` tracing.
+    Traces,
+    /// Fields only JSON-RPC returns (see `rpc_details::backfill_rpc_details`),
+    /// set on save by the native RPC fetcher. Blocks indexed from cryo parquet
+    /// are backfilled with opt-in `--rpc-details`.
+    RpcDetails,
+}
+
+impl BlockMarker {
+    fn column(self) -> &'static str {
+        match self {
+            Self::BalanceChanges => "balance_changes_traced",
+            Self::Traces => "traces_traced",
+            Self::RpcDetails => "rpc_details_fetched",
+        }
+    }
 }
 
 #[hotpath::measure_all(future = true)]
 impl Block {
-    // Default cryo `blocks` columns: 0 block_hash, 1 author, 2 block_number,
-    // 3 gas_used, 4 extra_data, 5 timestamp, 6 base_fee_per_gas.
-    pub(crate) fn from_parquet_row(batch: &RecordBatch, row_idx: usize) -> Result<(Block, u64)> {
-        let get = |col_idx: usize| -> String { get_parquet_string_value(batch, col_idx, row_idx) };
-
-        let block_number = get(2).parse::<u64>().unwrap();
-
-        let block = Block {
-            block_number,
-            block_hash: FixedBytes::<32>::from_str(&get(0)).unwrap(),
-            miner: Address::from_str(&get(1)).unwrap(),
-            gas_used: get(3).parse::<u64>().unwrap(),
-            timestamp: get(5).parse::<u64>().unwrap(),
-            base_fee_per_gas: get(6).parse::<u64>().ok(),
+    // Cryo `blocks` columns are looked up by name: `--include-columns` (see
+    // `data_fetch::cryo_include_columns`) shifts the default positions, and
+    // cache files written before a column was requested lack it entirely.
+    pub(crate) fn from_parquet_batch(batch: &RecordBatch) -> Result<Vec<Block>> {
+        let block_numbers = required(u64_column(batch, "block_number")?, "blocks", "block_number")?;
+        let block_hashes = required(binary_column(batch, "block_hash")?, "blocks", "block_hash")?;
+        let parent_hashes = binary_column(batch, "parent_hash")?;
+        let authors = required(binary_column(batch, "author")?, "blocks", "author")?;
+        let gas_used = required(u64_column(batch, "gas_used")?, "blocks", "gas_used")?;
+        let timestamps = required(u64_column(batch, "timestamp")?, "blocks", "timestamp")?;
+        let base_fees = u64_column(batch, "base_fee_per_gas")?;
+        let gas_limits = u64_column(batch, "gas_limit")?;
+        let extra_data = binary_column(batch, "extra_data")?;
+        let sizes = u64_column(batch, "size")?;
+        let state_roots = binary_column(batch, "state_root")?;
+        let transactions_roots = binary_column(batch, "transactions_root")?;
+        let difficulties = u256_column(batch, "difficulty")?;
+        let mix_hashes = binary_column(batch, "mix_hash")?;
+
+        let hash_at = |column: Option<&LargeBinaryArray>, row_idx: usize| {
+            bytes_at(column, row_idx)
+                .map(FixedBytes::<32>::try_from)
+                .transpose()
         };
 
-        Ok((block, block_number))
+        (0..batch.num_rows())
+            .map(|row_idx| {
+                let null = |name: &str| eyre!("cryo blocks parquet has a null '{name}'");
+
+                Ok(Block {
+                    block_number: u64_at(&block_numbers, row_idx)
+                        .ok_or_else(|| null("block_number"))?,
+                    block_hash: FixedBytes::<32>::try_from(
+                        bytes_at(Some(&block_hashes), row_idx).ok_or_else(|| null("block_hash"))?,
+                    )?,
+                    parent_hash: hash_at(parent_hashes.as_ref(), row_idx)?,
+                    miner: Address::try_from(
+                        bytes_at(Some(&authors), row_idx).ok_or_else(|| null("author"))?,
+                    )?,
+                    gas_used: u64_at(&gas_used, row_idx).ok_or_else(|| null("gas_used"))?,
+                    timestamp: u64_at(&timestamps, row_idx).ok_or_else(|| null("timestamp"))?,
+                    base_fee_per_gas: base_fees.as_ref().and_then(|c| u64_at(c, row_idx)),
+                    blob_gas_used: None,
+                    excess_blob_gas: None,
+                    gas_limit: gas_limits.as_ref().and_then(|c| u64_at(c, row_idx)),
+                    extra_data: bytes_at(extra_data.as_ref(), row_idx).map(Bytes::copy_from_slice),
+                    size: sizes.as_ref().and_then(|c| u64_at(c, row_idx)),
+                    state_root: hash_at(state_roots.as_ref(), row_idx)?,
+                    transactions_root: hash_at(transactions_roots.as_ref(), row_idx)?,
+                    difficulty: difficulties.as_ref().and_then(|c| c[row_idx]),
+                    mix_hash: hash_at(mix_hashes.as_ref(), row_idx)?,
+                    parent_beacon_block_root: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Builds a block row from an `eth_getBlockByNumber` response; the
+    /// native-fetcher counterpart of [`Self::from_parquet_batch`].
+    pub(crate) fn from_rpc(block: &AnyRpcBlock) -> Block {
+        let header = &block.header;
+        Block {
+            block_number: header.number(),
+            block_hash: header.hash,
+            parent_hash: Some(header.parent_hash()),
+            miner: header.beneficiary(),
+            gas_used: header.gas_used(),
+            timestamp: header.timestamp(),
+            base_fee_per_gas: header.base_fee_per_gas(),
+            blob_gas_used: header.blob_gas_used(),
+            excess_blob_gas: header.excess_blob_gas(),
+            gas_limit: Some(header.gas_limit()),
+            extra_data: Some(header.extra_data().clone()),
+            size: header.size.and_then(|size| u64::try_from(size).ok()),
+            state_root: Some(header.state_root()),
+            transactions_root: Some(header.transactions_root()),
+            difficulty: Some(header.difficulty()),
+            mix_hash: header.mix_hash(),
+            parent_beacon_block_root: header.parent_beacon_block_root(),
+        }
     }
 
     #[allow(dead_code)] // used in tests
@@ -57,24 +174,92 @@ impl Block {
     }
 
     pub(crate) async fn save<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        self.save_as(false, executor).await
+    }
+
+    /// Inserts the block row, flagged `logs_only` when only some of its txs
+    /// were saved. A full save clears the flag of an existing logs-only row;
+    /// a logs-only save never sets it on a fully indexed one.
+    async fn save_as<'c, E>(&self, logs_only: bool, executor: E) -> Result<()>
     where
         E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
     {
         sqlx::query(
             r#"
             INSERT INTO blocks (
-                block_number, block_hash, miner, gas_used,
-                timestamp, base_fee_per_gas
-            ) VALUES (?, ?, ?, ?, ?, ?)
-            ON CONFLICT(block_number) DO NOTHING
+                block_number, block_hash, parent_hash, miner, gas_used,
+                timestamp, base_fee_per_gas, blob_gas_used, excess_blob_gas,
+                gas_limit, extra_data, size, state_root, transactions_root,
+                difficulty, mix_hash, parent_beacon_block_root, logs_only
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(block_number)
+            DO UPDATE SET logs_only = MIN(blocks.logs_only, excluded.logs_only)
             "#,
         )
         .bind(self.block_number as i64)
         .bind(self.block_hash.as_slice())
+        .bind(self.parent_hash.as_ref().map(|hash| hash.as_slice()))
         .bind(self.miner.as_slice())
         .bind(self.gas_used as i64)
         .bind(self.timestamp as i64)
         .bind(self.base_fee_per_gas.map(|v| v as i64))
+        .bind(self.blob_gas_used.map(|v| v as i64))
+        .bind(self.excess_blob_gas.map(|v| v as i64))
+        .bind(self.gas_limit.map(|v| v as i64))
+        .bind(self.extra_data.as_ref().map(|data| data.as_ref()))
+        .bind(self.size.map(|v| v as i64))
+        .bind(self.state_root.as_ref().map(|hash| hash.as_slice()))
+        .bind(self.transactions_root.as_ref().map(|hash| hash.as_slice()))
+        .bind(self.difficulty.map(|d| d.to_be_bytes::<32>().to_vec()))
+        .bind(self.mix_hash.as_ref().map(|hash| hash.as_slice()))
+        .bind(
+            self.parent_beacon_block_root
+                .as_ref()
+                .map(|hash| hash.as_slice()),
+        )
+        .bind(logs_only)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Overwrites the header columns of the saved row with `self`'s, leaving
+    /// its markers and `logs_only` flag as they are. Fills in the fields that
+    /// cryo datasets lack.
+    pub(crate) async fn update_header<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query(
+            r#"
+            UPDATE blocks
+            SET parent_hash = ?, blob_gas_used = ?, excess_blob_gas = ?,
+                gas_limit = ?, extra_data = ?, size = ?, state_root = ?,
+                transactions_root = ?, difficulty = ?, mix_hash = ?,
+                parent_beacon_block_root = ?
+            WHERE block_number = ?
+            "#,
+        )
+        .bind(self.parent_hash.as_ref().map(|hash| hash.as_slice()))
+        .bind(self.blob_gas_used.map(|v| v as i64))
+        .bind(self.excess_blob_gas.map(|v| v as i64))
+        .bind(self.gas_limit.map(|v| v as i64))
+        .bind(self.extra_data.as_ref().map(|data| data.as_ref()))
+        .bind(self.size.map(|v| v as i64))
+        .bind(self.state_root.as_ref().map(|hash| hash.as_slice()))
+        .bind(self.transactions_root.as_ref().map(|hash| hash.as_slice()))
+        .bind(self.difficulty.map(|d| d.to_be_bytes::<32>().to_vec()))
+        .bind(self.mix_hash.as_ref().map(|hash| hash.as_slice()))
+        .bind(
+            self.parent_beacon_block_root
+                .as_ref()
+                .map(|hash| hash.as_slice()),
+        )
+        .bind(self.block_number as i64)
         .execute(executor)
         .await?;
 
@@ -92,9 +277,23 @@ impl Block {
         Ok(())
     }
 
+    /// Saves blocks of which only the txs matching a log filter were indexed.
+    pub(crate) async fn save_batch_logs_only(blocks: &[Block], conn: &SqlitePool) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        for block in blocks {
+            block.save_as(true, &mut *db_tx).await?;
+        }
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    /// Blocks in `from..=to` without a fully indexed row. Logs-only rows count
+    /// as missing, so regular indexing fills in the rest of their txs.
     pub(crate) async fn missing_blocks(from: u64, to: u64, conn: &SqlitePool) -> Result<Vec<u64>> {
         let existing: Vec<i64> = sqlx::query_scalar(
-            "SELECT block_number FROM blocks WHERE block_number BETWEEN ? AND ?",
+            "SELECT block_number FROM blocks WHERE block_number BETWEEN ? AND ? AND logs_only = 0",
         )
         .bind(from as i64)
         .bind(to as i64)
@@ -105,6 +304,94 @@ impl Block {
         Ok((from..=to).filter(|b| !indexed.contains(b)).collect())
     }
 
+    /// The last indexed block at or below `head` stamped before `timestamp`, and
+    /// the first one stamped at or after it. They bound the binary search of
+    /// [`block_at_timestamp`] so that an indexed window needs no RPC.
+    ///
+    /// [`block_at_timestamp`]: crate::misc::args_parsing::block_at_timestamp
+    pub(crate) async fn timestamp_bounds(
+        timestamp: u64,
+        head: u64,
+        conn: &SqlitePool,
+    ) -> Result<(Option<u64>, Option<u64>)> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                (SELECT MAX(block_number) FROM blocks
+                 WHERE timestamp < ?1 AND block_number <= ?2) AS before,
+                (SELECT MIN(block_number) FROM blocks
+                 WHERE timestamp >= ?1 AND block_number <= ?2) AS at_or_after
+            "#,
+        )
+        .bind(timestamp as i64)
+        .bind(head as i64)
+        .fetch_one(conn)
+        .await?;
+
+        let before: Option<i64> = row.try_get("before")?;
+        let at_or_after: Option<i64> = row.try_get("at_or_after")?;
+        Ok((before.map(|b| b as u64), at_or_after.map(|b| b as u64)))
+    }
+
+    /// Fully indexed blocks in `from..=to` not yet marked with `marker`, in
+    /// ascending order. Logs-only blocks are left out, as backfilling them
+    /// would reference txs that were never saved.
+    pub(crate) async fn unmarked(
+        marker: BlockMarker,
+        from: u64,
+        to: u64,
+        conn: &SqlitePool,
+    ) -> Result<Vec<u64>> {
+        let sql = format!(
+            "SELECT block_number FROM blocks \
+             WHERE {} = 0 AND logs_only = 0 AND block_number BETWEEN ? AND ? \
+             ORDER BY block_number ASC",
+            marker.column()
+        );
+        let unmarked: Vec<i64> = sqlx::query_scalar(sqlx::AssertSqlSafe(sql))
+            .bind(from as i64)
+            .bind(to as i64)
+            .fetch_all(conn)
+            .await?;
+
+        Ok(unmarked.into_iter().map(|b| b as u64).collect())
+    }
+
+    pub(crate) async fn mark<'c, E>(
+        marker: BlockMarker,
+        block_number: u64,
+        executor: E,
+    ) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        let sql = format!(
+            "UPDATE blocks SET {} = 1 WHERE block_number = ?",
+            marker.column()
+        );
+        sqlx::query(sqlx::AssertSqlSafe(sql))
+            .bind(block_number as i64)
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn mark_batch(
+        marker: BlockMarker,
+        block_numbers: &[u64],
+        conn: &SqlitePool,
+    ) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        for &block_number in block_numbers {
+            Self::mark(marker, block_number, &mut *db_tx).await?;
+        }
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
     pub(crate) async fn query_where(where_sql: &str, conn: &SqlitePool) -> Result<Vec<Block>> {
         let sql = format!("SELECT * FROM blocks WHERE {where_sql} ORDER BY block_number DESC");
 
@@ -117,18 +404,41 @@ impl Block {
     fn from_row(row: &SqliteRow) -> Result<Block> {
         let block_number: i64 = row.try_get("block_number")?;
         let block_hash: Vec<u8> = row.try_get("block_hash")?;
+        let parent_hash: Option<Vec<u8>> = row.try_get("parent_hash")?;
         let miner: Vec<u8> = row.try_get("miner")?;
         let gas_used: i64 = row.try_get("gas_used")?;
         let timestamp: i64 = row.try_get("timestamp")?;
         let base_fee_per_gas: Option<i64> = row.try_get("base_fee_per_gas")?;
+        let blob_gas_used: Option<i64> = row.try_get("blob_gas_used")?;
+        let excess_blob_gas: Option<i64> = row.try_get("excess_blob_gas")?;
+        let gas_limit: Option<i64> = row.try_get("gas_limit")?;
+        let extra_data: Option<Vec<u8>> = row.try_get("extra_data")?;
+        let size: Option<i64> = row.try_get("size")?;
+        let state_root: Option<Vec<u8>> = row.try_get("state_root")?;
+        let transactions_root: Option<Vec<u8>> = row.try_get("transactions_root")?;
+        let difficulty: Option<Vec<u8>> = row.try_get("difficulty")?;
+        let mix_hash: Option<Vec<u8>> = row.try_get("mix_hash")?;
+        let parent_beacon_block_root: Option<Vec<u8>> = row.try_get("parent_beacon_block_root")?;
+        let hash = |bytes: Vec<u8>| FixedBytes::<32>::from_slice(&bytes);
 
         Ok(Block {
             block_number: block_number as u64,
             block_hash: FixedBytes::<32>::from_slice(&block_hash),
+            parent_hash: parent_hash.map(|hash| FixedBytes::<32>::from_slice(&hash)),
             miner: Address::from_slice(&miner),
             gas_used: gas_used as u64,
             timestamp: timestamp as u64,
             base_fee_per_gas: base_fee_per_gas.map(|v| v as u64),
+            blob_gas_used: blob_gas_used.map(|v| v as u64),
+            excess_blob_gas: excess_blob_gas.map(|v| v as u64),
+            gas_limit: gas_limit.map(|v| v as u64),
+            extra_data: extra_data.map(Bytes::from),
+            size: size.map(|v| v as u64),
+            state_root: state_root.map(hash),
+            transactions_root: transactions_root.map(hash),
+            difficulty: difficulty.map(|d| U256::from_be_slice(&d)),
+            mix_hash: mix_hash.map(hash),
+            parent_beacon_block_root: parent_beacon_block_root.map(hash),
         })
     }
 }
@@ -136,24 +446,13 @@ impl Block {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::db::txs::models::transaction::test::setup_test_db;
-
-    fn sample_block(block_number: u64, base_fee_per_gas: Option<u64>) -> Block {
-        Block {
-            block_number,
-            block_hash: FixedBytes::<32>::from([0xab; 32]),
-            miner: Address::from([0x11; 20]),
-            gas_used: 16_000_000,
-            timestamp: 1_693_066_895,
-            base_fee_per_gas,
-        }
-    }
+    use crate::db::txs::{fixtures::sample_block, models::transaction::test::setup_test_db};
 
     #[tokio::test]
     async fn save_batch_and_query_roundtrips() -> Result<()> {
         let (conn, _cl) = setup_test_db().await;
 
-        let block = sample_block(100, Some(21_721_091_641));
+        let block = sample_block(100);
         Block::save_batch(std::slice::from_ref(&block), &conn).await?;
 
         assert_eq!(Block::count(&conn).await?, 1);
@@ -165,11 +464,67 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn update_header_fills_in_cryo_block() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let block = sample_block(100);
+        Block::save_batch(std::slice::from_ref(&block), &conn).await?;
+
+        let mut rpc_block = block.clone();
+        rpc_block.blob_gas_used = Some(786_432);
+        rpc_block.excess_blob_gas = Some(79_429_632);
+        rpc_block.difficulty = Some(U256::ZERO);
+        rpc_block.mix_hash = Some(FixedBytes::<32>::from([0xcc; 32]));
+        rpc_block.update_header(&conn).await?;
+
+        let found = Block::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, vec![rpc_block]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn blob_gas_fields_roundtrip() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let mut block = sample_block(100);
+        block.blob_gas_used = Some(786_432);
+        block.excess_blob_gas = Some(79_429_632);
+        Block::save_batch(std::slice::from_ref(&block), &conn).await?;
+
+        let found = Block::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, vec![block]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn header_fields_roundtrip() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let mut block = sample_block(100);
+        block.gas_limit = Some(36_000_000);
+        block.extra_data = Some(Bytes::from_static(b"beaverbuild.org"));
+        block.size = Some(161_348);
+        block.state_root = Some(FixedBytes::<32>::from([0x01; 32]));
+        block.transactions_root = Some(FixedBytes::<32>::from([0x02; 32]));
+        block.difficulty = Some(U256::ZERO);
+        block.mix_hash = Some(FixedBytes::<32>::from([0x03; 32]));
+        block.parent_beacon_block_root = Some(FixedBytes::<32>::from([0x04; 32]));
+        Block::save_batch(std::slice::from_ref(&block), &conn).await?;
+
+        let found = Block::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, vec![block]);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn save_batch_is_idempotent() -> Result<()> {
         let (conn, _cl) = setup_test_db().await;
 
-        let block = sample_block(100, None);
+        let block = sample_block(100);
         Block::save_batch(std::slice::from_ref(&block), &conn).await?;
         Block::save_batch(std::slice::from_ref(&block), &conn).await?;
 
@@ -182,11 +537,91 @@ mod test {
         let (conn, _cl) = setup_test_db().await;
 
         // Empty blocks are still recorded, so 101 and 103 count as indexed.
-        Block::save_batch(&[sample_block(101, None), sample_block(103, None)], &conn).await?;
+        Block::save_batch(&[sample_block(101), sample_block(103)], &conn).await?;
 
         let missing = Block::missing_blocks(100, 104, &conn).await?;
         assert_eq!(missing, vec![100, 102, 104]);
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn logs_only_blocks_count_as_missing_until_fully_saved() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        Block::save_batch_logs_only(&[sample_block(101), sample_block(102)], &conn).await?;
+        Block::save_batch(&[sample_block(102)], &conn).await?;
+        // A later logs-only run doesn't downgrade a fully indexed block.
+        Block::save_batch_logs_only(&[sample_block(102)], &conn).await?;
+
+        assert_eq!(Block::count(&conn).await?, 2);
+        assert_eq!(Block::missing_blocks(101, 102, &conn).await?, vec![101]);
+        assert_eq!(
+            Block::unmarked(BlockMarker::Traces, 101, 102, &conn).await?,
+            vec![102]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn timestamp_bounds_brackets_the_timestamp() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let block = |block_number: u64, timestamp: u64| Block {
+            timestamp,
+            ..sample_block(block_number)
+        };
+        Block::save_batch(
+            &[block(100, 1_000), block(101, 1_012), block(105, 1_060)],
+            &conn,
+        )
+        .await?;
+
+        assert_eq!(
+            Block::timestamp_bounds(1_012, 200, &conn).await?,
+            (Some(100), Some(101))
+        );
+        assert_eq!(
+            Block::timestamp_bounds(1_013, 200, &conn).await?,
+            (Some(101), Some(105))
+        );
+        // Blocks above the head are ignored.
+        assert_eq!(
+            Block::timestamp_bounds(1_013, 104, &conn).await?,
+            (Some(101), None)
+        );
+        assert_eq!(
+            Block::timestamp_bounds(999, 200, &conn).await?,
+            (None, Some(100))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unmarked_excludes_marked() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        Block::save_batch(&[sample_block(101), sample_block(102)], &conn).await?;
+        Block::mark(BlockMarker::BalanceChanges, 101, &conn).await?;
+
+        assert_eq!(
+            Block::unmarked(BlockMarker::BalanceChanges, 100, 104, &conn).await?,
+            vec![102]
+        );
+        assert_eq!(
+            Block::unmarked(BlockMarker::Traces, 100, 104, &conn).await?,
+            vec![101, 102]
+        );
+
+        Block::mark_batch(BlockMarker::RpcDetails, &[101, 102], &conn).await?;
+        assert!(
+            Block::unmarked(BlockMarker::RpcDetails, 100, 104, &conn)
+                .await?
+                .is_empty()
+        );
+
+        Ok(())
+    }
 }
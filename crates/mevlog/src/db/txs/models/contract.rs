@@ -0,0 +1,285 @@
+use alloy::rpc::types::trace::{
+    geth::CallFrame,
+    parity::{Action, CreationMethod, TraceOutput, TransactionTrace},
+};
+use eyre::Result;
+use revm::primitives::{Address, FixedBytes, keccak256};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+
+use crate::db::txs::models::transaction::Transaction;
+
+/// A contract deployment, from a top-level create tx or an internal
+/// `CREATE`/`CREATE2` frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contract {
+    pub address: Address,
+    pub block_number: u64,
+    pub tx_index: u64,
+    /// Account that executed the create: the tx sender for top-level creates,
+    /// the factory contract for internal ones.
+    pub deployer: Address,
+    /// `CREATE` or `CREATE2`.
+    pub create_type: String,
+    /// keccak256 of the deployed runtime code. Only known from traces; `None`
+    /// for top-level creates of untraced blocks.
+    pub code_hash: Option<FixedBytes<32>>,
+}
+
+#[hotpath::measure_all(future = true)]
+impl Contract {
+    /// Contracts deployed by successful top-level create txs. The address is
+    /// the one derived from sender and nonce at indexing time.
+    pub(crate) fn from_transactions(txs: &[Transaction]) -> Vec<Contract> {
+        txs.iter()
+            .filter(|tx| {
+                tx.success
+                    && tx.signature_hash.is_none()
+                    && tx.signature.as_deref() == Some("CREATE()")
+            })
+            .filter_map(|tx| {
+                Some(Contract {
+                    address: tx.to_address?,
+                    block_number: tx.block_number,
+                    tx_index: tx.tx_index,
+                    deployer: tx.from_address,
+                    create_type: "CREATE".to_string(),
+                    code_hash: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Contracts deployed within a geth `callTracer` tree, root included. A
+    /// create under an errored frame was rolled back and is skipped.
+    pub(crate) fn from_call_frame(
+        root: &CallFrame,
+        block_number: u64,
+        tx_index: u64,
+    ) -> Vec<Contract> {
+        let mut contracts = Vec::new();
+        collect_frame_contracts(root, block_number, tx_index, &mut contracts);
+        contracts
+    }
+
+    /// Contracts deployed within a Revm (parity-style) trace list, with the
+    /// same rules as [`Self::from_call_frame`].
+    pub(crate) fn from_parity(
+        traces: &[TransactionTrace],
+        block_number: u64,
+        tx_index: u64,
+    ) -> Vec<Contract> {
+        let mut reverted: Vec<&[usize]> = Vec::new();
+        let mut contracts = Vec::new();
+
+        for trace in traces {
+            if reverted
+                .iter()
+                .any(|prefix| trace.trace_address.starts_with(prefix))
+            {
+                continue;
+            }
+            if trace.error.is_some() {
+                reverted.push(&trace.trace_address);
+                continue;
+            }
+
+            if let (Action::Create(create), Some(TraceOutput::Create(output))) =
+                (&trace.action, &trace.result)
+            {
+                let create_type = match create.creation_method {
+                    CreationMethod::Create2 => "CREATE2",
+                    _ => "CREATE",
+                };
+                contracts.push(Contract {
+                    address: output.address,
+                    block_number,
+                    tx_index,
+                    deployer: create.from,
+                    create_type: create_type.to_string(),
+                    code_hash: Some(keccak256(&output.code)),
+                });
+            }
+        }
+
+        contracts
+    }
+
+    #[allow(dead_code)] // used in tests
+    pub(crate) async fn count(conn: &SqlitePool) -> Result<i64> {
+        let count = sqlx::query("SELECT COUNT(*) FROM contracts")
+            .fetch_one(conn)
+            .await?
+            .get::<i64, _>(0);
+
+        Ok(count)
+    }
+
+    pub(crate) async fn save<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        // A top-level create is recorded at indexing time without a code hash;
+        // tracing the same block later fills it in.
+        sqlx::query(
+            r#"
+            INSERT INTO contracts (address, block_number, tx_index, deployer, create_type, code_hash)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(address, block_number, tx_index)
+            DO UPDATE SET code_hash = COALESCE(contracts.code_hash, excluded.code_hash)
+            "#,
+        )
+        .bind(self.address.as_slice())
+        .bind(self.block_number as i64)
+        .bind(self.tx_index as i64)
+        .bind(self.deployer.as_slice())
+        .bind(&self.create_type)
+        .bind(self.code_hash.as_ref().map(|h| h.as_slice()))
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn save_batch(contracts: &[Contract], conn: &SqlitePool) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        for contract in contracts {
+            contract.save(&mut *db_tx).await?;
+        }
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn query_where(where_sql: &str, conn: &SqlitePool) -> Result<Vec<Contract>> {
+        let sql = format!(
+            "SELECT * FROM contracts WHERE {where_sql} \
+             ORDER BY block_number DESC, tx_index ASC, rowid ASC"
+        );
+
+        let rows = sqlx::query(sqlx::AssertSqlSafe(sql))
+            .fetch_all(conn)
+            .await?;
+        rows.iter().map(Self::from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Contract> {
+        let address: Vec<u8> = row.try_get("address")?;
+        let block_number: i64 = row.try_get("block_number")?;
+        let tx_index: i64 = row.try_get("tx_index")?;
+        let deployer: Vec<u8> = row.try_get("deployer")?;
+        let code_hash: Option<Vec<u8>> = row.try_get("code_hash")?;
+
+        Ok(Contract {
+            address: Address::from_slice(&address),
+            block_number: block_number as u64,
+            tx_index: tx_index as u64,
+            deployer: Address::from_slice(&deployer),
+            create_type: row.try_get("create_type")?,
+            code_hash: code_hash.map(|h| FixedBytes::<32>::from_slice(&h)),
+        })
+    }
+}
+
+fn collect_frame_contracts(
+    frame: &CallFrame,
+    block_number: u64,
+    tx_index: u64,
+    contracts: &mut Vec<Contract>,
+) {
+    if frame.error.is_some() {
+        return;
+    }
+
+    let create_type = frame.typ.to_uppercase();
+    if create_type.starts_with("CREATE")
+        && let Some(address) = frame.to
+    {
+        // For creates, callTracer reports the deployed runtime code as output.
+        contracts.push(Contract {
+            address,
+            block_number,
+            tx_index,
+            deployer: frame.from,
+            create_type,
+            code_hash: Some(keccak256(frame.output.as_deref().unwrap_or_default())),
+        });
+    }
+
+    for call in &frame.calls {
+        collect_frame_contracts(call, block_number, tx_index, contracts);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use revm::primitives::Bytes;
+
+    use super::*;
+    use crate::db::txs::models::transaction::test::setup_test_db;
+
+    const FACTORY: Address = Address::new([0xfa; 20]);
+
+    fn frame(typ: &str, from: Address, to: u8, calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame {
+            from,
+            to: Some(Address::from([to; 20])),
+            output: Some(Bytes::from_static(&[0x60, 0x80])),
+            typ: typ.to_string(),
+            calls,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn from_call_frame_skips_rolled_back_creates() {
+        let mut reverted = frame(
+            "CALL",
+            FACTORY,
+            0x03,
+            vec![frame("CREATE", FACTORY, 0x04, vec![])],
+        );
+        reverted.error = Some("execution reverted".to_string());
+        let root = frame(
+            "CALL",
+            Address::from([0x01; 20]),
+            0xfa,
+            vec![frame("CREATE2", FACTORY, 0x02, vec![]), reverted],
+        );
+
+        let contracts = Contract::from_call_frame(&root, 100, 1);
+
+        assert_eq!(
+            contracts,
+            vec![Contract {
+                address: Address::from([0x02; 20]),
+                block_number: 100,
+                tx_index: 1,
+                deployer: FACTORY,
+                create_type: "CREATE2".to_string(),
+                code_hash: Some(keccak256([0x60, 0x80])),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn traced_save_fills_code_hash_of_indexed_create() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+        let root = frame("CREATE", Address::from([0x01; 20]), 0x02, vec![]);
+        let traced = Contract::from_call_frame(&root, 100, 0);
+        let untraced = Contract {
+            code_hash: None,
+            ..traced[0].clone()
+        };
+
+        Contract::save_batch(&[untraced.clone()], &conn).await?;
+        Contract::save_batch(&traced, &conn).await?;
+        Contract::save_batch(&[untraced], &conn).await?;
+
+        assert_eq!(Contract::count(&conn).await?, 1);
+        let found = Contract::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, traced);
+
+        Ok(())
+    }
+}
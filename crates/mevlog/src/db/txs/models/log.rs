@@ -1,9 +1,15 @@
-use arrow::record_batch::RecordBatch;
-use eyre::Result;
+use std::collections::HashMap;
+
+use alloy::rpc::types::Log as RpcLog;
+use arrow::{array::UInt64Array, record_batch::RecordBatch};
+use eyre::{Result, eyre};
 use revm::primitives::{Address, FixedBytes, U256};
 use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
 
-use crate::{db::sigs::models::event::Event, misc::parquet_utils::get_parquet_string_value};
+use crate::{
+    db::sigs::models::event::Event,
+    misc::parquet_utils::{binary_column, bytes_at, required, u64_at, u64_column},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Log {
@@ -23,48 +29,101 @@ pub struct Log {
 
 #[hotpath::measure_all(future = true)]
 impl Log {
-    // Default cryo `logs` columns: 0 block_number, 1 transaction_index,
-    // 2 log_index, 3 transaction_hash, 4 address, 5 topic0, 6 topic1,
-    // 7 topic2, 8 topic3, 9 data, 10 chain_id.
-    pub(crate) async fn from_parquet_row(
+    /// Parses a cryo `logs` record batch. Columns are decoded once per batch
+    /// and the distinct `topic0`s resolved with a single
+    /// [`Event::find_by_topics`] lookup, instead of one sigs DB query per row.
+    pub(crate) async fn from_parquet_batch(
         batch: &RecordBatch,
-        row_idx: usize,
         sqlite: &SqlitePool,
+    ) -> Result<Vec<Log>> {
+        let col = |name: &str| -> Result<UInt64Array> {
+            required(u64_column(batch, name)?, "logs", name)
+        };
+
+        let block_numbers = col("block_number")?;
+        let tx_indexes = col("transaction_index")?;
+        let log_indexes = col("log_index")?;
+        let addresses = required(binary_column(batch, "address")?, "logs", "address")?;
+        let topic_columns = [
+            binary_column(batch, "topic0")?,
+            binary_column(batch, "topic1")?,
+            binary_column(batch, "topic2")?,
+            binary_column(batch, "topic3")?,
+        ];
+        let data_column = binary_column(batch, "data")?;
+
+        let topic0s = (0..batch.num_rows())
+            .filter_map(|row_idx| bytes_at(topic_columns[0].as_ref(), row_idx))
+            .map(<[u8; 32]>::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let resolved = Event::find_by_topics(&topic0s, sqlite).await?;
+
+        let null = |name: &str| eyre!("cryo logs parquet has a null '{name}'");
+
+        (0..batch.num_rows())
+            .map(|row_idx| {
+                let topics = topic_columns
+                    .iter()
+                    .filter_map(|column| bytes_at(column.as_ref(), row_idx))
+                    .map(FixedBytes::<32>::try_from)
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let signature = topics
+                    .first()
+                    .and_then(|topic0| resolved.get(&topic0.0).cloned());
+
+                let data = bytes_at(data_column.as_ref(), row_idx)
+                    .unwrap_or_default()
+                    .to_vec();
+                let erc20_amount = erc20_amount(signature.as_deref(), &data);
+
+                Ok(Log {
+                    block_number: u64_at(&block_numbers, row_idx)
+                        .ok_or_else(|| null("block_number"))?,
+                    tx_index: u64_at(&tx_indexes, row_idx)
+                        .ok_or_else(|| null("transaction_index"))?,
+                    log_index: u64_at(&log_indexes, row_idx).ok_or_else(|| null("log_index"))?,
+                    address: Address::try_from(
+                        bytes_at(Some(&addresses), row_idx).ok_or_else(|| null("address"))?,
+                    )?,
+                    topics,
+                    data,
+                    erc20_amount,
+                    signature,
+                })
+            })
+            .collect()
+    }
+
+    /// Builds a log from an `eth_getBlockReceipts` entry; the native-fetcher
+    /// counterpart of [`Self::from_parquet_batch`], against `topic0`s resolved
+    /// up front with [`Event::find_by_topics`]. Errors on a pending log, which
+    /// has no `transactionIndex`/`logIndex` to key the row by.
+    pub(crate) fn from_rpc(
+        log: &RpcLog,
+        block_number: u64,
+        resolved: &HashMap<[u8; 32], String>,
     ) -> Result<Log> {
-        let get = |col_idx: usize| -> String { get_parquet_string_value(batch, col_idx, row_idx) };
+        let missing = |name: &str| eyre!("block {block_number} receipt log has no '{name}'");
+        let tx_index = log
+            .transaction_index
+            .ok_or_else(|| missing("transactionIndex"))?;
+        let log_index = log.log_index.ok_or_else(|| missing("logIndex"))?;
 
-        let block_number = get(0).parse::<u64>().unwrap();
-        let tx_index = get(1).parse::<u64>().unwrap();
-        let log_index = get(2).parse::<u64>().unwrap();
-        let address = get(4).parse::<Address>().unwrap();
+        let topics = log.topics().to_vec();
 
-        let signature = Event::find_by_topic(&get(5), sqlite).await?;
+        let signature = topics
+            .first()
+            .and_then(|topic0| resolved.get(&topic0.0).cloned());
 
-        let topics = [get(5), get(6), get(7), get(8)]
-            .iter()
-            .filter(|s| !s.is_empty())
-            .map(|s| {
-                FixedBytes::from_slice(&hex::decode(s.strip_prefix("0x").unwrap_or(s)).unwrap())
-            })
-            .collect::<Vec<_>>();
-
-        let data_str = get(9);
-        let data = hex::decode(data_str.strip_prefix("0x").unwrap_or(&data_str)).unwrap();
-
-        let erc20_amount = if signature.as_deref() == Some("Transfer(address,address,uint256)")
-            && data.len() >= 32
-        {
-            let amount_bytes: [u8; 32] = data[..32].try_into().unwrap_or([0; 32]);
-            Some(U256::from_be_bytes(amount_bytes))
-        } else {
-            None
-        };
+        let data = log.data().data.to_vec();
+        let erc20_amount = erc20_amount(signature.as_deref(), &data);
 
         Ok(Log {
             block_number,
             tx_index,
             log_index,
-            address,
+            address: log.address(),
             topics,
             data,
             erc20_amount,
@@ -168,33 +227,31 @@ impl Log {
     }
 }
 
+/// Decoded ERC20 transfer value: the first data word of a resolved
+/// `Transfer(address,address,uint256)` log.
+fn erc20_amount(signature: Option<&str>, data: &[u8]) -> Option<U256> {
+    if signature == Some("Transfer(address,address,uint256)") && data.len() >= 32 {
+        let amount_bytes: [u8; 32] = data[..32].try_into().unwrap_or([0; 32]);
+        Some(U256::from_be_bytes(amount_bytes))
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::db::txs::models::transaction::test::setup_test_db;
-
-    fn sample_log(block_number: u64, log_index: u64, erc20_amount: Option<U256>) -> Log {
-        Log {
-            block_number,
-            tx_index: 0,
-            log_index,
-            address: Address::from([0x11; 20]),
-            topics: vec![
-                FixedBytes::<32>::from([0xdd; 32]),
-                FixedBytes::<32>::from([0x01; 32]),
-                FixedBytes::<32>::from([0x02; 32]),
-            ],
-            data: vec![0xde, 0xad, 0xbe, 0xef],
-            erc20_amount,
-            signature: Some("Transfer(address,address,uint256)".to_string()),
-        }
-    }
+    use crate::db::txs::{fixtures::sample_log, models::transaction::test::setup_test_db};
 
     #[tokio::test]
     async fn save_batch_and_query_roundtrips() -> Result<()> {
         let (conn, _cl) = setup_test_db().await;
 
-        let log = sample_log(100, 0, Some(U256::from(1_000_000_000u64)));
+        let log = Log {
+            erc20_amount: Some(U256::from(1_000_000_000u64)),
+            signature: Some("Transfer(address,address,uint256)".to_string()),
+            ..sample_log(100)
+        };
         Log::save_batch(std::slice::from_ref(&log), &conn).await?;
 
         assert_eq!(Log::count(&conn).await?, 1);
@@ -210,7 +267,7 @@ mod test {
     async fn save_batch_is_idempotent() -> Result<()> {
         let (conn, _cl) = setup_test_db().await;
 
-        let log = sample_log(100, 0, None);
+        let log = sample_log(100);
         Log::save_batch(std::slice::from_ref(&log), &conn).await?;
         Log::save_batch(std::slice::from_ref(&log), &conn).await?;
 
@@ -222,8 +279,15 @@ mod test {
     async fn erc20_amount_blob_compares_numerically() -> Result<()> {
         let (conn, _cl) = setup_test_db().await;
 
-        let small = sample_log(100, 0, Some(U256::from(500u64)));
-        let large = sample_log(100, 1, Some(U256::from(2_000u64)));
+        let small = Log {
+            erc20_amount: Some(U256::from(500u64)),
+            ..sample_log(100)
+        };
+        let large = Log {
+            log_index: 1,
+            erc20_amount: Some(U256::from(2_000u64)),
+            ..sample_log(100)
+        };
         Log::save_batch(&[small, large], &conn).await?;
 
         // 1000 left-padded to 32 bytes (big-endian) -> lexicographic blob compare.
@@ -235,4 +299,21 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn from_rpc_rejects_pending_logs() {
+        let rpc_log = RpcLog {
+            transaction_index: Some(3),
+            log_index: Some(7),
+            ..Default::default()
+        };
+        let log = Log::from_rpc(&rpc_log, 100, &HashMap::new()).unwrap();
+        assert_eq!((log.tx_index, log.log_index), (3, 7));
+
+        let pending = RpcLog {
+            log_index: None,
+            ..rpc_log
+        };
+        assert!(Log::from_rpc(&pending, 100, &HashMap::new()).is_err());
+    }
 }
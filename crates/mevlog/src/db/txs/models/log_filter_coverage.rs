@@ -0,0 +1,163 @@
+use eyre::Result;
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+
+/// A block span already scanned by `index --logs-only` for one log filter.
+///
+/// Logs-only indexing saves blocks only when they hold a matching log, so the
+/// `blocks` rows can't tell a scanned block without matches from one never
+/// looked at. Spans scanned for a filter are recorded here instead, keyed by
+/// the filter's canonical form (see `LogFilter::key`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFilterCoverage {
+    pub filter: String,
+    pub from_block: u64,
+    /// Inclusive.
+    pub to_block: u64,
+}
+
+#[hotpath::measure_all(future = true)]
+impl LogFilterCoverage {
+    #[allow(dead_code)] // used in tests
+    pub(crate) async fn count(conn: &SqlitePool) -> Result<i64> {
+        let count = sqlx::query("SELECT COUNT(*) FROM log_filter_coverage")
+            .fetch_one(conn)
+            .await?
+            .get::<i64, _>(0);
+
+        Ok(count)
+    }
+
+    pub(crate) async fn save<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO log_filter_coverage (filter, from_block, to_block)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(&self.filter)
+        .bind(self.from_block as i64)
+        .bind(self.to_block as i64)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Spans of `from..=to` not yet scanned for `filter`, as ascending
+    /// inclusive `(start, end)` ranges.
+    pub(crate) async fn uncovered(
+        filter: &str,
+        from: u64,
+        to: u64,
+        conn: &SqlitePool,
+    ) -> Result<Vec<(u64, u64)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT from_block, to_block FROM log_filter_coverage
+            WHERE filter = ? AND from_block <= ? AND to_block >= ?
+            ORDER BY from_block ASC
+            "#,
+        )
+        .bind(filter)
+        .bind(to as i64)
+        .bind(from as i64)
+        .fetch_all(conn)
+        .await?;
+
+        let mut gaps = vec![];
+        // First block not yet known to be covered; `None` once past `to`.
+        let mut cursor = Some(from);
+        for row in rows {
+            let Some(start) = cursor else {
+                break;
+            };
+            let covered_from = row.try_get::<i64, _>("from_block")? as u64;
+            let covered_to = row.try_get::<i64, _>("to_block")? as u64;
+
+            if covered_from > start {
+                gaps.push((start, covered_from - 1));
+            }
+            if covered_to >= start {
+                cursor = covered_to.checked_add(1).filter(|&next| next <= to);
+            }
+        }
+        if let Some(start) = cursor {
+            gaps.push((start, to));
+        }
+
+        Ok(gaps)
+    }
+
+    pub async fn query_where(where_sql: &str, conn: &SqlitePool) -> Result<Vec<LogFilterCoverage>> {
+        let sql = format!(
+            "SELECT * FROM log_filter_coverage WHERE {where_sql} ORDER BY filter ASC, from_block ASC"
+        );
+
+        let rows = sqlx::query(sqlx::AssertSqlSafe(sql))
+            .fetch_all(conn)
+            .await?;
+        rows.iter().map(Self::from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<LogFilterCoverage> {
+        let from_block: i64 = row.try_get("from_block")?;
+        let to_block: i64 = row.try_get("to_block")?;
+
+        Ok(LogFilterCoverage {
+            filter: row.try_get("filter")?,
+            from_block: from_block as u64,
+            to_block: to_block as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::txs::models::transaction::test::setup_test_db;
+
+    fn coverage(filter: &str, from_block: u64, to_block: u64) -> LogFilterCoverage {
+        LogFilterCoverage {
+            filter: filter.to_string(),
+            from_block,
+            to_block,
+        }
+    }
+
+    #[tokio::test]
+    async fn uncovered_subtracts_overlapping_spans_of_the_same_filter() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        for span in [
+            coverage("a", 110, 119),
+            coverage("a", 115, 129),
+            coverage("a", 150, 160),
+            coverage("b", 100, 200),
+        ] {
+            span.save(&conn).await?;
+        }
+
+        assert_eq!(
+            LogFilterCoverage::uncovered("a", 100, 155, &conn).await?,
+            vec![(100, 109), (130, 149)]
+        );
+        assert_eq!(
+            LogFilterCoverage::uncovered("a", 112, 125, &conn).await?,
+            vec![]
+        );
+        assert_eq!(
+            LogFilterCoverage::uncovered("c", 100, 105, &conn).await?,
+            vec![(100, 105)]
+        );
+        assert_eq!(LogFilterCoverage::count(&conn).await?, 4);
+        assert_eq!(
+            LogFilterCoverage::query_where("filter = 'b'", &conn).await?,
+            vec![coverage("b", 100, 200)]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,410 @@
+use alloy::{sol, sol_types::SolEvent};
+use eyre::Result;
+use revm::primitives::{Address, U256};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+
+use crate::db::txs::models::log::Log;
+
+sol! {
+    interface IERC721 {
+        event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    }
+
+    interface IERC1155 {
+        event TransferSingle(
+            address indexed operator,
+            address indexed from,
+            address indexed to,
+            uint256 id,
+            uint256 value
+        );
+        event TransferBatch(
+            address indexed operator,
+            address indexed from,
+            address indexed to,
+            uint256[] ids,
+            uint256[] values
+        );
+    }
+}
+
+/// One token moved by an ERC721 `Transfer` or ERC1155 `TransferSingle` /
+/// `TransferBatch` log. A batch log yields one row per token id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NftTransfer {
+    pub block_number: u64,
+    pub tx_index: u64,
+    pub log_index: u64,
+    /// Position within a `TransferBatch`; `0` for single transfers.
+    pub batch_index: u64,
+    /// Emitting token contract.
+    pub contract: Address,
+    /// `ERC721` or `ERC1155`.
+    pub standard: String,
+    /// Zero address for mints.
+    pub from_address: Address,
+    /// Zero address for burns.
+    pub to_address: Address,
+    pub token_id: U256,
+    /// Always `1` for ERC721.
+    pub amount: U256,
+}
+
+#[hotpath::measure_all(future = true)]
+impl NftTransfer {
+    /// Decodes the NFT transfers among `logs`. Logs are matched on `topic0`
+    /// and topic count rather than the resolved signature: an ERC721
+    /// `Transfer` shares its signature with ERC20's but indexes the token id
+    /// as a fourth topic. Logs that don't decode are skipped.
+    pub(crate) fn from_logs(logs: &[Log]) -> Vec<NftTransfer> {
+        logs.iter().flat_map(Self::from_log).collect()
+    }
+
+    fn from_log(log: &Log) -> Vec<NftTransfer> {
+        let transfer = |batch_index: usize,
+                        standard: &str,
+                        from_address: Address,
+                        to_address: Address,
+                        token_id: U256,
+                        amount: U256| NftTransfer {
+            block_number: log.block_number,
+            tx_index: log.tx_index,
+            log_index: log.log_index,
+            batch_index: batch_index as u64,
+            contract: log.address,
+            standard: standard.to_string(),
+            from_address,
+            to_address,
+            token_id,
+            amount,
+        };
+        // All three events index three fields, so four topics in total.
+        let [topic0, _, _, _] = log.topics.as_slice() else {
+            return vec![];
+        };
+        let topics = log.topics.iter().copied();
+
+        match *topic0 {
+            hash if hash == IERC721::Transfer::SIGNATURE_HASH => {
+                IERC721::Transfer::decode_raw_log(topics, &log.data)
+                    .map(|event| {
+                        vec![transfer(
+                            0,
+                            "ERC721",
+                            event.from,
+                            event.to,
+                            event.tokenId,
+                            U256::from(1),
+                        )]
+                    })
+                    .unwrap_or_default()
+            }
+            hash if hash == IERC1155::TransferSingle::SIGNATURE_HASH => {
+                IERC1155::TransferSingle::decode_raw_log(topics, &log.data)
+                    .map(|event| {
+                        vec![transfer(
+                            0,
+                            "ERC1155",
+                            event.from,
+                            event.to,
+                            event.id,
+                            event.value,
+                        )]
+                    })
+                    .unwrap_or_default()
+            }
+            hash if hash == IERC1155::TransferBatch::SIGNATURE_HASH => {
+                let Ok(event) = IERC1155::TransferBatch::decode_raw_log(topics, &log.data) else {
+                    return vec![];
+                };
+                // Mismatched arrays are malformed; there is no pairing to trust.
+                if event.ids.len() != event.values.len() {
+                    return vec![];
+                }
+                event
+                    .ids
+                    .iter()
+                    .zip(&event.values)
+                    .enumerate()
+                    .map(|(idx, (id, value))| {
+                        transfer(idx, "ERC1155", event.from, event.to, *id, *value)
+                    })
+                    .collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    #[allow(dead_code)] // used in tests
+    pub(crate) async fn count(conn: &SqlitePool) -> Result<i64> {
+        let count = sqlx::query("SELECT COUNT(*) FROM nft_transfers")
+            .fetch_one(conn)
+            .await?
+            .get::<i64, _>(0);
+
+        Ok(count)
+    }
+
+    pub(crate) async fn save<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO nft_transfers (
+                block_number, tx_index, log_index, batch_index, contract, standard,
+                from_address, to_address, token_id, amount
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(block_number, log_index, batch_index) DO NOTHING
+            "#,
+        )
+        .bind(self.block_number as i64)
+        .bind(self.tx_index as i64)
+        .bind(self.log_index as i64)
+        .bind(self.batch_index as i64)
+        .bind(self.contract.as_slice())
+        .bind(&self.standard)
+        .bind(self.from_address.as_slice())
+        .bind(self.to_address.as_slice())
+        .bind(self.token_id.to_be_bytes::<32>().to_vec())
+        .bind(self.amount.to_be_bytes::<32>().to_vec())
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn save_batch(transfers: &[NftTransfer], conn: &SqlitePool) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        for transfer in transfers {
+            transfer.save(&mut *db_tx).await?;
+        }
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn query_where(where_sql: &str, conn: &SqlitePool) -> Result<Vec<NftTransfer>> {
+        let sql = format!(
+            "SELECT * FROM nft_transfers WHERE {where_sql} \
+             ORDER BY block_number DESC, log_index ASC, batch_index ASC"
+        );
+
+        let rows = sqlx::query(sqlx::AssertSqlSafe(sql))
+            .fetch_all(conn)
+            .await?;
+        rows.iter().map(Self::from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<NftTransfer> {
+        let block_number: i64 = row.try_get("block_number")?;
+        let tx_index: i64 = row.try_get("tx_index")?;
+        let log_index: i64 = row.try_get("log_index")?;
+        let batch_index: i64 = row.try_get("batch_index")?;
+        let contract: Vec<u8> = row.try_get("contract")?;
+        let from_address: Vec<u8> = row.try_get("from_address")?;
+        let to_address: Vec<u8> = row.try_get("to_address")?;
+        let token_id: Vec<u8> = row.try_get("token_id")?;
+        let amount: Vec<u8> = row.try_get("amount")?;
+
+        Ok(NftTransfer {
+            block_number: block_number as u64,
+            tx_index: tx_index as u64,
+            log_index: log_index as u64,
+            batch_index: batch_index as u64,
+            contract: Address::from_slice(&contract),
+            standard: row.try_get("standard")?,
+            from_address: Address::from_slice(&from_address),
+            to_address: Address::from_slice(&to_address),
+            token_id: U256::from_be_slice(&token_id),
+            amount: U256::from_be_slice(&amount),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloy::sol_types::SolValue;
+    use revm::primitives::FixedBytes;
+
+    use super::*;
+    use crate::db::txs::{fixtures::sample_log, models::transaction::test::setup_test_db};
+
+    const ALICE: Address = Address::new([0xaa; 20]);
+    const BOB: Address = Address::new([0xbb; 20]);
+
+    fn log(log_index: u64, topics: Vec<FixedBytes<32>>, data: Vec<u8>) -> Log {
+        Log {
+            log_index,
+            address: Address::from([0x70; 20]),
+            topics,
+            data,
+            ..sample_log(100)
+        }
+    }
+
+    #[test]
+    fn from_logs_decodes_erc721_and_expands_batches() {
+        let erc721 = log(
+            0,
+            vec![
+                IERC721::Transfer::SIGNATURE_HASH,
+                ALICE.into_word(),
+                BOB.into_word(),
+                U256::from(42).into(),
+            ],
+            vec![],
+        );
+        // An ERC20 Transfer shares topic0 but has three topics.
+        let erc20 = log(
+            1,
+            vec![
+                IERC721::Transfer::SIGNATURE_HASH,
+                ALICE.into_word(),
+                BOB.into_word(),
+            ],
+            U256::from(5).to_be_bytes::<32>().to_vec(),
+        );
+        let batch = log(
+            2,
+            vec![
+                IERC1155::TransferBatch::SIGNATURE_HASH,
+                ALICE.into_word(),
+                Address::ZERO.into_word(),
+                BOB.into_word(),
+            ],
+            (
+                vec![U256::from(1), U256::from(2)],
+                vec![U256::from(10), U256::from(20)],
+            )
+                .abi_encode_params(),
+        );
+
+        let transfers = NftTransfer::from_logs(&[erc721, erc20, batch]);
+
+        let shape: Vec<(u64, u64, &str, Address, U256, U256)> = transfers
+            .iter()
+            .map(|t| {
+                (
+                    t.log_index,
+                    t.batch_index,
+                    t.standard.as_str(),
+                    t.from_address,
+                    t.token_id,
+                    t.amount,
+                )
+            })
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                (0, 0, "ERC721", ALICE, U256::from(42), U256::from(1)),
+                (
+                    2,
+                    0,
+                    "ERC1155",
+                    Address::ZERO,
+                    U256::from(1),
+                    U256::from(10)
+                ),
+                (
+                    2,
+                    1,
+                    "ERC1155",
+                    Address::ZERO,
+                    U256::from(2),
+                    U256::from(20)
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn backfill_migration_matches_from_logs() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+        let logs = vec![
+            log(
+                0,
+                vec![
+                    IERC721::Transfer::SIGNATURE_HASH,
+                    ALICE.into_word(),
+                    BOB.into_word(),
+                    U256::from(42).into(),
+                ],
+                vec![],
+            ),
+            log(
+                1,
+                vec![
+                    IERC721::Transfer::SIGNATURE_HASH,
+                    ALICE.into_word(),
+                    BOB.into_word(),
+                ],
+                U256::from(5).to_be_bytes::<32>().to_vec(),
+            ),
+            log(
+                2,
+                vec![
+                    IERC1155::TransferSingle::SIGNATURE_HASH,
+                    ALICE.into_word(),
+                    ALICE.into_word(),
+                    BOB.into_word(),
+                ],
+                (U256::from(7), U256::from(3)).abi_encode_params(),
+            ),
+            log(
+                3,
+                vec![
+                    IERC1155::TransferBatch::SIGNATURE_HASH,
+                    ALICE.into_word(),
+                    Address::ZERO.into_word(),
+                    BOB.into_word(),
+                ],
+                (
+                    vec![U256::from(1), U256::from(2)],
+                    vec![U256::from(10), U256::from(20)],
+                )
+                    .abi_encode_params(),
+            ),
+        ];
+        Log::save_batch(&logs, &conn).await?;
+
+        // Logs stored before the table existed are decoded by the migration.
+        sqlx::raw_sql(include_str!(
+            "../../../../migrations/txs/20261017130000_backfill_nft_transfers.up.sql"
+        ))
+        .execute(&conn)
+        .await?;
+
+        let found = NftTransfer::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, NftTransfer::from_logs(&logs));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn save_batch_roundtrips_and_is_idempotent() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+        let single = log(
+            3,
+            vec![
+                IERC1155::TransferSingle::SIGNATURE_HASH,
+                ALICE.into_word(),
+                ALICE.into_word(),
+                BOB.into_word(),
+            ],
+            (U256::from(7), U256::from(3)).abi_encode_params(),
+        );
+        let transfers = NftTransfer::from_logs(&[single]);
+
+        NftTransfer::save_batch(&transfers, &conn).await?;
+        NftTransfer::save_batch(&transfers, &conn).await?;
+
+        assert_eq!(NftTransfer::count(&conn).await?, 1);
+        let found = NftTransfer::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, transfers);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use eyre::Result;
+use revm::primitives::Address;
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+
+/// ERC20 metadata, resolved once per token via `eth_call`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub address: Address,
+    /// `None` when the call reverted or returned something undecodable.
+    pub symbol: Option<String>,
+    pub name: Option<String>,
+    pub decimals: Option<u8>,
+}
+
+#[hotpath::measure_all(future = true)]
+impl Token {
+    /// Distinct contracts with an ERC20 `Transfer` log in `from..=to` and no
+    /// `tokens` row yet.
+    pub(crate) async fn unknown_erc20_addresses(
+        from: u64,
+        to: u64,
+        conn: &SqlitePool,
+    ) -> Result<Vec<Address>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT address FROM logs
+            WHERE block_number BETWEEN ? AND ?
+                AND erc20_amount IS NOT NULL
+                AND address NOT IN (SELECT address FROM tokens)
+            "#,
+        )
+        .bind(from as i64)
+        .bind(to as i64)
+        .fetch_all(conn)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let address: Vec<u8> = row.try_get("address")?;
+                Ok(Address::from_slice(&address))
+            })
+            .collect()
+    }
+
+    #[allow(dead_code)] // used in tests
+    pub(crate) async fn count(conn: &SqlitePool) -> Result<i64> {
+        let count = sqlx::query("SELECT COUNT(*) FROM tokens")
+            .fetch_one(conn)
+            .await?
+            .get::<i64, _>(0);
+
+        Ok(count)
+    }
+
+    pub(crate) async fn save<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO tokens (address, symbol, name, decimals)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(address) DO NOTHING
+            "#,
+        )
+        .bind(self.address.as_slice())
+        .bind(self.symbol.as_deref())
+        .bind(self.name.as_deref())
+        .bind(self.decimals.map(|d| d as i64))
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn save_batch(tokens: &[Token], conn: &SqlitePool) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        for token in tokens {
+            token.save(&mut *db_tx).await?;
+        }
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn query_where(where_sql: &str, conn: &SqlitePool) -> Result<Vec<Token>> {
+        let sql = format!("SELECT * FROM tokens WHERE {where_sql} ORDER BY address ASC");
+
+        let rows = sqlx::query(sqlx::AssertSqlSafe(sql))
+            .fetch_all(conn)
+            .await?;
+        rows.iter().map(Self::from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Token> {
+        let address: Vec<u8> = row.try_get("address")?;
+        let decimals: Option<i64> = row.try_get("decimals")?;
+
+        Ok(Token {
+            address: Address::from_slice(&address),
+            symbol: row.try_get("symbol")?,
+            name: row.try_get("name")?,
+            decimals: decimals.map(|d| d as u8),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use revm::primitives::{FixedBytes, U256};
+
+    use super::*;
+    use crate::db::txs::{
+        fixtures::sample_log,
+        models::{log::Log, transaction::test::setup_test_db},
+    };
+
+    const USDC: Address = Address::new([0x70; 20]);
+
+    fn transfer_log(log_index: u64, address: Address, erc20_amount: Option<U256>) -> Log {
+        Log {
+            log_index,
+            address,
+            topics: vec![FixedBytes::ZERO],
+            data: vec![],
+            erc20_amount,
+            ..sample_log(100)
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_erc20_addresses_skips_resolved_and_non_transfer_logs() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+        let other = Address::new([0x71; 20]);
+        Log::save_batch(
+            &[
+                transfer_log(0, USDC, Some(U256::from(1))),
+                transfer_log(1, other, Some(U256::from(2))),
+                transfer_log(2, Address::new([0x72; 20]), None),
+            ],
+            &conn,
+        )
+        .await?;
+        let usdc = Token {
+            address: USDC,
+            symbol: Some("USDC".to_string()),
+            name: Some("USD Coin".to_string()),
+            decimals: Some(6),
+        };
+        Token::save_batch(std::slice::from_ref(&usdc), &conn).await?;
+        Token::save_batch(std::slice::from_ref(&usdc), &conn).await?;
+
+        assert_eq!(
+            Token::unknown_erc20_addresses(100, 100, &conn).await?,
+            vec![other]
+        );
+        assert_eq!(Token::count(&conn).await?, 1);
+        assert_eq!(Token::query_where("1 = 1", &conn).await?, vec![usdc]);
+
+        Ok(())
+    }
+}
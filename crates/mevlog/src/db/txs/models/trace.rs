@@ -0,0 +1,391 @@
+use std::collections::HashSet;
+
+use alloy::rpc::types::trace::{
+    geth::CallFrame,
+    parity::{Action, CallType, CreationMethod, TraceOutput, TransactionTrace},
+};
+use eyre::Result;
+use revm::primitives::{Address, FixedBytes, U256};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+
+use crate::db::{
+    sigs::models::method::Method,
+    txs::models::{
+        block::{Block, BlockMarker},
+        transaction::{UNKNOWN_SIGNATURE, find_sig_overwrite},
+    },
+};
+
+/// One call frame of a tx's execution, root call included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub block_number: u64,
+    pub tx_index: u64,
+    /// Position in the call tree as dot-separated child indexes, e.g. `0.2` is
+    /// the third subcall of the root's first subcall. Empty for the root.
+    pub trace_address: String,
+    /// Nesting level; `0` for the root call.
+    pub depth: u64,
+    /// `CALL`, `STATICCALL`, `DELEGATECALL`, `CALLCODE`, `CREATE`, `CREATE2`
+    /// or `SELFDESTRUCT`.
+    pub call_type: String,
+    pub from_address: Address,
+    /// Callee, the deployed contract for creates or the beneficiary for
+    /// selfdestructs. `None` when a create failed.
+    pub to_address: Option<Address>,
+    pub value: Option<U256>,
+    pub gas_used: u64,
+    /// First 4 calldata bytes of a call. `None` for creates, selfdestructs and
+    /// calls without calldata.
+    pub selector: Option<FixedBytes<4>>,
+    /// Resolved method signature; `CREATE()` for creates.
+    pub signature: Option<String>,
+    /// Whether the frame itself finished without an error. A frame that
+    /// succeeded under a reverted parent was still rolled back.
+    pub success: bool,
+}
+
+#[hotpath::measure_all(future = true)]
+impl Trace {
+    /// Flattens a geth `callTracer` tree into rows, parents before children.
+    /// Signatures are left for [`Self::resolve_signatures`].
+    pub(crate) fn from_call_frame(
+        root: &CallFrame,
+        block_number: u64,
+        tx_index: u64,
+    ) -> Vec<Trace> {
+        let mut traces = Vec::new();
+        collect_frames(root, &mut vec![], block_number, tx_index, &mut traces);
+        traces
+    }
+
+    /// Converts a Revm (parity-style) trace list into rows. Signatures are
+    /// left for [`Self::resolve_signatures`].
+    pub(crate) fn from_parity(
+        traces: &[TransactionTrace],
+        block_number: u64,
+        tx_index: u64,
+    ) -> Vec<Trace> {
+        traces
+            .iter()
+            .filter_map(|trace| {
+                let (call_type, from_address, to_address, value, selector) = match &trace.action {
+                    Action::Call(call) => (
+                        parity_call_type(&call.call_type),
+                        call.from,
+                        Some(call.to),
+                        call.value,
+                        call.input.get(..4).map(FixedBytes::<4>::from_slice),
+                    ),
+                    Action::Create(create) => {
+                        let call_type = match create.creation_method {
+                            CreationMethod::Create2 => "CREATE2",
+                            _ => "CREATE",
+                        };
+                        let created = match &trace.result {
+                            Some(TraceOutput::Create(output)) => Some(output.address),
+                            _ => None,
+                        };
+                        (call_type, create.from, created, create.value, None)
+                    }
+                    Action::Selfdestruct(selfdestruct) => (
+                        "SELFDESTRUCT",
+                        selfdestruct.address,
+                        Some(selfdestruct.refund_address),
+                        selfdestruct.balance,
+                        None,
+                    ),
+                    _ => return None,
+                };
+                let gas_used = match &trace.result {
+                    Some(TraceOutput::Call(output)) => output.gas_used,
+                    Some(TraceOutput::Create(output)) => output.gas_used,
+                    None => 0,
+                };
+
+                Some(Trace {
+                    block_number,
+                    tx_index,
+                    trace_address: format_trace_address(&trace.trace_address),
+                    depth: trace.trace_address.len() as u64,
+                    call_type: call_type.to_string(),
+                    from_address,
+                    to_address,
+                    value: Some(value),
+                    gas_used,
+                    selector,
+                    signature: call_type
+                        .starts_with("CREATE")
+                        .then(|| "CREATE()".to_string()),
+                    success: trace.error.is_none(),
+                })
+            })
+            .collect()
+    }
+
+    /// Fills `signature` for every trace with a selector, resolving the
+    /// distinct selectors with a single [`Method::find_by_selectors`] lookup.
+    pub(crate) async fn resolve_signatures(
+        traces: &mut [Trace],
+        sqlite: &SqlitePool,
+    ) -> Result<()> {
+        let selectors: HashSet<[u8; 4]> = traces
+            .iter()
+            .filter_map(|trace| trace.selector.map(|s| s.0))
+            .collect();
+        let selectors: Vec<[u8; 4]> = selectors.into_iter().collect();
+        let resolved = Method::find_by_selectors(&selectors, sqlite).await?;
+
+        for trace in traces.iter_mut() {
+            let Some(selector) = trace.selector else {
+                continue;
+            };
+            trace.signature = Some(
+                find_sig_overwrite(&format!("0x{}", hex::encode(selector)), trace.tx_index)
+                    .or_else(|| resolved.get(&selector.0).cloned())
+                    .unwrap_or_else(|| UNKNOWN_SIGNATURE.to_string()),
+            );
+        }
+
+        Ok(())
+    }
+
+    #[allow(dead_code)] // used in tests
+    pub(crate) async fn count(conn: &SqlitePool) -> Result<i64> {
+        let count = sqlx::query("SELECT COUNT(*) FROM traces")
+            .fetch_one(conn)
+            .await?
+            .get::<i64, _>(0);
+
+        Ok(count)
+    }
+
+    pub(crate) async fn save<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO traces (
+                block_number, tx_index, trace_address, depth, call_type,
+                from_address, to_address, value, gas_used, selector, signature, success
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(block_number, tx_index, trace_address) DO NOTHING
+            "#,
+        )
+        .bind(self.block_number as i64)
+        .bind(self.tx_index as i64)
+        .bind(&self.trace_address)
+        .bind(self.depth as i64)
+        .bind(&self.call_type)
+        .bind(self.from_address.as_slice())
+        .bind(self.to_address.as_ref().map(|a| a.as_slice()))
+        .bind(self.value.map(|v| v.to_be_bytes::<32>().to_vec()))
+        .bind(self.gas_used as i64)
+        .bind(self.selector.as_ref().map(|s| s.as_slice()))
+        .bind(&self.signature)
+        .bind(self.success)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Saves the traces of a fully traced block and marks the block as traced
+    /// in the same transaction.
+    pub(crate) async fn save_block(
+        block_number: u64,
+        traces: &[Trace],
+        conn: &SqlitePool,
+    ) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        for trace in traces {
+            trace.save(&mut *db_tx).await?;
+        }
+        Block::mark(BlockMarker::Traces, block_number, &mut *db_tx).await?;
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn query_where(where_sql: &str, conn: &SqlitePool) -> Result<Vec<Trace>> {
+        let sql = format!(
+            "SELECT * FROM traces WHERE {where_sql} \
+             ORDER BY block_number DESC, tx_index ASC, rowid ASC"
+        );
+
+        let rows = sqlx::query(sqlx::AssertSqlSafe(sql))
+            .fetch_all(conn)
+            .await?;
+        rows.iter().map(Self::from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Trace> {
+        let block_number: i64 = row.try_get("block_number")?;
+        let tx_index: i64 = row.try_get("tx_index")?;
+        let depth: i64 = row.try_get("depth")?;
+        let from_address: Vec<u8> = row.try_get("from_address")?;
+        let to_address: Option<Vec<u8>> = row.try_get("to_address")?;
+        let value: Option<Vec<u8>> = row.try_get("value")?;
+        let gas_used: i64 = row.try_get("gas_used")?;
+        let selector: Option<Vec<u8>> = row.try_get("selector")?;
+
+        Ok(Trace {
+            block_number: block_number as u64,
+            tx_index: tx_index as u64,
+            trace_address: row.try_get("trace_address")?,
+            depth: depth as u64,
+            call_type: row.try_get("call_type")?,
+            from_address: Address::from_slice(&from_address),
+            to_address: to_address.map(|a| Address::from_slice(&a)),
+            value: value.map(|v| U256::from_be_slice(&v)),
+            gas_used: gas_used as u64,
+            selector: selector.map(|s| FixedBytes::<4>::from_slice(&s)),
+            signature: row.try_get("signature")?,
+            success: row.try_get("success")?,
+        })
+    }
+}
+
+fn collect_frames(
+    frame: &CallFrame,
+    path: &mut Vec<usize>,
+    block_number: u64,
+    tx_index: u64,
+    traces: &mut Vec<Trace>,
+) {
+    let call_type = frame.typ.to_uppercase();
+    let is_create = call_type.starts_with("CREATE");
+    let selector = if is_create || call_type == "SELFDESTRUCT" {
+        None
+    } else {
+        frame.input.get(..4).map(FixedBytes::<4>::from_slice)
+    };
+
+    traces.push(Trace {
+        block_number,
+        tx_index,
+        trace_address: format_trace_address(path),
+        depth: path.len() as u64,
+        call_type,
+        from_address: frame.from,
+        to_address: frame.to,
+        value: frame.value,
+        gas_used: frame.gas_used.saturating_to(),
+        selector,
+        signature: is_create.then(|| "CREATE()".to_string()),
+        success: frame.error.is_none(),
+    });
+
+    for (idx, call) in frame.calls.iter().enumerate() {
+        path.push(idx);
+        collect_frames(call, path, block_number, tx_index, traces);
+        path.pop();
+    }
+}
+
+fn format_trace_address(path: &[usize]) -> String {
+    path.iter()
+        .map(|idx| idx.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn parity_call_type(call_type: &CallType) -> &'static str {
+    match call_type {
+        CallType::StaticCall => "STATICCALL",
+        CallType::DelegateCall => "DELEGATECALL",
+        CallType::CallCode => "CALLCODE",
+        _ => "CALL",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use revm::primitives::Bytes;
+
+    use super::*;
+    use crate::db::txs::models::transaction::test::setup_test_db;
+
+    fn frame(typ: &str, to: u8, input: &[u8], calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame {
+            from: Address::from([0x01; 20]),
+            to: Some(Address::from([to; 20])),
+            input: Bytes::copy_from_slice(input),
+            value: Some(U256::ZERO),
+            gas_used: U256::from(21_000),
+            typ: typ.to_string(),
+            calls,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn from_call_frame_flattens_tree_with_addresses() {
+        let mut reverted = frame("STATICCALL", 0x04, &[0xde, 0xad, 0xbe, 0xef, 0x00], vec![]);
+        reverted.error = Some("execution reverted".to_string());
+        let root = frame(
+            "CALL",
+            0x02,
+            &[0xa9, 0x05, 0x9c, 0xbb],
+            vec![
+                frame("DELEGATECALL", 0x03, &[], vec![reverted]),
+                frame("CREATE2", 0x05, &[0x60, 0x80, 0x60, 0x40], vec![]),
+            ],
+        );
+
+        let traces = Trace::from_call_frame(&root, 100, 3);
+
+        let shape: Vec<(&str, u64, &str, bool)> = traces
+            .iter()
+            .map(|t| {
+                (
+                    t.trace_address.as_str(),
+                    t.depth,
+                    t.call_type.as_str(),
+                    t.success,
+                )
+            })
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                ("", 0, "CALL", true),
+                ("0", 1, "DELEGATECALL", true),
+                ("0.0", 2, "STATICCALL", false),
+                ("1", 1, "CREATE2", true),
+            ]
+        );
+        assert_eq!(
+            traces[0].selector,
+            Some(FixedBytes::from([0xa9, 0x05, 0x9c, 0xbb]))
+        );
+        assert_eq!(traces[1].selector, None);
+        assert_eq!(traces[3].selector, None);
+        assert_eq!(traces[3].signature.as_deref(), Some("CREATE()"));
+    }
+
+    #[tokio::test]
+    async fn save_block_roundtrips_and_is_idempotent() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let root = frame(
+            "CALL",
+            0x02,
+            &[0xa9, 0x05, 0x9c, 0xbb],
+            vec![frame("CALL", 0x03, &[], vec![])],
+        );
+        let mut traces = Trace::from_call_frame(&root, 100, 0);
+        traces[0].signature = Some("transfer(address,uint256)".to_string());
+
+        Trace::save_block(100, &traces, &conn).await?;
+        Trace::save_block(100, &traces, &conn).await?;
+
+        assert_eq!(Trace::count(&conn).await?, 2);
+        let found = Trace::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, traces);
+
+        Ok(())
+    }
+}
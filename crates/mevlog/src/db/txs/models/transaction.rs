@@ -1,14 +1,27 @@
-use std::str::FromStr;
-
-use alloy::rlp::Encodable;
-use arrow::record_batch::RecordBatch;
-use eyre::Result;
+use std::{collections::HashMap, str::FromStr};
+
+use alloy::{
+    consensus::Transaction as TxTrait,
+    eips::Typed2718,
+    network::{AnyRpcTransaction, AnyTransactionReceipt, ReceiptResponse, TransactionResponse},
+    rlp::Encodable,
+};
+use arrow::{
+    array::{Array, LargeBinaryArray, UInt64Array},
+    record_batch::RecordBatch,
+};
+use eyre::{Result, eyre};
 use revm::primitives::{Address, Bytes, FixedBytes, TxKind, U256, keccak256};
 use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
 
-use crate::{db::sigs::models::method::Method, misc::parquet_utils::get_parquet_string_value};
+use crate::{
+    db::sigs::models::method::Method,
+    misc::parquet_utils::{
+        binary_column, bool_column, bytes_at, required, string_column, u64_at, u64_column,
+    },
+};
 
-const UNKNOWN_SIGNATURE: &str = "?";
+pub(crate) const UNKNOWN_SIGNATURE: &str = "?";
 
 /// Basic SQLite-backed transaction record.
 ///
@@ -26,6 +39,9 @@ pub struct Transaction {
     pub value: U256,
     pub gas_limit: u64,
     pub gas_used: u64,
+    /// Price per gas actually paid, as reported by the receipt. Derived with
+    /// [`Self::apply_base_fee`] for `cryo`-indexed txs until their receipts are
+    /// backfilled.
     pub effective_gas_price: u128,
     pub gas_price: u128,
     pub max_fee_per_gas: u128,
@@ -41,67 +57,211 @@ pub struct Transaction {
     /// U256. `None` = not traced (no `--evm-trace`, or the trace failed);
     /// `Some(0)` = traced, no coinbase payment; `Some(n)` = the bribe amount.
     pub coinbase_transfer: Option<U256>,
+    /// EIP-4844 blob gas consumed (receipt `blobGasUsed`). `None` for non-blob
+    /// txs, and for txs indexed with the `cryo` fetcher until their receipts
+    /// are backfilled (see [`ReceiptFees`]), as its parquet output carries no
+    /// blob fields.
+    pub blob_gas_used: Option<u64>,
+    /// Price per blob gas paid (receipt `blobGasPrice`). `None` as above.
+    pub blob_gas_price: Option<u128>,
+    /// Sender's blob gas price cap. `None` as above.
+    pub max_fee_per_blob_gas: Option<u128>,
+    /// Full calldata. `None` when calldata storage is disabled (`--skip-input`
+    /// or `store_input = false`) or the tx was indexed before the column existed.
+    pub input: Option<Bytes>,
 }
 
 #[hotpath::measure_all(future = true)]
 impl Transaction {
-    // Parquet columns: 0 block_number, 1 transaction_index, 2 transaction_hash,
-    // 3 nonce, 4 from_address, 5 to_address, 7 value_string, 9 input,
-    // 10 gas_limit, 11 gas_used, 12 gas_price, 13 transaction_type,
-    // 14 max_priority_fee_per_gas, 15 max_fee_per_gas, 16 success.
-    pub(crate) async fn from_parquet_row(
+    /// Parses a cryo `transactions` record batch. Columns are decoded once per
+    /// batch and the distinct selectors resolved with a single
+    /// [`Method::find_by_selectors`] lookup, instead of one sigs DB query per
+    /// row.
+    pub(crate) async fn from_parquet_batch(
         batch: &RecordBatch,
-        row_idx: usize,
         sqlite: &SqlitePool,
-    ) -> Result<(Transaction, u64)> {
-        let get = |col_idx: usize| -> String { get_parquet_string_value(batch, col_idx, row_idx) };
-
-        let block_number = get(0).parse::<u64>().unwrap();
-        let tx_index = get(1).parse::<u64>().unwrap();
-        let tx_hash = FixedBytes::<32>::from_str(&get(2)).unwrap();
-        let nonce = get(3).parse::<u64>().unwrap();
-        let from_address = Address::from_str(&get(4)).unwrap();
-
-        let to_str = get(5);
-        let to = if to_str == "0x" || to_str.is_empty() {
-            TxKind::Create
-        } else {
-            TxKind::Call(Address::from_str(&to_str).unwrap())
+    ) -> Result<Vec<Transaction>> {
+        let col = |name: &str| -> Result<UInt64Array> {
+            required(u64_column(batch, name)?, "transactions", name)
+        };
+        let bin = |name: &str| -> Result<LargeBinaryArray> {
+            required(binary_column(batch, name)?, "transactions", name)
         };
 
-        let input = Bytes::from_str(&get(9)).unwrap();
-        let (signature_hash, signature) =
-            extract_signature(Some(&input), tx_index, Some(to), sqlite).await?;
+        let block_numbers = col("block_number")?;
+        let tx_indexes = col("transaction_index")?;
+        let tx_hashes = bin("transaction_hash")?;
+        let nonces = col("nonce")?;
+        let from_addresses = bin("from_address")?;
+        let to_addresses = binary_column(batch, "to_address")?;
+        let values = required(
+            string_column(batch, "value_string")?,
+            "transactions",
+            "value_string",
+        )?;
+        let inputs = binary_column(batch, "input")?;
+        let gas_limits = col("gas_limit")?;
+        let gas_used = col("gas_used")?;
+        let gas_prices = col("gas_price")?;
+        let tx_types = u64_column(batch, "transaction_type")?;
+        let max_priority_fees = u64_column(batch, "max_priority_fee_per_gas")?;
+        let max_fees = u64_column(batch, "max_fee_per_gas")?;
+        let successes = required(bool_column(batch, "success")?, "transactions", "success")?;
+
+        let null = |name: &str| eyre!("cryo transactions parquet has a null '{name}'");
+        let kind = |row_idx: usize| -> Result<TxKind> {
+            Ok(match bytes_at(to_addresses.as_ref(), row_idx) {
+                None | Some([]) => TxKind::Create,
+                Some(to) => TxKind::Call(Address::try_from(to)?),
+            })
+        };
+        let calldata = |row_idx: usize| bytes_at(inputs.as_ref(), row_idx).unwrap_or_default();
+
+        let mut selectors = vec![];
+        for row_idx in 0..batch.num_rows() {
+            if let (TxKind::Call(_), Some(selector)) = (kind(row_idx)?, calldata(row_idx).get(..4))
+            {
+                selectors.push(<[u8; 4]>::try_from(selector)?);
+            }
+        }
+        let resolved = Method::find_by_selectors(&selectors, sqlite).await?;
+
+        (0..batch.num_rows())
+            .map(|row_idx| {
+                let tx_index =
+                    u64_at(&tx_indexes, row_idx).ok_or_else(|| null("transaction_index"))?;
+                let nonce = u64_at(&nonces, row_idx).ok_or_else(|| null("nonce"))?;
+                let from_address = Address::try_from(
+                    bytes_at(Some(&from_addresses), row_idx).ok_or_else(|| null("from_address"))?,
+                )?;
+
+                let to = kind(row_idx)?;
+                let input = Bytes::copy_from_slice(calldata(row_idx));
+                let (signature_hash, signature) =
+                    resolve_signature(&input, tx_index, to, &resolved);
+
+                let to_address = match to {
+                    TxKind::Call(address) => Some(address),
+                    TxKind::Create => Some(calculate_create_address(nonce, from_address)),
+                };
+
+                let gas_price =
+                    u64_at(&gas_prices, row_idx).ok_or_else(|| null("gas_price"))? as u128;
+                let optional =
+                    |column: &Option<UInt64Array>| column.as_ref().and_then(|c| u64_at(c, row_idx));
+
+                Ok(Transaction {
+                    block_number: u64_at(&block_numbers, row_idx)
+                        .ok_or_else(|| null("block_number"))?,
+                    tx_index,
+                    tx_hash: FixedBytes::<32>::try_from(
+                        bytes_at(Some(&tx_hashes), row_idx)
+                            .ok_or_else(|| null("transaction_hash"))?,
+                    )?,
+                    nonce,
+                    from_address,
+                    to_address,
+                    value: U256::from_str(
+                        values
+                            .is_valid(row_idx)
+                            .then(|| values.value(row_idx))
+                            .ok_or_else(|| null("value_string"))?,
+                    )?,
+                    gas_limit: u64_at(&gas_limits, row_idx).ok_or_else(|| null("gas_limit"))?,
+                    gas_used: u64_at(&gas_used, row_idx).ok_or_else(|| null("gas_used"))?,
+                    // Fixed up from the block base fee via `apply_base_fee` once the
+                    // batch's blocks are parsed.
+                    effective_gas_price: gas_price,
+                    gas_price,
+                    max_fee_per_gas: optional(&max_fees).unwrap_or(0) as u128,
+                    max_priority_fee_per_gas: optional(&max_priority_fees).unwrap_or(0) as u128,
+                    transaction_type: optional(&tx_types).and_then(|t| u8::try_from(t).ok()),
+                    success: successes
+                        .is_valid(row_idx)
+                        .then(|| successes.value(row_idx))
+                        .ok_or_else(|| null("success"))?,
+                    signature_hash,
+                    signature,
+                    coinbase_transfer: None,
+                    blob_gas_used: None,
+                    blob_gas_price: None,
+                    max_fee_per_blob_gas: None,
+                    input: Some(input),
+                })
+            })
+            .collect()
+    }
+
+    /// Sets `effective_gas_price` to what the receipt's `effectiveGasPrice`
+    /// reports for the tx: `min(max_fee_per_gas, base_fee + max_priority_fee_per_gas)`
+    /// for EIP-1559 style txs. Legacy txs (and pre-London blocks) pay
+    /// `gas_price`, which is left as is.
+    pub(crate) fn apply_base_fee(&mut self, base_fee_per_gas: Option<u64>) {
+        if let Some(base_fee) = base_fee_per_gas
+            && self.max_fee_per_gas > 0
+        {
+            self.effective_gas_price = self
+                .max_fee_per_gas
+                .min(base_fee as u128 + self.max_priority_fee_per_gas);
+        }
+    }
+
+    /// Builds a tx from a full-block JSON-RPC transaction and its receipt; the
+    /// native-fetcher counterpart of [`Self::from_parquet_batch`]. Gas prices
+    /// mirror cryo's columns: `gas_price` is the price actually paid, and the
+    /// EIP-1559 caps are zero for legacy txs. Blob fee fields come from the
+    /// receipt and tx body; cryo-indexed txs get them via [`ReceiptFees`].
+    /// Selectors are resolved up front with [`Method::find_by_selectors`].
+    pub(crate) fn from_rpc(
+        tx: &AnyRpcTransaction,
+        receipt: &AnyTransactionReceipt,
+        block_number: u64,
+        resolved: &HashMap<[u8; 4], String>,
+    ) -> Transaction {
+        let tx_index = tx.transaction_index().unwrap_or_default();
+        let nonce = TxTrait::nonce(tx);
+        let from_address = tx.from();
+        let to = TxTrait::kind(tx);
+        let input = TxTrait::input(tx);
+
+        let (signature_hash, signature) = resolve_signature(input, tx_index, to, resolved);
 
         let to_address = match to {
             TxKind::Call(address) => Some(address),
             TxKind::Create => Some(calculate_create_address(nonce, from_address)),
         };
 
-        let gas_price = get(12).parse::<u128>().unwrap();
+        let gas_price = receipt.effective_gas_price;
+        let max_fee_per_gas = if TxTrait::is_dynamic_fee(tx) {
+            TxTrait::max_fee_per_gas(tx)
+        } else {
+            0
+        };
 
-        let tx = Transaction {
+        Transaction {
             block_number,
             tx_index,
-            tx_hash,
+            tx_hash: tx.tx_hash(),
             nonce,
             from_address,
             to_address,
-            value: U256::from_str(&get(7)).unwrap(),
-            gas_limit: get(10).parse::<u64>().unwrap(),
-            gas_used: get(11).parse::<u64>().unwrap(),
+            value: TxTrait::value(tx),
+            gas_limit: TxTrait::gas_limit(tx),
+            gas_used: receipt.gas_used,
             effective_gas_price: gas_price,
             gas_price,
-            max_fee_per_gas: get(15).parse::<u128>().unwrap_or(0),
-            max_priority_fee_per_gas: get(14).parse::<u128>().unwrap_or(0),
-            transaction_type: get(13).parse::<u8>().ok(),
-            success: get(16).parse::<bool>().unwrap(),
+            max_fee_per_gas,
+            max_priority_fee_per_gas: TxTrait::max_priority_fee_per_gas(tx).unwrap_or(0),
+            transaction_type: Some(tx.ty()),
+            success: receipt.status(),
             signature_hash,
             signature,
             coinbase_transfer: None,
-        };
-
-        Ok((tx, block_number))
+            blob_gas_used: receipt.blob_gas_used,
+            blob_gas_price: receipt.blob_gas_price,
+            max_fee_per_blob_gas: TxTrait::max_fee_per_blob_gas(tx),
+            input: Some(input.clone()),
+        }
     }
 
     #[allow(dead_code)] // used in tests
@@ -126,11 +286,15 @@ impl Transaction {
             Ok(gas_price),
             Ok(max_fee_per_gas),
             Ok(max_priority_fee_per_gas),
+            Ok(blob_gas_price),
+            Ok(max_fee_per_blob_gas),
         ) = (
             i64::try_from(self.effective_gas_price),
             i64::try_from(self.gas_price),
             i64::try_from(self.max_fee_per_gas),
             i64::try_from(self.max_priority_fee_per_gas),
+            self.blob_gas_price.map(i64::try_from).transpose(),
+            self.max_fee_per_blob_gas.map(i64::try_from).transpose(),
         )
         else {
             tracing::warn!(
@@ -146,8 +310,9 @@ impl Transaction {
                 block_number, tx_index, tx_hash, nonce, from_address, to_address,
                 value, gas_limit, gas_used, effective_gas_price, gas_price,
                 max_fee_per_gas, max_priority_fee_per_gas, transaction_type,
-                success, coinbase_transfer, signature_hash, signature
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                success, coinbase_transfer, signature_hash, signature,
+                blob_gas_used, blob_gas_price, max_fee_per_blob_gas, input
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(tx_hash) DO NOTHING
             "#,
         )
@@ -172,6 +337,10 @@ impl Transaction {
         )
         .bind(self.signature_hash.as_ref().map(|s| s.as_slice()))
         .bind(self.signature.as_deref())
+        .bind(self.blob_gas_used.map(|v| v as i64))
+        .bind(blob_gas_price)
+        .bind(max_fee_per_blob_gas)
+        .bind(self.input.as_ref().map(|i| i.as_ref()))
         .execute(executor)
         .await?;
 
@@ -237,6 +406,10 @@ impl Transaction {
         let signature_hash: Option<Vec<u8>> = row.try_get("signature_hash")?;
         let signature: Option<String> = row.try_get("signature")?;
         let coinbase_transfer: Option<Vec<u8>> = row.try_get("coinbase_transfer")?;
+        let blob_gas_used: Option<i64> = row.try_get("blob_gas_used")?;
+        let blob_gas_price: Option<i64> = row.try_get("blob_gas_price")?;
+        let max_fee_per_blob_gas: Option<i64> = row.try_get("max_fee_per_blob_gas")?;
+        let input: Option<Vec<u8>> = row.try_get("input")?;
 
         Ok(Transaction {
             block_number: block_number as u64,
@@ -257,10 +430,74 @@ impl Transaction {
             signature_hash: signature_hash.map(|b| FixedBytes::<4>::from_slice(&b)),
             signature,
             coinbase_transfer: coinbase_transfer.map(|b| U256::from_be_slice(&b)),
+            blob_gas_used: blob_gas_used.map(|v| v as u64),
+            blob_gas_price: blob_gas_price.map(|v| v as u128),
+            max_fee_per_blob_gas: max_fee_per_blob_gas.map(|v| v as u128),
+            input: input.map(Bytes::from),
         })
     }
 }
 
+/// The fee fields of a tx that only its receipt reports, used to fill in txs
+/// saved from cryo datasets (see [`Transaction::apply_base_fee`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ReceiptFees {
+    pub tx_hash: FixedBytes<32>,
+    pub effective_gas_price: u128,
+    pub blob_gas_used: Option<u64>,
+    pub blob_gas_price: Option<u128>,
+    pub max_fee_per_blob_gas: Option<u128>,
+}
+
+impl ReceiptFees {
+    pub(crate) fn from_rpc(tx: &AnyRpcTransaction, receipt: &AnyTransactionReceipt) -> Self {
+        Self {
+            tx_hash: tx.tx_hash(),
+            effective_gas_price: receipt.effective_gas_price,
+            blob_gas_used: receipt.blob_gas_used,
+            blob_gas_price: receipt.blob_gas_price,
+            max_fee_per_blob_gas: TxTrait::max_fee_per_blob_gas(tx),
+        }
+    }
+
+    /// Overwrites the fee columns of the saved tx. Like [`Transaction::save`],
+    /// skips values that don't fit SQLite's signed 64-bit INTEGER.
+    pub(crate) async fn update<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        let (Ok(effective_gas_price), Ok(blob_gas_price), Ok(max_fee_per_blob_gas)) = (
+            i64::try_from(self.effective_gas_price),
+            self.blob_gas_price.map(i64::try_from).transpose(),
+            self.max_fee_per_blob_gas.map(i64::try_from).transpose(),
+        ) else {
+            tracing::warn!(
+                "Skipping fees of tx 0x{}: gas price exceeds i64::MAX, cannot store",
+                hex::encode(self.tx_hash)
+            );
+            return Ok(());
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE transactions
+            SET effective_gas_price = ?, blob_gas_used = ?, blob_gas_price = ?,
+                max_fee_per_blob_gas = ?
+            WHERE tx_hash = ?
+            "#,
+        )
+        .bind(effective_gas_price)
+        .bind(self.blob_gas_used.map(|v| v as i64))
+        .bind(blob_gas_price)
+        .bind(max_fee_per_blob_gas)
+        .bind(self.tx_hash.as_slice())
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
+
 pub(crate) async fn extract_signature(
     input: Option<&Bytes>,
     index: u64,
@@ -292,6 +529,29 @@ pub(crate) async fn extract_signature(
     Ok((signature_hash, signature))
 }
 
+/// [`extract_signature`] for a parquet row or RPC tx, against selectors
+/// resolved up front with [`Method::find_by_selectors`].
+fn resolve_signature(
+    input: &Bytes,
+    index: u64,
+    to: TxKind,
+    resolved: &HashMap<[u8; 4], String>,
+) -> (Option<FixedBytes<4>>, Option<String>) {
+    if to == TxKind::Create {
+        return (None, Some("CREATE()".to_string()));
+    }
+
+    let signature_hash = input.get(..4).map(FixedBytes::<4>::from_slice);
+
+    let signature = signature_hash.map(|hash| {
+        find_sig_overwrite(&format!("0x{}", hex::encode(hash)), index)
+            .or_else(|| resolved.get(&hash.0).cloned())
+            .unwrap_or_else(|| UNKNOWN_SIGNATURE.to_string())
+    });
+
+    (signature_hash, signature)
+}
+
 pub(crate) fn calculate_create_address(nonce: u64, from: Address) -> Address {
     let mut out = Vec::new();
     let list: [&dyn Encodable; 2] = [&from, &U256::from(nonce)];
@@ -316,7 +576,7 @@ pub mod test {
     use uuid::Uuid;
 
     use super::*;
-    use crate::db::txs::{conn, init_db};
+    use crate::db::txs::{conn, fixtures::sample_tx, init_db};
 
     pub(crate) async fn setup_test_db() -> (SqlitePool, SqliteCleaner) {
         let (write, _path, cleaner) = setup_test_db_rw().await;
@@ -371,26 +631,13 @@ pub mod test {
         }
     }
 
-    fn sample_tx(block_number: u64, tx_index: u64, hash_byte: u8) -> Transaction {
+    fn transfer_tx(block_number: u64, tx_index: u64, hash_byte: u8) -> Transaction {
         Transaction {
             block_number,
             tx_index,
             tx_hash: FixedBytes::<32>::from([hash_byte; 32]),
-            nonce: 7,
-            from_address: Address::from([0x11; 20]),
-            to_address: Some(Address::from([0x22; 20])),
-            value: U256::from(1_000_000_000_000_000_000u128),
-            gas_limit: 21_000,
-            gas_used: 21_000,
-            effective_gas_price: 30_000_000_000,
-            gas_price: 30_000_000_000,
-            max_fee_per_gas: 40_000_000_000,
-            max_priority_fee_per_gas: 2_000_000_000,
-            transaction_type: Some(2),
-            success: true,
-            signature_hash: Some(FixedBytes::<4>::from([0xa9, 0x05, 0x9c, 0xbb])),
-            signature: Some("transfer(address,uint256)".to_string()),
-            coinbase_transfer: None,
+            input: Some(Bytes::from_static(&[0xa9, 0x05, 0x9c, 0xbb, 0x00, 0x01])),
+            ..sample_tx(block_number)
         }
     }
 
@@ -398,7 +645,7 @@ pub mod test {
     async fn save_batch_and_query_roundtrips() -> Result<()> {
         let (conn, _cl) = setup_test_db().await;
 
-        let tx = sample_tx(100, 0, 0xaa);
+        let tx = transfer_tx(100, 0, 0xaa);
         Transaction::save_batch(std::slice::from_ref(&tx), &conn).await?;
 
         assert_eq!(Transaction::count(&conn).await?, 1);
@@ -415,9 +662,9 @@ pub mod test {
         let (conn, _cl) = setup_test_db().await;
 
         // None = not traced, Some(n) = traced coinbase payment.
-        let mut untraced = sample_tx(100, 0, 0xaa);
+        let mut untraced = transfer_tx(100, 0, 0xaa);
         untraced.coinbase_transfer = None;
-        let mut bribed = sample_tx(100, 1, 0xbb);
+        let mut bribed = transfer_tx(100, 1, 0xbb);
         bribed.coinbase_transfer = Some(U256::from(123_456_789u64));
 
         Transaction::save_batch(&[untraced.clone(), bribed.clone()], &conn).await?;
@@ -431,11 +678,96 @@ pub mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn blob_fee_fields_roundtrip() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let mut blob_tx = transfer_tx(100, 0, 0xaa);
+        blob_tx.transaction_type = Some(3);
+        blob_tx.blob_gas_used = Some(262_144);
+        blob_tx.blob_gas_price = Some(1_000_000);
+        blob_tx.max_fee_per_blob_gas = Some(5_000_000);
+
+        Transaction::save_batch(std::slice::from_ref(&blob_tx), &conn).await?;
+
+        let found = Transaction::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, vec![blob_tx]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn receipt_fees_update_saved_tx() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let tx = transfer_tx(100, 0, 0xaa);
+        Transaction::save_batch(std::slice::from_ref(&tx), &conn).await?;
+
+        let fees = ReceiptFees {
+            tx_hash: tx.tx_hash,
+            effective_gas_price: 25_000_000_000,
+            blob_gas_used: Some(131_072),
+            blob_gas_price: Some(1_000_000),
+            max_fee_per_blob_gas: Some(5_000_000),
+        };
+        fees.update(&conn).await?;
+
+        let found = Transaction::query_where("block_number = 100", &conn).await?;
+        assert_eq!(
+            found,
+            vec![Transaction {
+                effective_gas_price: 25_000_000_000,
+                blob_gas_used: Some(131_072),
+                blob_gas_price: Some(1_000_000),
+                max_fee_per_blob_gas: Some(5_000_000),
+                ..tx
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn input_roundtrips_and_is_optional() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let with_input = transfer_tx(100, 0, 0xaa);
+        let mut without_input = transfer_tx(100, 1, 0xbb);
+        without_input.input = None;
+
+        Transaction::save_batch(&[with_input.clone(), without_input.clone()], &conn).await?;
+
+        let found = Transaction::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, vec![with_input, without_input]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn apply_base_fee_caps_effective_gas_price() {
+        // base_fee + tip below the cap: pays base_fee + tip.
+        let mut tx = transfer_tx(100, 0, 0xaa);
+        tx.apply_base_fee(Some(10_000_000_000));
+        assert_eq!(tx.effective_gas_price, 12_000_000_000);
+
+        // base_fee + tip above the cap: pays max_fee_per_gas.
+        let mut tx = transfer_tx(100, 0, 0xaa);
+        tx.apply_base_fee(Some(39_000_000_000));
+        assert_eq!(tx.effective_gas_price, 40_000_000_000);
+
+        // Legacy tx: pays gas_price regardless of the base fee.
+        let mut tx = transfer_tx(100, 0, 0xaa);
+        tx.max_fee_per_gas = 0;
+        tx.max_priority_fee_per_gas = 0;
+        tx.apply_base_fee(Some(10_000_000_000));
+        assert_eq!(tx.effective_gas_price, 30_000_000_000);
+    }
+
     #[tokio::test]
     async fn save_batch_is_idempotent() -> Result<()> {
         let (conn, _cl) = setup_test_db().await;
 
-        let tx = sample_tx(100, 0, 0xaa);
+        let tx = transfer_tx(100, 0, 0xaa);
         Transaction::save_batch(std::slice::from_ref(&tx), &conn).await?;
         Transaction::save_batch(std::slice::from_ref(&tx), &conn).await?;
 
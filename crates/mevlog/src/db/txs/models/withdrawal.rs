@@ -0,0 +1,142 @@
+use alloy::{consensus::BlockHeader, network::AnyRpcBlock};
+use eyre::Result;
+use revm::primitives::Address;
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow};
+
+/// One EIP-4895 beacon chain withdrawal credited in a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Withdrawal {
+    pub block_number: u64,
+    /// Monotonic withdrawal index assigned by the consensus layer.
+    pub withdrawal_index: u64,
+    pub validator_index: u64,
+    /// Recipient of the withdrawn ETH.
+    pub address: Address,
+    pub amount_gwei: u64,
+}
+
+#[hotpath::measure_all(future = true)]
+impl Withdrawal {
+    /// Extracts the withdrawals of an `eth_getBlockByNumber` block. Empty for
+    /// pre-Shanghai blocks.
+    pub(crate) fn from_rpc(block: &AnyRpcBlock) -> Vec<Withdrawal> {
+        let block_number = block.header.number();
+        let Some(withdrawals) = block.withdrawals.as_ref() else {
+            return vec![];
+        };
+
+        withdrawals
+            .iter()
+            .map(|withdrawal| Withdrawal {
+                block_number,
+                withdrawal_index: withdrawal.index,
+                validator_index: withdrawal.validator_index,
+                address: withdrawal.address,
+                amount_gwei: withdrawal.amount,
+            })
+            .collect()
+    }
+
+    #[allow(dead_code)] // used in tests
+    pub(crate) async fn count(conn: &SqlitePool) -> Result<i64> {
+        let count = sqlx::query("SELECT COUNT(*) FROM withdrawals")
+            .fetch_one(conn)
+            .await?
+            .get::<i64, _>(0);
+
+        Ok(count)
+    }
+
+    pub(crate) async fn save<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO withdrawals (
+                block_number, withdrawal_index, validator_index, address, amount_gwei
+            ) VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(block_number, withdrawal_index) DO NOTHING
+            "#,
+        )
+        .bind(self.block_number as i64)
+        .bind(self.withdrawal_index as i64)
+        .bind(self.validator_index as i64)
+        .bind(self.address.as_slice())
+        .bind(self.amount_gwei as i64)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn save_batch(withdrawals: &[Withdrawal], conn: &SqlitePool) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        for withdrawal in withdrawals {
+            withdrawal.save(&mut *db_tx).await?;
+        }
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn query_where(where_sql: &str, conn: &SqlitePool) -> Result<Vec<Withdrawal>> {
+        let sql = format!(
+            "SELECT * FROM withdrawals WHERE {where_sql} \
+             ORDER BY block_number DESC, withdrawal_index ASC"
+        );
+
+        let rows = sqlx::query(sqlx::AssertSqlSafe(sql))
+            .fetch_all(conn)
+            .await?;
+        rows.iter().map(Self::from_row).collect()
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Withdrawal> {
+        let block_number: i64 = row.try_get("block_number")?;
+        let withdrawal_index: i64 = row.try_get("withdrawal_index")?;
+        let validator_index: i64 = row.try_get("validator_index")?;
+        let address: Vec<u8> = row.try_get("address")?;
+        let amount_gwei: i64 = row.try_get("amount_gwei")?;
+
+        Ok(Withdrawal {
+            block_number: block_number as u64,
+            withdrawal_index: withdrawal_index as u64,
+            validator_index: validator_index as u64,
+            address: Address::from_slice(&address),
+            amount_gwei: amount_gwei as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::txs::{fixtures::sample_withdrawal, models::transaction::test::setup_test_db};
+
+    #[tokio::test]
+    async fn save_batch_roundtrips_and_is_idempotent() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let withdrawals = vec![
+            Withdrawal {
+                withdrawal_index: 7,
+                ..sample_withdrawal(100)
+            },
+            Withdrawal {
+                withdrawal_index: 8,
+                validator_index: 1_008,
+                ..sample_withdrawal(100)
+            },
+        ];
+        Withdrawal::save_batch(&withdrawals, &conn).await?;
+        Withdrawal::save_batch(&withdrawals, &conn).await?;
+
+        assert_eq!(Withdrawal::count(&conn).await?, 2);
+        let found = Withdrawal::query_where("block_number = 100", &conn).await?;
+        assert_eq!(found, withdrawals);
+
+        Ok(())
+    }
+}
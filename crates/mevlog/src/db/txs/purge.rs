@@ -2,7 +2,10 @@ use eyre::Result;
 use sqlx::SqlitePool;
 use tracing::debug;
 
-use crate::db::{shared::truncate_wal, txs::custom_tables};
+use crate::db::{
+    shared::{column_exists, table_exists, truncate_wal},
+    txs::custom_tables,
+};
 
 /// Row counts removed by [`purge_old_blocks`]. `latest_block`/`cutoff_block`
 /// are `None` when the DB had no indexed blocks (nothing to purge).
@@ -14,6 +17,15 @@ pub struct PurgeStats {
     pub purged_blocks: u64,
     pub purged_transactions: u64,
     pub purged_logs: u64,
+    pub purged_access_list_entries: u64,
+    pub purged_authorizations: u64,
+    pub purged_withdrawals: u64,
+    pub purged_balance_changes: u64,
+    pub purged_traces: u64,
+    pub purged_contracts: u64,
+    pub purged_nft_transfers: u64,
+    /// Calldata bytes (`transactions.input`) freed along with the purged txs.
+    pub purged_input_bytes: u64,
 }
 
 /// Number of indexed blocks deleted (and committed) per chunk. A one-off purge
@@ -31,10 +43,12 @@ const PURGE_CHUNK_BLOCKS: u64 = 1000;
 ///
 /// The highest block present in the local DB is the reference (no RPC calls):
 /// rows with `block_number < MAX(blocks.block_number) - keep + 1` are removed
-/// from `logs`, `transactions`, `blocks`, and every tracked custom table
-/// (`keep = 0` purges everything). Custom-table rows are derived from `logs`,
-/// so they must not outlive their source rows; their counts are not reported in
-/// [`PurgeStats`].
+/// from `logs`, `transactions`, `access_list_entries`, `authorizations`,
+/// `withdrawals`, `balance_changes`, `traces`, `contracts`, `nft_transfers`,
+/// `blocks`, and every tracked custom table (`keep = 0` purges everything).
+/// Custom-table rows are derived from `logs`, so they must not outlive their
+/// source rows; their counts are not reported in [`PurgeStats`]. Spans that
+/// `index --logs-only` recorded as scanned are trimmed to the cutoff as well.
 ///
 /// The deletion is chunked into [`PURGE_CHUNK_BLOCKS`]-block transactions
 /// (oldest first), each committed independently. When `reclaim` is set,
@@ -54,6 +68,15 @@ pub async fn purge_old_blocks(keep: u64, reclaim: bool, conn: &SqlitePool) -> Re
     let cutoff_block = latest_block.saturating_add(1).saturating_sub(keep);
 
     let custom_tables = custom_tables::tracked_table_names(conn).await?;
+    let has_input = column_exists("transactions", "input", conn).await?;
+    let has_access_lists = table_exists("access_list_entries", conn).await?;
+    let has_authorizations = table_exists("authorizations", conn).await?;
+    let has_withdrawals = table_exists("withdrawals", conn).await?;
+    let has_balance_changes = table_exists("balance_changes", conn).await?;
+    let has_traces = table_exists("traces", conn).await?;
+    let has_contracts = table_exists("contracts", conn).await?;
+    let has_nft_transfers = table_exists("nft_transfers", conn).await?;
+    let has_log_filter_coverage = table_exists("log_filter_coverage", conn).await?;
 
     let mut stats = PurgeStats {
         latest_block: Some(latest_block),
@@ -61,6 +84,14 @@ pub async fn purge_old_blocks(keep: u64, reclaim: bool, conn: &SqlitePool) -> Re
         purged_blocks: 0,
         purged_transactions: 0,
         purged_logs: 0,
+        purged_access_list_entries: 0,
+        purged_authorizations: 0,
+        purged_withdrawals: 0,
+        purged_balance_changes: 0,
+        purged_traces: 0,
+        purged_contracts: 0,
+        purged_nft_transfers: 0,
+        purged_input_bytes: 0,
     };
 
     loop {
@@ -103,6 +134,18 @@ pub async fn purge_old_blocks(keep: u64, reclaim: bool, conn: &SqlitePool) -> Re
             .await?
             .rows_affected();
 
+        // Calldata is by far the largest per-tx column, so report what the
+        // chunk frees before the rows go.
+        if has_input {
+            let input_bytes: i64 = sqlx::query_scalar(
+                "SELECT COALESCE(SUM(LENGTH(input)), 0) FROM transactions WHERE block_number <= ?",
+            )
+            .bind(chunk_upper)
+            .fetch_one(&mut *db_tx)
+            .await?;
+            stats.purged_input_bytes += input_bytes as u64;
+        }
+
         stats.purged_transactions +=
             sqlx::query("DELETE FROM transactions WHERE block_number <= ?")
                 .bind(chunk_upper)
@@ -110,6 +153,67 @@ pub async fn purge_old_blocks(keep: u64, reclaim: bool, conn: &SqlitePool) -> Re
                 .await?
                 .rows_affected();
 
+        if has_access_lists {
+            stats.purged_access_list_entries +=
+                sqlx::query("DELETE FROM access_list_entries WHERE block_number <= ?")
+                    .bind(chunk_upper)
+                    .execute(&mut *db_tx)
+                    .await?
+                    .rows_affected();
+        }
+
+        if has_authorizations {
+            stats.purged_authorizations +=
+                sqlx::query("DELETE FROM authorizations WHERE block_number <= ?")
+                    .bind(chunk_upper)
+                    .execute(&mut *db_tx)
+                    .await?
+                    .rows_affected();
+        }
+
+        if has_withdrawals {
+            stats.purged_withdrawals +=
+                sqlx::query("DELETE FROM withdrawals WHERE block_number <= ?")
+                    .bind(chunk_upper)
+                    .execute(&mut *db_tx)
+                    .await?
+                    .rows_affected();
+        }
+
+        if has_balance_changes {
+            stats.purged_balance_changes +=
+                sqlx::query("DELETE FROM balance_changes WHERE block_number <= ?")
+                    .bind(chunk_upper)
+                    .execute(&mut *db_tx)
+                    .await?
+                    .rows_affected();
+        }
+
+        if has_traces {
+            stats.purged_traces += sqlx::query("DELETE FROM traces WHERE block_number <= ?")
+                .bind(chunk_upper)
+                .execute(&mut *db_tx)
+                .await?
+                .rows_affected();
+        }
+
+        if has_contracts {
+            stats.purged_contracts += sqlx::query("DELETE FROM contracts WHERE block_number <= ?")
+                .bind(chunk_upper)
+                .execute(&mut *db_tx)
+                .await?
+                .rows_affected();
+        }
+
+        if has_nft_transfers {
+            stats.purged_nft_transfers +=
+                sqlx::query("DELETE FROM nft_transfers WHERE block_number <= ?")
+                    .bind(chunk_upper)
+                    .execute(&mut *db_tx)
+                    .await?
+                    .rows_affected();
+        }
+
         stats.purged_blocks += sqlx::query("DELETE FROM blocks WHERE block_number <= ?")
             .bind(chunk_upper)
             .execute(&mut *db_tx)
@@ -123,14 +227,28 @@ pub async fn purge_old_blocks(keep: u64, reclaim: bool, conn: &SqlitePool) -> Re
         }
 
         debug!(
-            "Purge chunk committed: deleted through block {chunk_upper}, cutoff {cutoff_block} (cumulative {} blocks, {} txs, {} logs{})",
+            "Purge chunk committed: deleted through block {chunk_upper}, cutoff {cutoff_block} (cumulative {} blocks, {} txs, {} logs, {} input bytes{})",
             stats.purged_blocks,
             stats.purged_transactions,
             stats.purged_logs,
+            stats.purged_input_bytes,
             if reclaim { ", reclaimed" } else { "" }
         );
     }
 
+    // Spans scanned by `index --logs-only` no longer hold their matches below
+    // the cutoff, so they must not keep those blocks from being scanned again.
+    if has_log_filter_coverage {
+        sqlx::query("DELETE FROM log_filter_coverage WHERE to_block < ?")
+            .bind(cutoff_block as i64)
+            .execute(conn)
+            .await?;
+        sqlx::query("UPDATE log_filter_coverage SET from_block = ?1 WHERE from_block < ?1")
+            .bind(cutoff_block as i64)
+            .execute(conn)
+            .await?;
+    }
+
     Ok(stats)
 }
 
@@ -144,70 +262,59 @@ pub async fn reclaim_space(conn: &SqlitePool) -> Result<()> {
 
 #[cfg(test)]
 mod test {
-    use revm::primitives::{Address, FixedBytes, U256};
+    use revm::primitives::FixedBytes;
 
     use super::*;
-    use crate::db::txs::models::{
-        block::Block,
-        log::Log,
-        transaction::{Transaction, test::setup_test_db},
+    use crate::db::txs::{
+        fixtures::{
+            sample_access_list_entry, sample_authorization, sample_balance_change, sample_block,
+            sample_contract, sample_log, sample_nft_transfer, sample_trace, sample_tx,
+            sample_withdrawal,
+        },
+        models::{
+            access_list_entry::AccessListEntry,
+            authorization::Authorization,
+            balance_change::BalanceChange,
+            block::Block,
+            contract::Contract,
+            log::Log,
+            log_filter_coverage::LogFilterCoverage,
+            nft_transfer::NftTransfer,
+            trace::Trace,
+            transaction::{Transaction, test::setup_test_db},
+            withdrawal::Withdrawal,
+        },
     };
 
-    fn sample_block(block_number: u64) -> Block {
-        Block {
-            block_number,
-            block_hash: FixedBytes::<32>::from([0xab; 32]),
-            miner: Address::from([0x11; 20]),
-            gas_used: 16_000_000,
-            timestamp: 1_693_066_895,
-            base_fee_per_gas: None,
-        }
-    }
-
-    fn sample_tx(block_number: u64) -> Transaction {
-        Transaction {
-            block_number,
-            tx_index: 0,
-            tx_hash: FixedBytes::<32>::from([block_number as u8; 32]),
-            nonce: 7,
-            from_address: Address::from([0x11; 20]),
-            to_address: Some(Address::from([0x22; 20])),
-            value: U256::from(1u64),
-            gas_limit: 21_000,
-            gas_used: 21_000,
-            effective_gas_price: 30_000_000_000,
-            gas_price: 30_000_000_000,
-            max_fee_per_gas: 40_000_000_000,
-            max_priority_fee_per_gas: 2_000_000_000,
-            transaction_type: Some(2),
-            success: true,
-            signature_hash: None,
-            signature: None,
-            coinbase_transfer: None,
-        }
-    }
-
-    fn sample_log(block_number: u64) -> Log {
-        Log {
-            block_number,
-            tx_index: 0,
-            log_index: 0,
-            address: Address::from([0x11; 20]),
-            topics: vec![FixedBytes::<32>::from([0xdd; 32])],
-            data: vec![0xde, 0xad],
-            erc20_amount: None,
-            signature: None,
-        }
-    }
-
     async fn seed_blocks(range: std::ops::RangeInclusive<u64>, conn: &SqlitePool) -> Result<()> {
         let blocks: Vec<Block> = range.clone().map(sample_block).collect();
-        let txs: Vec<Transaction> = range.clone().map(sample_tx).collect();
-        let logs: Vec<Log> = range.map(sample_log).collect();
+        let txs: Vec<Transaction> = range
+            .clone()
+            .map(|block_number| Transaction {
+                tx_hash: FixedBytes::<32>::from([block_number as u8; 32]),
+                ..sample_tx(block_number)
+            })
+            .collect();
+        let logs: Vec<Log> = range.clone().map(sample_log).collect();
+        let entries: Vec<AccessListEntry> = range.clone().map(sample_access_list_entry).collect();
+        let authorizations: Vec<Authorization> = range.clone().map(sample_authorization).collect();
+        let withdrawals: Vec<Withdrawal> = range.clone().map(sample_withdrawal).collect();
+        let contracts: Vec<Contract> = range.clone().map(sample_contract).collect();
+        let nft_transfers: Vec<NftTransfer> = range.clone().map(sample_nft_transfer).collect();
 
         Block::save_batch(&blocks, conn).await?;
         Transaction::save_batch(&txs, conn).await?;
         Log::save_batch(&logs, conn).await?;
+        AccessListEntry::save_batch(&entries, conn).await?;
+        Authorization::save_batch(&authorizations, conn).await?;
+        Withdrawal::save_batch(&withdrawals, conn).await?;
+        Contract::save_batch(&contracts, conn).await?;
+        NftTransfer::save_batch(&nft_transfers, conn).await?;
+        for block_number in range {
+            BalanceChange::save_block(block_number, &[sample_balance_change(block_number)], conn)
+                .await?;
+            Trace::save_block(block_number, &[sample_trace(block_number)], conn).await?;
+        }
         Ok(())
     }
 
@@ -226,6 +333,14 @@ mod test {
                 purged_blocks: 3,
                 purged_transactions: 3,
                 purged_logs: 3,
+                purged_access_list_entries: 3,
+                purged_authorizations: 3,
+                purged_withdrawals: 3,
+                purged_balance_changes: 3,
+                purged_traces: 3,
+                purged_contracts: 3,
+                purged_nft_transfers: 3,
+                purged_input_bytes: 12,
             }
         );
 
@@ -243,6 +358,21 @@ mod test {
                 .await?
                 .is_empty()
         );
+        assert!(
+            AccessListEntry::query_where("block_number < 103", &conn)
+                .await?
+                .is_empty()
+        );
+        assert!(
+            Authorization::query_where("block_number < 103", &conn)
+                .await?
+                .is_empty()
+        );
+        assert!(
+            Withdrawal::query_where("block_number < 103", &conn)
+                .await?
+                .is_empty()
+        );
 
         Ok(())
     }
@@ -287,14 +417,8 @@ mod test {
         let topic0 = FixedBytes::<32>::from([0xdd; 32]);
         let logs: Vec<Log> = (100..=104)
             .map(|block_number| Log {
-                block_number,
-                tx_index: 0,
-                log_index: 0,
-                address: Address::from([0x11; 20]),
                 topics: vec![topic0],
-                data: U256::from(1u64).to_be_bytes::<32>().to_vec(),
-                erc20_amount: None,
-                signature: None,
+                ..sample_log(block_number)
             })
             .collect();
 
@@ -333,6 +457,28 @@ type = "uint256"
         Ok(())
     }
 
+    #[tokio::test]
+    async fn purge_trims_log_filter_coverage_below_cutoff() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+        seed_blocks(100..=104, &conn).await?;
+        let coverage = |from_block: u64, to_block: u64| LogFilterCoverage {
+            filter: "address=0x11".to_string(),
+            from_block,
+            to_block,
+        };
+        coverage(90, 99).save(&conn).await?;
+        coverage(100, 104).save(&conn).await?;
+
+        purge_old_blocks(2, false, &conn).await?;
+
+        assert_eq!(
+            LogFilterCoverage::query_where("1 = 1", &conn).await?,
+            vec![coverage(103, 104)]
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn purge_on_empty_db_is_a_noop() -> Result<()> {
         let (conn, _cl) = setup_test_db().await;
@@ -1,5 +1,7 @@
 use std::{
     collections::HashSet,
+    fmt,
+    str::FromStr,
     time::{Duration, Instant},
 };
 
@@ -12,12 +14,11 @@ use rusqlite::{
 };
 use serde_json::{Map, Value};
 
-/// Built-in tables a user-supplied `--sql` query is always allowed to read.
-/// Config-defined custom tables (passed in by the caller from the synced
-/// `SharedDeps::custom_tables`) are added on top; everything else (the
-/// `custom_tables` / `_sqlx_migrations` bookkeeping tables, attached databases,
-/// etc.) is rejected by the authorizer below.
-const BUILTIN_TABLES: [&str; 3] = ["transactions", "logs", "blocks"];
+use crate::db::txs::{
+    BLOCK_TABLES, NON_BLOCK_TABLES,
+    rpc_functions::{self, RpcFunctions},
+    sql_functions,
+};
 
 /// Read-only PRAGMA table-valued functions a query may call. These expose only
 /// the database file's size (`page_count * page_size`), never row data, so they
@@ -72,6 +73,116 @@ fn authorize(ctx: AuthContext<'_>, allowed: &HashSet<String>) -> Authorization {
     }
 }
 
+/// A named bind parameter of a `--sql` query (`--param name=value`), bound to
+/// the query's `:name` placeholder so addresses and hashes need no `X'...'`
+/// literal spliced into the SQL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlParam {
+    pub name: String,
+    pub value: SqlParamValue,
+}
+
+/// How a [`SqlParam`] value is bound, inferred from its text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqlParamValue {
+    /// A `0x`-prefixed 20-byte address or 32-byte hash, matching the BLOB
+    /// columns they are compared with.
+    Blob(Vec<u8>),
+    /// A plain (optionally negative) integer.
+    Integer(i64),
+    /// Anything else, e.g. a method signature.
+    Text(String),
+}
+
+impl FromStr for SqlParam {
+    type Err = eyre::Report;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let Some((name, value)) = input.split_once('=') else {
+            bail!("invalid --param '{input}', expected NAME=VALUE");
+        };
+        // Accept the placeholder spelling too: `--param :addr=0x...`.
+        let name = name.strip_prefix(':').unwrap_or(name);
+        let valid_name = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            bail!("invalid --param name '{name}', expected letters, digits and underscores");
+        }
+
+        let value = if let Some(hex_value) = value.strip_prefix("0x") {
+            let bytes = hex::decode(hex_value)
+                .map_err(|_| eyre!("--param {name}: '{value}' is not valid hex"))?;
+            if !matches!(bytes.len(), 20 | 32) {
+                bail!(
+                    "--param {name}: 0x values must be a 20-byte address or a 32-byte hash, got {} bytes",
+                    bytes.len()
+                );
+            }
+            SqlParamValue::Blob(bytes)
+        } else if let Ok(n) = value.parse::<i64>() {
+            SqlParamValue::Integer(n)
+        } else {
+            SqlParamValue::Text(value.to_string())
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            value,
+        })
+    }
+}
+
+impl fmt::Display for SqlParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            SqlParamValue::Blob(bytes) => write!(f, "{}=0x{}", self.name, hex::encode(bytes)),
+            SqlParamValue::Integer(n) => write!(f, "{}={n}", self.name),
+            SqlParamValue::Text(text) => write!(f, "{}={text}", self.name),
+        }
+    }
+}
+
+/// Binds `params` to their `:name` placeholders. Every placeholder must be
+/// bound and every param must have a placeholder, so a typo can't silently
+/// compare against `NULL`.
+fn bind_params(stmt: &mut rusqlite::Statement<'_>, params: &[SqlParam]) -> Result<()> {
+    let mut bound = HashSet::with_capacity(params.len());
+    for param in params {
+        let Some(idx) = stmt.parameter_index(&format!(":{}", param.name))? else {
+            bail!(
+                "--param {} is not used by the query; reference it as :{}",
+                param.name,
+                param.name
+            );
+        };
+        if !bound.insert(idx) {
+            bail!("--param {} is given more than once", param.name);
+        }
+        match &param.value {
+            SqlParamValue::Blob(bytes) => stmt.raw_bind_parameter(idx, bytes)?,
+            SqlParamValue::Integer(n) => stmt.raw_bind_parameter(idx, n)?,
+            SqlParamValue::Text(text) => stmt.raw_bind_parameter(idx, text)?,
+        }
+    }
+
+    if let Some(unbound) = (1..=stmt.parameter_count()).find(|idx| !bound.contains(idx)) {
+        match stmt.parameter_name(unbound) {
+            Some(name) if name.starts_with(':') => {
+                bail!(
+                    "query parameter {name} is not bound; pass --param {}=VALUE",
+                    &name[1..]
+                )
+            }
+            _ => bail!("query parameters must be named (:name) and passed with --param"),
+        }
+    }
+
+    Ok(())
+}
+
 /// Result of a raw SQL query: the selected column names (in `SELECT` order) plus
 /// one JSON object per row. Columns are carried separately so tabular consumers
 /// can render headers even when no rows are returned.
@@ -90,9 +201,19 @@ pub(crate) async fn run_raw_query_async(
     max_rows: Option<usize>,
     timeout: Option<Duration>,
     custom_tables: Vec<String>,
+    params: Vec<SqlParam>,
+    rpc_functions: Option<RpcFunctions>,
 ) -> Result<QueryResult> {
     tokio::task::spawn_blocking(move || {
-        run_raw_query(&sql, &db_path, max_rows, timeout, &custom_tables)
+        run_raw_query(
+            &sql,
+            &db_path,
+            max_rows,
+            timeout,
+            &custom_tables,
+            &params,
+            rpc_functions.as_ref(),
+        )
     })
     .await
     .map_err(|e| eyre!("query execution task failed: {e}"))?
@@ -101,16 +222,22 @@ pub(crate) async fn run_raw_query_async(
 /// Runs a user-provided SQL statement against the read-only txs DB and
 /// serializes each result row into a JSON object keyed by column name.
 /// Errors if the result exceeds `max_rows` (`None` = unlimited); rows are
-/// stepped lazily, so nothing past the cap is ever materialized.
+/// stepped lazily, so nothing past the cap is ever materialized. `params` are
+/// bound to the statement's `:name` placeholders (see [`SqlParam`]).
+/// `rpc_functions` enables the `eth_call` / `eth_balance` functions, which
+/// otherwise error when used.
 ///
 /// Uses a read-only `rusqlite` connection rather than `sqlx` so the custom
-/// `u256_sum` SQL function is available to the query.
+/// `evm-sqlite` helpers and mevlog's own [`sql_functions`] are available to
+/// the query.
 fn run_raw_query(
     sql: &str,
     db_path: &str,
     max_rows: Option<usize>,
     timeout: Option<Duration>,
     custom_tables: &[String],
+    params: &[SqlParam],
+    rpc_functions: Option<&RpcFunctions>,
 ) -> Result<QueryResult> {
     // Accept both `sqlite://<path>` URLs and bare filesystem paths.
     let filename = db_path
@@ -124,6 +251,8 @@ fn run_raw_query(
     // A C-API call, not a PRAGMA, so the authorizer (installed below) allows it.
     conn.busy_timeout(crate::db::shared::BUSY_TIMEOUT)?;
     register_functions(&conn)?;
+    sql_functions::register(&conn)?;
+    rpc_functions::register(&conn, rpc_functions)?;
 
     // Defense-in-depth on top of the read-only handle and the authorizer. Set
     // before the authorizer is installed, since the authorizer denies PRAGMA.
@@ -136,9 +265,15 @@ fn run_raw_query(
         conn.progress_handler(PROGRESS_OPS, Some(move || Instant::now() >= deadline));
     }
 
-    // Allowlist the built-in tables plus the config-defined custom tables.
-    // `move` into the closure so it lives as long as the connection.
-    let mut allowed: HashSet<String> = BUILTIN_TABLES.iter().map(|t| t.to_string()).collect();
+    // Allowlist the built-in tables plus the config-defined custom tables;
+    // everything else (the `custom_tables` / `_sqlx_migrations` bookkeeping
+    // tables, attached databases, etc.) is rejected by the authorizer. `move`
+    // into the closure so it lives as long as the connection.
+    let mut allowed: HashSet<String> = BLOCK_TABLES
+        .iter()
+        .chain(&NON_BLOCK_TABLES)
+        .map(|t| t.to_string())
+        .collect();
     allowed.extend(custom_tables.iter().cloned());
     conn.authorizer(Some(move |ctx: AuthContext<'_>| authorize(ctx, &allowed)));
 
@@ -152,9 +287,11 @@ fn run_raw_query(
         bail!("query returns duplicate column name `{dup}`; alias columns to make them unique");
     }
 
+    bind_params(&mut stmt, params)?;
+
     let col_count = columns.len();
     let mut out = Vec::new();
-    let mut rows = stmt.query([]).map_err(|e| map_query_err(e, deadline))?;
+    let mut rows = stmt.raw_query();
     while let Some(row) = rows.next().map_err(|e| map_query_err(e, deadline))? {
         if let Some(max_rows) = max_rows
             && out.len() == max_rows
@@ -181,34 +318,14 @@ fn run_raw_query(
 
 #[cfg(test)]
 mod test {
-    use revm::primitives::{Address, FixedBytes, U256};
+    use revm::primitives::FixedBytes;
     use serde_json::json;
 
     use super::*;
-    use crate::db::txs::models::transaction::{Transaction, test::setup_test_db_rw};
-
-    fn sample_tx() -> Transaction {
-        Transaction {
-            block_number: 100,
-            tx_index: 0,
-            tx_hash: FixedBytes::<32>::from([0xaa; 32]),
-            nonce: 7,
-            from_address: Address::from([0x11; 20]),
-            to_address: Some(Address::from([0x22; 20])),
-            value: U256::from(1_000_000_000_000_000_000u128),
-            gas_limit: 21_000,
-            gas_used: 21_000,
-            effective_gas_price: 30_000_000_000,
-            gas_price: 30_000_000_000,
-            max_fee_per_gas: 40_000_000_000,
-            max_priority_fee_per_gas: 2_000_000_000,
-            transaction_type: Some(2),
-            success: true,
-            signature_hash: Some(FixedBytes::<4>::from([0xa9, 0x05, 0x9c, 0xbb])),
-            signature: Some("transfer(address,uint256)".to_string()),
-            coinbase_transfer: None,
-        }
-    }
+    use crate::db::txs::{
+        fixtures::sample_tx,
+        models::transaction::{Transaction, test::setup_test_db_rw},
+    };
 
     #[tokio::test]
     async fn raw_query_interrupts_long_query_past_timeout() -> Result<()> {
@@ -218,7 +335,7 @@ mod test {
             .map(|i| Transaction {
                 tx_index: i,
                 tx_hash: FixedBytes::<32>::from([i as u8; 32]),
-                ..sample_tx()
+                ..sample_tx(100)
             })
             .collect();
         Transaction::save_batch(&txs, &write).await?;
@@ -227,8 +344,16 @@ mod test {
         // timeout; the progress handler must interrupt it once it elapses.
         let sql = "SELECT count(*) FROM transactions a, transactions b, \
                    transactions c, transactions d, transactions e";
-        let err =
-            run_raw_query(sql, &path, None, Some(Duration::from_millis(50)), &[]).unwrap_err();
+        let err = run_raw_query(
+            sql,
+            &path,
+            None,
+            Some(Duration::from_millis(50)),
+            &[],
+            &[],
+            None,
+        )
+        .unwrap_err();
         assert!(
             err.to_string().contains("SQL query timed out"),
             "expected timeout, got: {err}"
@@ -240,7 +365,7 @@ mod test {
     #[tokio::test]
     async fn raw_query_encodes_blobs_as_hex_and_ints_as_numbers() -> Result<()> {
         let (write, path, _cl) = setup_test_db_rw().await;
-        Transaction::save_batch(&[sample_tx()], &write).await?;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
 
         let result = run_raw_query(
             "SELECT block_number, tx_hash, from_address, signature FROM transactions",
@@ -248,6 +373,8 @@ mod test {
             None,
             None,
             &[],
+            &[],
+            None,
         )?;
 
         assert_eq!(
@@ -264,10 +391,51 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn raw_query_binds_typed_named_params() -> Result<()> {
+        let (write, path, _cl) = setup_test_db_rw().await;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
+        let params: Vec<SqlParam> = [
+            format!("from=0x{}", "11".repeat(20)),
+            ":block=100".to_string(),
+            "sig=transfer(address,uint256)".to_string(),
+        ]
+        .iter()
+        .map(|p| p.parse())
+        .collect::<Result<_>>()?;
+        assert_eq!(params[1].value, SqlParamValue::Integer(100));
+        assert_eq!(params[2].to_string(), "sig=transfer(address,uint256)");
+
+        let sql = "SELECT block_number FROM transactions \
+                   WHERE from_address = :from AND block_number = :block AND signature = :sig";
+        let result = run_raw_query(sql, &path, None, None, &[], &params, None)?;
+        assert_eq!(result.rows.len(), 1);
+
+        // Unbound placeholders, unused params and bad blob sizes are errors.
+        assert!(run_raw_query(sql, &path, None, None, &[], &params[..2], None).is_err());
+        let unused: SqlParam = "extra=1".parse()?;
+        assert!(
+            run_raw_query(
+                sql,
+                &path,
+                None,
+                None,
+                &[],
+                &[params.clone(), vec![unused]].concat(),
+                None
+            )
+            .is_err()
+        );
+        assert!("addr=0x1234".parse::<SqlParam>().is_err());
+        assert!("1bad=1".parse::<SqlParam>().is_err());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn raw_query_returns_columns_when_no_rows_match() -> Result<()> {
         let (write, path, _cl) = setup_test_db_rw().await;
-        Transaction::save_batch(&[sample_tx()], &write).await?;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
 
         let result = run_raw_query(
             "SELECT block_number, tx_hash FROM transactions WHERE 1 = 0",
@@ -275,6 +443,8 @@ mod test {
             None,
             None,
             &[],
+            &[],
+            None,
         )?;
 
         assert!(result.rows.is_empty());
@@ -286,7 +456,7 @@ mod test {
     #[tokio::test]
     async fn raw_query_rejects_duplicate_column_names() -> Result<()> {
         let (write, path, _cl) = setup_test_db_rw().await;
-        Transaction::save_batch(&[sample_tx()], &write).await?;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
 
         let err = run_raw_query(
             "SELECT 1 AS x, 2 AS x FROM transactions",
@@ -294,6 +464,8 @@ mod test {
             None,
             None,
             &[],
+            &[],
+            None,
         )
         .unwrap_err();
         assert!(err.to_string().contains("duplicate column name `x`"));
@@ -304,7 +476,7 @@ mod test {
     #[tokio::test]
     async fn raw_query_supports_projection_and_aggregates() -> Result<()> {
         let (write, path, _cl) = setup_test_db_rw().await;
-        Transaction::save_batch(&[sample_tx()], &write).await?;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
 
         let result = run_raw_query(
             "SELECT COUNT(*) AS n FROM transactions",
@@ -312,6 +484,8 @@ mod test {
             None,
             None,
             &[],
+            &[],
+            None,
         )?;
         assert_eq!(result.rows[0]["n"], json!(1));
 
@@ -321,12 +495,12 @@ mod test {
     #[tokio::test]
     async fn raw_query_enforces_max_rows() -> Result<()> {
         let (write, path, _cl) = setup_test_db_rw().await;
-        Transaction::save_batch(&[sample_tx()], &write).await?;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
 
         let sql = "SELECT block_number FROM transactions";
-        assert!(run_raw_query(sql, &path, Some(1), None, &[]).is_ok());
+        assert!(run_raw_query(sql, &path, Some(1), None, &[], &[], None).is_ok());
 
-        let err = run_raw_query(sql, &path, Some(0), None, &[]).unwrap_err();
+        let err = run_raw_query(sql, &path, Some(0), None, &[], &[], None).unwrap_err();
         assert!(err.to_string().contains("more than 0 rows"));
 
         Ok(())
@@ -335,14 +509,14 @@ mod test {
     #[tokio::test]
     async fn raw_query_rejects_mutating_statements() -> Result<()> {
         let (write, path, _cl) = setup_test_db_rw().await;
-        Transaction::save_batch(&[sample_tx()], &write).await?;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
 
         for stmt in [
             "DELETE FROM transactions",
             "UPDATE transactions SET nonce = 0",
             "DROP TABLE transactions",
         ] {
-            let err = run_raw_query(stmt, &path, None, None, &[]);
+            let err = run_raw_query(stmt, &path, None, None, &[], &[], None);
             assert!(err.is_err(), "expected `{stmt}` to be rejected");
         }
 
@@ -352,6 +526,8 @@ mod test {
             None,
             None,
             &[],
+            &[],
+            None,
         )?;
         assert_eq!(result.rows[0]["n"], json!(1));
 
@@ -361,12 +537,12 @@ mod test {
     #[tokio::test]
     async fn raw_query_allows_allowed_tables() -> Result<()> {
         let (write, path, _cl) = setup_test_db_rw().await;
-        Transaction::save_batch(&[sample_tx()], &write).await?;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
 
         for table in ["transactions", "logs", "blocks"] {
             let sql = format!("SELECT COUNT(*) AS n FROM {table}");
             assert!(
-                run_raw_query(&sql, &path, None, None, &[]).is_ok(),
+                run_raw_query(&sql, &path, None, None, &[], &[], None).is_ok(),
                 "`{table}` should be readable"
             );
         }
@@ -377,14 +553,14 @@ mod test {
     #[tokio::test]
     async fn raw_query_denies_non_allowed_tables() -> Result<()> {
         let (write, path, _cl) = setup_test_db_rw().await;
-        Transaction::save_batch(&[sample_tx()], &write).await?;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
 
         for sql in [
             "SELECT name FROM sqlite_master",
             "SELECT version FROM _sqlx_migrations",
         ] {
             assert!(
-                run_raw_query(sql, &path, None, None, &[]).is_err(),
+                run_raw_query(sql, &path, None, None, &[], &[], None).is_err(),
                 "expected `{sql}` denied"
             );
         }
@@ -395,7 +571,7 @@ mod test {
     #[tokio::test]
     async fn raw_query_allows_db_size_pragma_functions() -> Result<()> {
         let (write, path, _cl) = setup_test_db_rw().await;
-        Transaction::save_batch(&[sample_tx()], &write).await?;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
 
         let result = run_raw_query(
             "SELECT (SELECT page_count FROM pragma_page_count()) \
@@ -404,6 +580,8 @@ mod test {
             None,
             None,
             &[],
+            &[],
+            None,
         )?;
 
         assert_eq!(result.columns, ["bytes"]);
@@ -430,7 +608,9 @@ mod test {
                 &path,
                 None,
                 None,
-                &allowed
+                &allowed,
+                &[],
+                None
             )
             .is_ok(),
             "configured custom table `swaps` should be readable"
@@ -438,7 +618,16 @@ mod test {
 
         // A table in the DB but not in the allowlist stays denied.
         assert!(
-            run_raw_query("SELECT COUNT(*) AS n FROM swaps", &path, None, None, &[]).is_err(),
+            run_raw_query(
+                "SELECT COUNT(*) AS n FROM swaps",
+                &path,
+                None,
+                None,
+                &[],
+                &[],
+                None
+            )
+            .is_err(),
             "unlisted table `swaps` should be denied"
         );
 
@@ -448,14 +637,14 @@ mod test {
     #[tokio::test]
     async fn raw_query_denies_attach_and_pragma() -> Result<()> {
         let (write, path, _cl) = setup_test_db_rw().await;
-        Transaction::save_batch(&[sample_tx()], &write).await?;
+        Transaction::save_batch(&[sample_tx(100)], &write).await?;
 
         for sql in [
             "ATTACH DATABASE 'file:/tmp/evil?mode=rwc' AS e",
             "PRAGMA query_only = OFF",
         ] {
             assert!(
-                run_raw_query(sql, &path, None, None, &[]).is_err(),
+                run_raw_query(sql, &path, None, None, &[], &[], None).is_err(),
                 "expected `{sql}` denied"
             );
         }
@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+
+use alloy::{eips::BlockNumberOrTag, providers::Provider};
+use eyre::{Result, bail, eyre};
+use sqlx::SqlitePool;
+use tracing::{info, warn};
+
+use crate::{
+    db::txs::{custom_tables, indexing::index_block_range, models::block::Block},
+    misc::{
+        data_fetch::invalidate_cached_range,
+        shared_init::{CryoOpts, SharedDeps},
+    },
+};
+
+/// Deepest reorg [`handle_reorgs`] will unwind. Reorgs this deep do not happen
+/// on chains with finality; hitting the limit means the local DB disagrees with
+/// the RPC for another reason (e.g. it was indexed from a different chain).
+const MAX_REORG_DEPTH: u64 = 128;
+
+/// Consecutive reorgs [`handle_reorgs`] heals before giving up, so an RPC
+/// flapping between forks cannot keep the live indexer re-indexing forever.
+const MAX_REORG_ROUNDS: usize = 8;
+
+/// A reorg found by [`find_reorg`]: indexed blocks above `common_ancestor` are
+/// not on the RPC's canonical chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reorg {
+    /// Highest indexed block whose hash matches the canonical chain, or the
+    /// gap in the indexed range the orphaned blocks sit above.
+    pub common_ancestor: u64,
+    /// Number of orphaned blocks that had been indexed (at least 1).
+    pub depth: u64,
+}
+
+/// Verifies the hash chain of freshly indexed blocks `from..=to` (each block's
+/// `parent_hash` against the stored hash of its predecessor, including block
+/// `from - 1`) and heals any reorg it uncovers: rows above the common ancestor
+/// are rolled back with [`rollback_above`] and the canonical blocks re-indexed.
+/// The re-indexed range is checked again, so back-to-back reorgs are handled
+/// too. Returns the reorgs healed, oldest first.
+///
+/// Blocks without a `parent_hash` (indexed before the column existed) never
+/// break the chain, so the first check against older data is a no-op.
+pub async fn handle_reorgs(
+    from: u64,
+    to: u64,
+    batch_size: usize,
+    deps: &SharedDeps,
+    cryo_opts: &CryoOpts,
+) -> Result<Vec<Reorg>> {
+    let mut reorgs = vec![];
+    let mut from = from;
+
+    while let Some(reorg) = find_reorg(from, to, deps).await? {
+        if reorgs.len() >= MAX_REORG_ROUNDS {
+            bail!(
+                "Chain still inconsistent after {} reorg rollbacks, RPC may be switching between forks",
+                MAX_REORG_ROUNDS
+            );
+        }
+
+        let rolled_back = rollback_above(reorg.common_ancestor, &deps.txs).await?;
+        warn!(
+            "Reorg detected: depth {}, common ancestor {}, rolled back {} indexed blocks",
+            reorg.depth, reorg.common_ancestor, rolled_back
+        );
+
+        from = reorg.common_ancestor + 1;
+        let invalidated = invalidate_cached_range(&deps.chain, from, to);
+        if invalidated > 0 {
+            info!("Invalidated {} cached parquet file(s)", invalidated);
+        }
+
+        index_block_range(from, to, batch_size, deps, cryo_opts).await?;
+        info!("Re-indexed canonical blocks {}..={}", from, to);
+
+        reorgs.push(reorg);
+    }
+
+    Ok(reorgs)
+}
+
+/// Looks for a broken parent link in `from..=to` and, if there is one, walks
+/// back comparing stored block hashes with the RPC's canonical ones to find
+/// the common ancestor. Only the walk-back issues RPC calls.
+pub(crate) async fn find_reorg(from: u64, to: u64, deps: &SharedDeps) -> Result<Option<Reorg>> {
+    let Some(child) = broken_link(from, to, &deps.txs).await? else {
+        return Ok(None);
+    };
+
+    let mut block_number = child - 1;
+    loop {
+        let depth = child - 1 - block_number;
+        if depth >= MAX_REORG_DEPTH {
+            bail!(
+                "Reorg below block {} is deeper than {} blocks",
+                child,
+                MAX_REORG_DEPTH
+            );
+        }
+
+        let stored = Block::query_where(&format!("block_number = {block_number}"), &deps.txs)
+            .await?
+            .pop();
+
+        // Nothing indexed below a gap can be rolled back, so the gap bounds
+        // the reorg.
+        let Some(stored) = stored else {
+            break;
+        };
+
+        let canonical = deps
+            .provider
+            .get_block_by_number(BlockNumberOrTag::Number(block_number))
+            .await?
+            .ok_or_else(|| eyre!("Block {} not found", block_number))?;
+
+        if stored.block_hash == canonical.header.hash || block_number == 0 {
+            break;
+        }
+
+        block_number -= 1;
+    }
+
+    Ok(Some(Reorg {
+        common_ancestor: block_number,
+        // A stale `child` with a canonical parent still orphans one block.
+        depth: (child - 1 - block_number).max(1),
+    }))
+}
+
+/// Returns the lowest block in `from..=to` whose `parent_hash` does not match
+/// the stored hash of the block below it. Pairs with a missing block or a
+/// missing `parent_hash` are skipped.
+pub(crate) async fn broken_link(from: u64, to: u64, conn: &SqlitePool) -> Result<Option<u64>> {
+    let from = from.max(1);
+    if from > to {
+        return Ok(None);
+    }
+
+    let blocks: HashMap<u64, Block> =
+        Block::query_where(&format!("block_number BETWEEN {} AND {to}", from - 1), conn)
+            .await?
+            .into_iter()
+            .map(|block| (block.block_number, block))
+            .collect();
+
+    for block_number in from..=to {
+        let (Some(child), Some(parent)) =
+            (blocks.get(&block_number), blocks.get(&(block_number - 1)))
+        else {
+            continue;
+        };
+
+        if child
+            .parent_hash
+            .is_some_and(|parent_hash| parent_hash != parent.block_hash)
+        {
+            return Ok(Some(block_number));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Deletes all indexed data above `block_number` from `logs`, `transactions`,
+/// `access_list_entries`, `authorizations`, `withdrawals`, `balance_changes`,
+/// `traces`, `contracts`, `nft_transfers`, `blocks`, and every tracked custom
+/// table, and trims the `log_filter_coverage` spans reaching above it, in a
+/// single transaction, so a partially rolled back reorg is never visible.
+/// Returns the number of `blocks` rows removed.
+pub async fn rollback_above(block_number: u64, conn: &SqlitePool) -> Result<u64> {
+    let custom_tables = custom_tables::tracked_table_names(conn).await?;
+
+    let mut db_tx = conn.begin().await?;
+
+    for name in &custom_tables {
+        sqlx::query(sqlx::AssertSqlSafe(format!(
+            "DELETE FROM \"{name}\" WHERE block_number > ?"
+        )))
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+    }
+
+    sqlx::query("DELETE FROM logs WHERE block_number > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    sqlx::query("DELETE FROM transactions WHERE block_number > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    sqlx::query("DELETE FROM access_list_entries WHERE block_number > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    sqlx::query("DELETE FROM authorizations WHERE block_number > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    sqlx::query("DELETE FROM withdrawals WHERE block_number > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    sqlx::query("DELETE FROM balance_changes WHERE block_number > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    sqlx::query("DELETE FROM traces WHERE block_number > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    sqlx::query("DELETE FROM contracts WHERE block_number > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    sqlx::query("DELETE FROM nft_transfers WHERE block_number > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    // Rolled back blocks must be scanned again by `index --logs-only`.
+    sqlx::query("DELETE FROM log_filter_coverage WHERE from_block > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    sqlx::query("UPDATE log_filter_coverage SET to_block = ?1 WHERE to_block > ?1")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?;
+
+    let rolled_back = sqlx::query("DELETE FROM blocks WHERE block_number > ?")
+        .bind(block_number as i64)
+        .execute(&mut *db_tx)
+        .await?
+        .rows_affected();
+
+    db_tx.commit().await?;
+
+    Ok(rolled_back)
+}
+
+#[cfg(test)]
+mod test {
+    use revm::primitives::FixedBytes;
+
+    use super::*;
+    use crate::db::txs::{
+        fixtures::{sample_block, sample_log, sample_tx},
+        models::{
+            log::Log,
+            transaction::{Transaction, test::setup_test_db},
+        },
+    };
+
+    fn hash(block_number: u64, fork: u8) -> FixedBytes<32> {
+        let mut bytes = [fork; 32];
+        bytes[24..].copy_from_slice(&block_number.to_be_bytes());
+        FixedBytes::from(bytes)
+    }
+
+    fn forked_block(block_number: u64, fork: u8, parent_fork: u8) -> Block {
+        Block {
+            block_number,
+            block_hash: hash(block_number, fork),
+            parent_hash: Some(hash(block_number - 1, parent_fork)),
+            ..sample_block(block_number)
+        }
+    }
+
+    #[tokio::test]
+    async fn broken_link_finds_lowest_mismatch() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        // 100..=102 on fork 0; 103 builds on a fork-1 version of 102.
+        let blocks = vec![
+            forked_block(100, 0, 0),
+            forked_block(101, 0, 0),
+            forked_block(102, 0, 0),
+            forked_block(103, 1, 1),
+            forked_block(104, 1, 1),
+        ];
+        Block::save_batch(&blocks, &conn).await?;
+
+        assert_eq!(broken_link(101, 104, &conn).await?, Some(103));
+        assert_eq!(broken_link(101, 102, &conn).await?, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn broken_link_skips_missing_parent_hash() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let mut legacy = forked_block(101, 1, 1);
+        legacy.parent_hash = None;
+        Block::save_batch(&[forked_block(100, 0, 0), legacy], &conn).await?;
+
+        assert_eq!(broken_link(101, 101, &conn).await?, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rollback_above_removes_orphaned_rows_everywhere() -> Result<()> {
+        let (conn, _cl) = setup_test_db().await;
+
+        let topic0 = FixedBytes::<32>::from([0xdd; 32]);
+        let blocks: Vec<Block> = (100..=104).map(|n| forked_block(n, 0, 0)).collect();
+        let txs: Vec<Transaction> = (100..=104)
+            .map(|block_number| Transaction {
+                tx_hash: hash(block_number, 0),
+                ..sample_tx(block_number)
+            })
+            .collect();
+        let logs: Vec<Log> = (100..=104)
+            .map(|block_number| Log {
+                topics: vec![topic0],
+                ..sample_log(block_number)
+            })
+            .collect();
+        Block::save_batch(&blocks, &conn).await?;
+        Transaction::save_batch(&txs, &conn).await?;
+        Log::save_batch(&logs, &conn).await?;
+
+        let toml_str = format!(
+            r#"
+[tables.events]
+topic0 = "0x{}"
+
+[[tables.events.columns]]
+name = "amount"
+source = "data[0:32]"
+type = "uint256"
+"#,
+            hex::encode(topic0)
+        );
+        let config: crate::misc::config::Config = toml::from_str(&toml_str).unwrap();
+        custom_tables::sync(&config.custom_tables()?, 1, &conn).await?;
+
+        let rolled_back = rollback_above(102, &conn).await?;
+        assert_eq!(rolled_back, 2);
+
+        let remaining: Vec<u64> = Block::query_where("1 = 1", &conn)
+            .await?
+            .iter()
+            .map(|b| b.block_number)
+            .collect();
+        assert_eq!(remaining, vec![102, 101, 100]);
+        assert!(
+            Transaction::query_where("block_number > 102", &conn)
+                .await?
+                .is_empty()
+        );
+        assert!(
+            Log::query_where("block_number > 102", &conn)
+                .await?
+                .is_empty()
+        );
+
+        let events: Vec<i64> =
+            sqlx::query_scalar("SELECT block_number FROM events ORDER BY block_number")
+                .fetch_all(&conn)
+                .await?;
+        assert_eq!(events, vec![100, 101, 102]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,417 @@
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use alloy::{
+    eips::BlockId,
+    primitives::Bytes,
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    transports::{TransportError, TransportResult},
+};
+use revm::primitives::keccak256;
+use rusqlite::{
+    Connection,
+    functions::{Context, FunctionFlags},
+    types::ValueRef,
+};
+use tokio::runtime::Handle;
+
+use crate::{
+    GenericProvider,
+    db::txs::sql_functions::{address_arg, user_error},
+    misc::shared_init::config_path,
+};
+
+// `eth_call` / `eth_balance` SQL functions, reading chain state at historical
+// blocks through the command's provider. Opt-in per query with a budget of
+// RPC requests, since a single scan could otherwise fire one per row.
+
+/// Provider, budget and cache for the `eth_call` / `eth_balance` SQL
+/// functions of one query (`query --rpc-calls <BUDGET>`).
+#[derive(Clone)]
+pub struct RpcFunctions {
+    provider: Arc<GenericProvider>,
+    chain_id: u64,
+    /// RPC requests the query may make; cache hits don't count.
+    budget: u64,
+    /// On-disk result cache shared across queries; `None` caches nothing
+    /// beyond SQLite's per-query evaluation of constant arguments.
+    cache_dir: Option<PathBuf>,
+    /// Finalized head when the query started. Only results at or below it
+    /// are disk-cached, since state above it can still be reorged.
+    finalized_block: u64,
+    /// Runtime the requests are driven on from `run_raw_query`'s blocking
+    /// thread.
+    handle: Handle,
+}
+
+impl RpcFunctions {
+    /// Without the on-disk cache; see [`Self::with_disk_cache`]. Must be
+    /// called within a Tokio runtime.
+    pub(crate) fn new(provider: Arc<GenericProvider>, chain_id: u64, budget: u64) -> Self {
+        Self {
+            provider,
+            chain_id,
+            budget,
+            cache_dir: None,
+            finalized_block: 0,
+            handle: Handle::current(),
+        }
+    }
+
+    /// Caches results at or below `finalized_block` on disk across queries.
+    /// The cache is cleared first once it outgrows [`MAX_DISK_CACHE_BYTES`].
+    pub(crate) fn with_disk_cache(mut self, finalized_block: u64) -> Self {
+        let cache_dir = config_path().join(".rpc-call-cache");
+        prune_disk_cache(&cache_dir);
+        self.cache_dir = Some(cache_dir);
+        self.finalized_block = finalized_block;
+        self
+    }
+
+    /// The disk cache, if `block`'s results may be stored in it.
+    fn cache_dir_for(&self, block: u64) -> Option<&PathBuf> {
+        self.cache_dir
+            .as_ref()
+            .filter(|_| block <= self.finalized_block)
+    }
+
+    /// Result cached under `key`, or else `request`'s, which is then cached
+    /// if `block` is finalized. Every request counts against `remaining`, and
+    /// errors once it is spent. `None` is a reverted call.
+    fn cached<F>(
+        &self,
+        key: &str,
+        block: u64,
+        remaining: &AtomicU64,
+        request: F,
+    ) -> rusqlite::Result<Option<Vec<u8>>>
+    where
+        F: Future<Output = TransportResult<Option<Vec<u8>>>>,
+    {
+        let cache_dir = self.cache_dir_for(block);
+        self.handle.block_on(async {
+            if let Some(cache_dir) = cache_dir
+                && let Ok(bytes) = cacache::read(cache_dir, key).await
+                && let Ok(cached) = serde_json::from_slice::<Option<Bytes>>(&bytes)
+            {
+                return Ok(cached.map(|out| out.to_vec()));
+            }
+
+            if remaining
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_err()
+            {
+                return Err(user_error(format!(
+                    "query exceeded its budget of {} RPC calls; raise --rpc-calls",
+                    self.budget
+                )));
+            }
+            let out = request
+                .await
+                .map_err(|e| user_error(format!("RPC request failed: {e}")))?;
+
+            if let Some(cache_dir) = cache_dir {
+                let cached = serde_json::to_vec(&out.clone().map(Bytes::from))
+                    .map_err(|e| user_error(e.to_string()))?;
+                if let Err(e) = cacache::write(cache_dir, key, cached).await {
+                    tracing::error!("Error writing RPC call cache: {}", e);
+                }
+            }
+            Ok(out)
+        })
+    }
+}
+
+/// Registers `eth_call(to, calldata, block)` and `eth_balance(addr, block)`.
+/// Without `rpc` they are registered anyway, only to explain how to enable
+/// them.
+pub(crate) fn register(conn: &Connection, rpc: Option<&RpcFunctions>) -> rusqlite::Result<()> {
+    let Some(rpc) = rpc else {
+        for (name, n_args) in [("eth_call", 3), ("eth_balance", 2)] {
+            conn.create_scalar_function(
+                name,
+                n_args,
+                FunctionFlags::SQLITE_UTF8,
+                move |_| -> rusqlite::Result<Option<Vec<u8>>> {
+                    Err(user_error(format!(
+                        "{name}() is disabled; enable it with --rpc-calls <BUDGET>"
+                    )))
+                },
+            )?;
+        }
+        return Ok(());
+    };
+
+    // State at a given block never changes, so a call with constant arguments
+    // is evaluated once per query.
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+    let remaining = Arc::new(AtomicU64::new(rpc.budget));
+
+    let (call_rpc, call_remaining) = (rpc.clone(), remaining.clone());
+    conn.create_scalar_function("eth_call", 3, flags, move |ctx| {
+        let (Some(to), Some(calldata), Some(block)) = (
+            address_arg(ctx, 0)?,
+            calldata_arg(ctx, 1)?,
+            block_arg(ctx, 2)?,
+        ) else {
+            return Ok(None);
+        };
+        let key = format!(
+            "{}-call-{block}-{}",
+            call_rpc.chain_id,
+            keccak256([to.as_slice(), calldata.as_slice()].concat())
+        );
+        let provider = call_rpc.provider.clone();
+        call_rpc.cached(&key, block, &call_remaining, async move {
+            let tx = TransactionRequest::default()
+                .to(to)
+                .input(Bytes::from(calldata).into());
+            match provider.call(tx).block(BlockId::number(block)).await {
+                Ok(out) => Ok(Some(out.to_vec())),
+                Err(e) if is_revert(&e) => Ok(None),
+                Err(e) => Err(e),
+            }
+        })
+    })?;
+
+    let balance_rpc = rpc.clone();
+    conn.create_scalar_function("eth_balance", 2, flags, move |ctx| {
+        let (Some(address), Some(block)) = (address_arg(ctx, 0)?, block_arg(ctx, 1)?) else {
+            return Ok(None);
+        };
+        let key = format!("{}-balance-{block}-{address:#x}", balance_rpc.chain_id);
+        let provider = balance_rpc.provider.clone();
+        balance_rpc.cached(&key, block, &remaining, async move {
+            let balance = provider
+                .get_balance(address)
+                .block_id(BlockId::number(block))
+                .await?;
+            Ok(Some(balance.to_be_bytes::<32>().to_vec()))
+        })
+    })?;
+
+    Ok(())
+}
+
+/// The call reverted, as opposed to the node failing to run it (e.g. state
+/// pruned at that block), which fails the query instead of reading as `NULL`.
+/// Only the `3` error code or revert data count; a bare message mentioning
+/// "revert" is not trusted.
+fn is_revert(e: &TransportError) -> bool {
+    e.as_error_resp()
+        .is_some_and(|resp| resp.code == 3 || resp.as_revert_data().is_some())
+}
+
+/// Size above which [`prune_disk_cache`] clears the on-disk cache.
+const MAX_DISK_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Clears the on-disk cache once its entries outgrow
+/// [`MAX_DISK_CACHE_BYTES`], so it stays bounded by that plus one query's
+/// budget of results.
+fn prune_disk_cache(cache_dir: &Path) {
+    let size: usize = cacache::list_sync(cache_dir)
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.size)
+        .sum();
+    if size > MAX_DISK_CACHE_BYTES
+        && let Err(e) = cacache::clear_sync(cache_dir)
+    {
+        tracing::error!("Error clearing RPC call cache: {}", e);
+    }
+}
+
+/// Argument `idx` as calldata: a BLOB or `0x`-prefixed hex TEXT (e.g.
+/// `'0x70a08231' || hex(topic2)`). `None` for NULL.
+fn calldata_arg(ctx: &Context<'_>, idx: usize) -> rusqlite::Result<Option<Vec<u8>>> {
+    match ctx.get_raw(idx) {
+        ValueRef::Null => Ok(None),
+        ValueRef::Blob(bytes) => Ok(Some(bytes.to_vec())),
+        ValueRef::Text(text) => {
+            let text = String::from_utf8_lossy(text);
+            let hex_text = text
+                .strip_prefix("0x")
+                .ok_or_else(|| user_error(format!("calldata TEXT must be 0x-hex, got '{text}'")))?;
+            hex::decode(hex_text)
+                .map(Some)
+                .map_err(|e| user_error(format!("calldata '{text}' is not valid hex: {e}")))
+        }
+        _ => Err(user_error(
+            "expected calldata BLOB or 0x-hex TEXT".to_string(),
+        )),
+    }
+}
+
+/// Argument `idx` as a block number. `None` for NULL.
+fn block_arg(ctx: &Context<'_>, idx: usize) -> rusqlite::Result<Option<u64>> {
+    match ctx.get_raw(idx) {
+        ValueRef::Null => Ok(None),
+        ValueRef::Integer(block) => u64::try_from(block)
+            .map(Some)
+            .map_err(|_| user_error(format!("invalid block number {block}"))),
+        _ => Err(user_error("expected an INTEGER block number".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::AtomicUsize;
+
+    use eyre::Result;
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::misc::{mock_node::mock_node, shared_init::init_provider};
+
+    const REVERTING: &str = "0x2222222222222222222222222222222222222222";
+    /// Reverts with revert data but a generic error code.
+    const REVERTING_WITH_DATA: &str = "0x4444444444444444444444444444444444444444";
+    /// Fails with a message that mentions a revert but no revert data.
+    const FAILING: &str = "0x5555555555555555555555555555555555555555";
+
+    /// JSON-RPC node answering `eth_call` (reverting for [`REVERTING`] and
+    /// [`REVERTING_WITH_DATA`], failing for [`FAILING`]) and `eth_getBalance`,
+    /// counting the requests for those.
+    async fn rpc_node(requests: Arc<AtomicUsize>) -> Result<String> {
+        mock_node(move |method, params| {
+            if matches!(method, "eth_call" | "eth_getBalance") {
+                requests.fetch_add(1, Ordering::SeqCst);
+            }
+            match method {
+                "eth_call" if params[0]["to"] == REVERTING => {
+                    Err(json!({ "code": 3, "message": "execution reverted" }))
+                }
+                "eth_call" if params[0]["to"] == REVERTING_WITH_DATA => Err(json!({
+                    "code": -32000,
+                    "message": "execution reverted",
+                    "data": "0x08c379a0",
+                })),
+                "eth_call" if params[0]["to"] == FAILING => Err(json!({
+                    "code": -32000,
+                    "message": "cannot revert to pruned state",
+                })),
+                "eth_call" => Ok(json!(format!("0x{}", "2a".repeat(32)))),
+                "eth_getBalance" => Ok(json!("0x64")),
+                _ => Ok(json!("0x1")),
+            }
+        })
+        .await
+    }
+
+    /// Runs `sql` on a fresh connection off the runtime, as `run_raw_query`
+    /// does, since the functions block on it.
+    async fn query_row(rpc: Option<RpcFunctions>, sql: &str) -> rusqlite::Result<Value> {
+        let sql = sql.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open_in_memory()?;
+            register(&conn, rpc.as_ref())?;
+            conn.query_row(&sql, [], |row| {
+                Ok(match row.get_ref(0)? {
+                    ValueRef::Null => Value::Null,
+                    ValueRef::Blob(bytes) => json!(hex::encode(bytes)),
+                    other => json!(format!("{other:?}")),
+                })
+            })
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rpc_functions_cache_and_budget() -> Result<()> {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let url = rpc_node(requests.clone()).await?;
+        let provider = Arc::new(init_provider(&url).await?);
+        let mut rpc = RpcFunctions::new(provider, 1, 2);
+        rpc.cache_dir =
+            Some(std::env::temp_dir().join(format!("{}-mevlog-rpc-cache", Uuid::new_v4())));
+        rpc.finalized_block = 100;
+
+        let call = "SELECT eth_call('0x1111111111111111111111111111111111111111', \
+                    '0x70a08231' || hex(zeroblob(32)), 100)";
+        let balance = "SELECT eth_balance(X'3333333333333333333333333333333333333333', 100)";
+        let reverting = format!("SELECT eth_call('{REVERTING}', X'12345678', 100)");
+
+        assert_eq!(
+            query_row(Some(rpc.clone()), call).await?,
+            json!("2a".repeat(32))
+        );
+        assert_eq!(
+            query_row(Some(rpc.clone()), balance).await?,
+            json!(format!("{}64", "00".repeat(31)))
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // Cached in a later query: no request.
+        assert_eq!(
+            query_row(Some(rpc.clone()), call).await?,
+            json!("2a".repeat(32))
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // A revert is NULL, and cached as such.
+        assert_eq!(query_row(Some(rpc.clone()), &reverting).await?, Value::Null);
+        assert_eq!(query_row(Some(rpc.clone()), &reverting).await?, Value::Null);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            query_row(Some(rpc.clone()), "SELECT eth_balance(NULL, 100)").await?,
+            Value::Null
+        );
+
+        // Revert data marks a revert regardless of the error code; a message
+        // alone does not.
+        let with_data = format!("SELECT eth_call('{REVERTING_WITH_DATA}', X'12345678', 100)");
+        assert_eq!(query_row(Some(rpc.clone()), &with_data).await?, Value::Null);
+        let failing = format!("SELECT eth_call('{FAILING}', X'12345678', 100)");
+        let err = query_row(Some(rpc.clone()), &failing)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("RPC request failed"), "got: {err}");
+
+        // Above the finalized head, results are refetched every query.
+        let before = requests.load(Ordering::SeqCst);
+        let unfinalized = "SELECT eth_balance(X'3333333333333333333333333333333333333333', 101)";
+        query_row(Some(rpc.clone()), unfinalized).await?;
+        query_row(Some(rpc.clone()), unfinalized).await?;
+        assert_eq!(requests.load(Ordering::SeqCst), before + 2);
+
+        // Three uncached blocks against a budget of two.
+        let err = query_row(
+            Some(rpc.clone()),
+            "WITH RECURSIVE blocks(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM blocks WHERE n < 3) \
+             SELECT count(eth_balance(X'3333333333333333333333333333333333333333', n)) FROM blocks",
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("budget of 2 RPC calls"), "got: {err}");
+
+        // Without the disk cache, a rerun requests again.
+        let before = requests.load(Ordering::SeqCst);
+        let uncached = RpcFunctions {
+            cache_dir: None,
+            ..rpc.clone()
+        };
+        assert_eq!(
+            query_row(Some(uncached), call).await?,
+            json!("2a".repeat(32))
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), before + 1);
+
+        let err = query_row(None, call).await.unwrap_err().to_string();
+        assert!(err.contains("eth_call() is disabled"), "got: {err}");
+
+        if let Some(cache_dir) = &rpc.cache_dir {
+            let _ = std::fs::remove_dir_all(cache_dir);
+        }
+        Ok(())
+    }
+}
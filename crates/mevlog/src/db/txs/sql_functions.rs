@@ -0,0 +1,621 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+
+use alloy::{
+    dyn_abi::{DynSolType, DynSolValue},
+    json_abi::{Event, Function},
+};
+use revm::primitives::{Address, I256, keccak256};
+use rusqlite::{
+    Connection, OpenFlags,
+    functions::{Aggregate, Context, FunctionFlags},
+    types::{Value, ValueRef},
+};
+use serde_json::json;
+
+// SQL functions mevlog registers on the read-only `--sql` connection on top of
+// the `evm-sqlite` helpers, for columns those don't cover.
+
+/// Registers every mevlog SQL function on `conn`.
+pub(crate) fn register(conn: &Connection) -> rusqlite::Result<()> {
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+
+    // Signed counterparts of `u256_to_dec` / `u256_sum` for two's-complement
+    // BLOBs such as `balance_changes.delta`.
+    conn.create_scalar_function("i256_to_dec", 1, flags, |ctx| {
+        Ok(i256_arg(ctx, 0)?.map(|value| value.to_string()))
+    })?;
+    conn.create_aggregate_function("i256_sum", 1, flags, I256Sum)?;
+
+    // Lookups into the `tokens` table, loaded on the first call (see
+    // `LazyTokens`) so queries that never use them don't read it.
+    let tokens = Arc::new(LazyTokens::new(conn));
+    let symbols = tokens.clone();
+    conn.create_scalar_function("token_symbol", 1, flags, move |ctx| {
+        let Some(address) = address_arg(ctx, 0)? else {
+            return Ok(None);
+        };
+        Ok(symbols
+            .get()?
+            .get(&address)
+            .and_then(|(symbol, _)| symbol.clone()))
+    })?;
+    conn.create_scalar_function("token_decimals", 1, flags, move |ctx| {
+        let Some(address) = address_arg(ctx, 0)? else {
+            return Ok(None);
+        };
+        Ok(tokens
+            .get()?
+            .get(&address)
+            .and_then(|(_, decimals)| *decimals))
+    })?;
+
+    // ABI decoding of log `data` / calldata. Data that doesn't decode as the
+    // given types yields NULL, so a scan over logs sharing a topic0 but not a
+    // layout doesn't abort; a malformed type list is an error. Parsed types are
+    // cached per statement while the type argument stays the same.
+    conn.create_scalar_function("abi_decode", 2, flags, |ctx| {
+        let types = ctx.get_or_create_aux(1, parse_abi_types)?;
+        let Some(data) = blob_arg(ctx, 0)? else {
+            return Ok(None);
+        };
+        Ok(types
+            .abi_decode_params(data)
+            .ok()
+            .map(|value| abi_json(&value).to_string()))
+    })?;
+    conn.create_scalar_function("abi_decode_at", 3, flags, |ctx| {
+        let types = ctx.get_or_create_aux(1, parse_abi_types)?;
+        let idx: i64 = ctx.get(2)?;
+        let Some(data) = blob_arg(ctx, 0)? else {
+            return Ok(Value::Null);
+        };
+        let fields = match types.as_ref() {
+            DynSolType::Tuple(fields) => fields.as_slice(),
+            single => std::slice::from_ref(single),
+        };
+        let idx = usize::try_from(idx)
+            .ok()
+            .filter(|&idx| idx < fields.len())
+            .ok_or_else(|| {
+                user_error(format!(
+                    "abi_decode_at index {idx} is out of range for {} type(s)",
+                    fields.len()
+                ))
+            })?;
+        // Head offsets count from the start of the data, so the fields after
+        // `idx` can be left out of the decode.
+        Ok(DynSolType::Tuple(fields[..=idx].to_vec())
+            .abi_decode_params(data)
+            .ok()
+            .and_then(|value| {
+                value
+                    .as_fixed_seq()
+                    .and_then(|fields| fields.last().cloned())
+            })
+            .map(|value| abi_sql(&value))
+            .unwrap_or(Value::Null))
+    })?;
+    conn.create_scalar_function("topic_address", 1, flags, |ctx| {
+        let Some(topic) = blob_arg(ctx, 0)? else {
+            return Ok(None);
+        };
+        let topic: [u8; 32] = topic.try_into().map_err(|_| {
+            user_error(format!(
+                "expected a 32-byte topic BLOB, got {} bytes",
+                topic.len()
+            ))
+        })?;
+        // A topic with any of the top 12 bytes set doesn't hold an address.
+        Ok(topic[..12]
+            .iter()
+            .all(|&b| b == 0)
+            .then(|| topic[12..].to_vec()))
+    })?;
+
+    // Hashing helpers, so `WHERE topic0 = event_topic('Swap(...)')` needs no
+    // precomputed blob. Being deterministic, a call with constant arguments is
+    // evaluated once per statement and the comparison can still use an index;
+    // the parsed signatures are also cached per statement.
+    conn.create_scalar_function("keccak256", 1, flags, |ctx| {
+        Ok(match ctx.get_raw(0) {
+            ValueRef::Null => None,
+            ValueRef::Text(bytes) | ValueRef::Blob(bytes) => Some(keccak256(bytes).to_vec()),
+            _ => return Err(user_error("keccak256 expects TEXT or a BLOB".to_string())),
+        })
+    })?;
+    conn.create_scalar_function("event_topic", 1, flags, |ctx| {
+        let topic = ctx.get_or_create_aux(0, |signature| {
+            let signature = text_arg(signature, "event_topic")?;
+            Event::parse(&signature)
+                .map(|event| event.selector())
+                .map_err(|e| format!("invalid event signature '{signature}': {e}"))
+        })?;
+        Ok(topic.to_vec())
+    })?;
+    conn.create_scalar_function("selector", 1, flags, |ctx| {
+        let selector = ctx.get_or_create_aux(0, |signature| {
+            let signature = text_arg(signature, "selector")?;
+            Function::parse(&signature)
+                .map(|function| function.selector())
+                .map_err(|e| format!("invalid function signature '{signature}': {e}"))
+        })?;
+        Ok(selector.to_vec())
+    })?;
+
+    Ok(())
+}
+
+/// A signature argument as text, for the errors of `event_topic` /
+/// `selector`.
+fn text_arg(value: ValueRef<'_>, function: &str) -> Result<String, String> {
+    match value {
+        ValueRef::Text(text) => Ok(String::from_utf8_lossy(text).into_owned()),
+        _ => Err(format!("{function} expects a signature TEXT")),
+    }
+}
+
+/// Parses the type argument of `abi_decode` / `abi_decode_at`: a
+/// comma-separated list, decoded as the params of a tuple.
+fn parse_abi_types(value: ValueRef<'_>) -> Result<DynSolType, String> {
+    let ValueRef::Text(text) = value else {
+        return Err("expected an ABI type TEXT, e.g. 'uint256,address'".to_string());
+    };
+    let text = String::from_utf8_lossy(text);
+    DynSolType::parse(&format!("({text})")).map_err(|e| format!("invalid ABI type '{text}': {e}"))
+}
+
+/// JSON form of a decoded value: integers as decimal strings (they overflow
+/// JSON numbers), addresses and bytes as `0x`-hex, arrays and tuples as arrays.
+fn abi_json(value: &DynSolValue) -> serde_json::Value {
+    if let Some(b) = value.as_bool() {
+        json!(b)
+    } else if let Some((int, _)) = value.as_int() {
+        json!(int.to_string())
+    } else if let Some((uint, _)) = value.as_uint() {
+        json!(uint.to_string())
+    } else if let Some(address) = value.as_address() {
+        json!(format!("{address:#x}"))
+    } else if let Some((word, size)) = value.as_fixed_bytes() {
+        json!(format!("0x{}", hex::encode(&word[..size])))
+    } else if let Some(function) = value.as_function() {
+        json!(format!("0x{}", hex::encode(function)))
+    } else if let Some(bytes) = value.as_bytes() {
+        json!(format!("0x{}", hex::encode(bytes)))
+    } else if let Some(text) = value.as_str() {
+        json!(text)
+    } else if let Some(values) = value.as_array().or_else(|| value.as_fixed_seq()) {
+        serde_json::Value::Array(values.iter().map(abi_json).collect())
+    } else {
+        serde_json::Value::Null
+    }
+}
+
+/// SQL form of a decoded value, matching the column conventions: integers as
+/// INTEGER for the types that always fit an `i64` (`int8`..`int64`,
+/// `uint8`..`uint56`) and as 32-byte (two's-complement) BLOBs for the
+/// u256/i256 helpers otherwise, so a column of one ABI type never mixes storage
+/// classes; addresses and bytes as BLOBs; arrays and tuples as JSON.
+fn abi_sql(value: &DynSolValue) -> Value {
+    if let Some(b) = value.as_bool() {
+        Value::Integer(i64::from(b))
+    } else if let Some((int, bits)) = value.as_int() {
+        match i64::try_from(int) {
+            Ok(int) if bits <= 64 => Value::Integer(int),
+            _ => Value::Blob(int.to_be_bytes::<32>().to_vec()),
+        }
+    } else if let Some((uint, bits)) = value.as_uint() {
+        match i64::try_from(uint) {
+            Ok(uint) if bits < 64 => Value::Integer(uint),
+            _ => Value::Blob(uint.to_be_bytes::<32>().to_vec()),
+        }
+    } else if let Some(address) = value.as_address() {
+        Value::Blob(address.to_vec())
+    } else if let Some((word, size)) = value.as_fixed_bytes() {
+        Value::Blob(word[..size].to_vec())
+    } else if let Some(bytes) = value.as_bytes() {
+        Value::Blob(bytes.to_vec())
+    } else if let Some(text) = value.as_str() {
+        Value::Text(text.to_string())
+    } else {
+        Value::Text(abi_json(value).to_string())
+    }
+}
+
+type TokenInfo = (Option<String>, Option<i64>);
+
+/// The `tokens` table, read once per connection on first use. A function can't
+/// query the connection it is registered on, so the rows come from a separate
+/// read-only connection to the same file; the table holds one small row per
+/// token, so loading it whole stays cheap.
+struct LazyTokens {
+    /// `None` for an in-memory DB, which has no tokens to read.
+    path: Option<String>,
+    tokens: OnceLock<HashMap<Address, TokenInfo>>,
+}
+
+impl LazyTokens {
+    fn new(conn: &Connection) -> Self {
+        Self {
+            path: conn
+                .path()
+                .filter(|path| !path.is_empty())
+                .map(str::to_string),
+            tokens: OnceLock::new(),
+        }
+    }
+
+    fn get(&self) -> rusqlite::Result<&HashMap<Address, TokenInfo>> {
+        if let Some(tokens) = self.tokens.get() {
+            return Ok(tokens);
+        }
+        let tokens = match &self.path {
+            Some(path) => {
+                let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+                conn.busy_timeout(crate::db::shared::BUSY_TIMEOUT)?;
+                load_tokens(&conn)?
+            }
+            None => HashMap::new(),
+        };
+        Ok(self.tokens.get_or_init(|| tokens))
+    }
+}
+
+/// `symbol` and `decimals` of every row in `tokens`, by address. Empty when
+/// the table doesn't exist (a DB from before it was added).
+fn load_tokens(conn: &Connection) -> rusqlite::Result<HashMap<Address, TokenInfo>> {
+    let has_tokens: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'tokens')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_tokens {
+        return Ok(HashMap::new());
+    }
+
+    let mut stmt = conn.prepare("SELECT address, symbol, decimals FROM tokens")?;
+    let rows = stmt.query_map([], |row| {
+        let address: Vec<u8> = row.get(0)?;
+        Ok((
+            Address::from_slice(&address),
+            (
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+            ),
+        ))
+    })?;
+    rows.collect()
+}
+
+struct I256Sum;
+
+impl Aggregate<I256, Option<Vec<u8>>> for I256Sum {
+    fn init(&self, _ctx: &mut Context<'_>) -> rusqlite::Result<I256> {
+        Ok(I256::ZERO)
+    }
+
+    fn step(&self, ctx: &mut Context<'_>, sum: &mut I256) -> rusqlite::Result<()> {
+        if let Some(value) = i256_arg(ctx, 0)? {
+            *sum = sum
+                .checked_add(value)
+                .ok_or_else(|| user_error("i256_sum overflow".to_string()))?;
+        }
+        Ok(())
+    }
+
+    // `sum` is `None` over an empty set, which yields NULL like plain `SUM()`.
+    fn finalize(
+        &self,
+        _ctx: &mut Context<'_>,
+        sum: Option<I256>,
+    ) -> rusqlite::Result<Option<Vec<u8>>> {
+        Ok(sum.map(|sum| sum.to_be_bytes::<32>().to_vec()))
+    }
+}
+
+/// Argument `idx` as an i256: a 32-byte two's-complement BLOB or an INTEGER.
+/// `None` for NULL.
+fn i256_arg(ctx: &Context<'_>, idx: usize) -> rusqlite::Result<Option<I256>> {
+    match ctx.get_raw(idx) {
+        ValueRef::Null => Ok(None),
+        ValueRef::Integer(value) => {
+            Ok(Some(I256::try_from(value).map_err(|e| {
+                user_error(format!("{value} is not a valid i256: {e}"))
+            })?))
+        }
+        ValueRef::Blob(bytes) => {
+            let bytes: [u8; 32] = bytes.try_into().map_err(|_| {
+                user_error(format!(
+                    "expected a 32-byte i256 BLOB, got {} bytes",
+                    bytes.len()
+                ))
+            })?;
+            Ok(Some(I256::from_be_bytes(bytes)))
+        }
+        _ => Err(user_error("expected an i256 BLOB or INTEGER".to_string())),
+    }
+}
+
+/// Argument `idx` as an address: a 20-byte BLOB or a `0x`-prefixed hex TEXT.
+/// `None` for NULL.
+pub(crate) fn address_arg(ctx: &Context<'_>, idx: usize) -> rusqlite::Result<Option<Address>> {
+    match ctx.get_raw(idx) {
+        ValueRef::Null => Ok(None),
+        ValueRef::Blob(bytes) => Address::try_from(bytes).map(Some).map_err(|_| {
+            user_error(format!(
+                "expected a 20-byte address BLOB, got {} bytes",
+                bytes.len()
+            ))
+        }),
+        ValueRef::Text(text) => {
+            let text = String::from_utf8_lossy(text);
+            text.parse::<Address>()
+                .map(Some)
+                .map_err(|e| user_error(format!("{text} is not a valid address: {e}")))
+        }
+        _ => Err(user_error(
+            "expected an address BLOB or hex TEXT".to_string(),
+        )),
+    }
+}
+
+/// Argument `idx` as raw bytes. `None` for NULL.
+fn blob_arg<'a>(ctx: &'a Context<'_>, idx: usize) -> rusqlite::Result<Option<&'a [u8]>> {
+    match ctx.get_raw(idx) {
+        ValueRef::Null => Ok(None),
+        ValueRef::Blob(bytes) => Ok(Some(bytes)),
+        _ => Err(user_error("expected a BLOB".to_string())),
+    }
+}
+
+pub(crate) fn user_error(message: String) -> rusqlite::Error {
+    rusqlite::Error::UserFunctionError(message.into())
+}
+
+#[cfg(test)]
+mod test {
+    use alloy::{primitives::Bytes, sol_types::SolValue};
+    use revm::primitives::U256;
+
+    use super::*;
+
+    #[test]
+    fn test_i256_functions() -> rusqlite::Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register(&conn)?;
+        conn.execute("CREATE TABLE deltas (delta BLOB)", [])?;
+        for value in [-300i64, 100, 50] {
+            let blob = I256::try_from(value).unwrap().to_be_bytes::<32>().to_vec();
+            conn.execute("INSERT INTO deltas (delta) VALUES (?)", [blob])?;
+        }
+
+        let total: String = conn.query_row(
+            "SELECT i256_to_dec(i256_sum(delta)) FROM deltas",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(total, "-150");
+
+        let empty: Option<Vec<u8>> =
+            conn.query_row("SELECT i256_sum(delta) FROM deltas WHERE 0", [], |row| {
+                row.get(0)
+            })?;
+        assert_eq!(empty, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_functions() -> rusqlite::Result<()> {
+        let path = std::env::temp_dir().join(format!("mevlog-tokens-{}.db", uuid::Uuid::new_v4()));
+        let conn = Connection::open(&path)?;
+        conn.execute(
+            "CREATE TABLE tokens (address BLOB PRIMARY KEY, symbol TEXT, name TEXT, decimals INTEGER)",
+            [],
+        )?;
+        conn.execute(
+            "INSERT INTO tokens VALUES (?, 'USDC', 'USD Coin', 6)",
+            [Address::new([0x70; 20]).to_vec()],
+        )?;
+        register(&conn)?;
+
+        let (symbol, decimals, unknown): (String, i64, Option<i64>) = conn.query_row(
+            &format!(
+                "SELECT token_symbol(X'{usdc}'), token_decimals('0x{usdc}'), token_decimals(X'{other}')",
+                usdc = "70".repeat(20),
+                other = "71".repeat(20),
+            ),
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        assert_eq!(symbol, "USDC");
+        assert_eq!(decimals, 6);
+        assert_eq!(unknown, None);
+
+        drop(conn);
+        let _ = std::fs::remove_file(&path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_abi_decode_functions() -> rusqlite::Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register(&conn)?;
+        let holder = Address::new([0x11; 20]);
+        let data = (
+            U256::MAX,
+            holder,
+            Bytes::from(vec![0xbe, 0xef]),
+            "hello".to_string(),
+            vec![U256::from(1), U256::from(2)],
+            I256::try_from(-5i64).unwrap(),
+        )
+            .abi_encode_params();
+        let types = "uint256,address,bytes,string,uint256[],int256";
+
+        let decoded: String = conn.query_row(
+            "SELECT abi_decode(?, ?)",
+            rusqlite::params![data, types],
+            |row| row.get(0),
+        )?;
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&decoded).unwrap(),
+            json!([
+                U256::MAX.to_string(),
+                format!("{holder:#x}"),
+                "0xbeef",
+                "hello",
+                ["1", "2"],
+                "-5"
+            ])
+        );
+
+        let field = |types: &str, idx: i64| {
+            conn.query_row(
+                "SELECT abi_decode_at(?, ?, ?)",
+                rusqlite::params![data, types, idx],
+                |row| row.get::<_, Value>(0),
+            )
+        };
+        assert_eq!(
+            field(types, 0)?,
+            Value::Blob(U256::MAX.to_be_bytes::<32>().to_vec())
+        );
+        assert_eq!(field(types, 1)?, Value::Blob(holder.to_vec()));
+        assert_eq!(field(types, 2)?, Value::Blob(vec![0xbe, 0xef]));
+        // Types after the field may be left out.
+        assert_eq!(
+            field("uint256,address,bytes,string", 3)?,
+            Value::Text("hello".to_string())
+        );
+        assert_eq!(field(types, 4)?, Value::Text(r#"["1","2"]"#.to_string()));
+        assert_eq!(
+            field(types, 5)?,
+            Value::Blob(I256::try_from(-5i64).unwrap().to_be_bytes::<32>().to_vec())
+        );
+        assert!(field(types, 6).is_err());
+        assert!(field(types, -1).is_err());
+
+        // Static tuples and fixed arrays span several head words, so `idx`
+        // counts fields, not words.
+        let nested = (
+            (U256::from(1), U256::from(2)),
+            [U256::from(3), U256::from(4)],
+            "after".to_string(),
+        )
+            .abi_encode_params();
+        let (tuple, after): (String, String) = conn.query_row(
+            "SELECT abi_decode_at(?1, ?2, 0), abi_decode_at(?1, ?2, 2)",
+            rusqlite::params![nested, "(uint256,uint256),uint256[2],string"],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!(tuple, r#"["1","2"]"#);
+        assert_eq!(after, "after");
+
+        // Undecodable data is NULL, a bad type list an error.
+        let short: Option<String> =
+            conn.query_row("SELECT abi_decode(X'01', 'uint256')", [], |row| row.get(0))?;
+        assert_eq!(short, None);
+        assert!(
+            conn.query_row("SELECT abi_decode(X'01', 'uint257')", [], |row| {
+                row.get::<_, Option<String>>(0)
+            })
+            .is_err()
+        );
+
+        let (address, not_address): (Vec<u8>, Option<Vec<u8>>) = conn.query_row(
+            "SELECT topic_address(?), topic_address(?)",
+            rusqlite::params![holder.into_word().to_vec(), vec![0xff; 32]],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!(address, holder.to_vec());
+        assert_eq!(not_address, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_abi_decode_at_keeps_one_storage_class_per_type() -> rusqlite::Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register(&conn)?;
+        let field = |value: Vec<u8>, r#type: &str| {
+            conn.query_row(
+                "SELECT abi_decode_at(?, ?, 0)",
+                rusqlite::params![value, r#type],
+                |row| row.get::<_, Value>(0),
+            )
+        };
+
+        // Small values of wide types stay BLOBs, so they compare with large ones.
+        assert_eq!(
+            field(U256::from(1).abi_encode(), "uint256")?,
+            Value::Blob(U256::from(1).to_be_bytes::<32>().to_vec())
+        );
+        assert_eq!(
+            field(U256::from(1).abi_encode(), "uint64")?,
+            Value::Blob(U256::from(1).to_be_bytes::<32>().to_vec())
+        );
+        assert_eq!(
+            field(U256::from(u64::MAX >> 8).abi_encode(), "uint56")?,
+            Value::Integer((u64::MAX >> 8) as i64)
+        );
+        assert_eq!(
+            field(I256::try_from(-5i64).unwrap().abi_encode(), "int64")?,
+            Value::Integer(-5)
+        );
+        assert_eq!(
+            field(I256::try_from(-5i64).unwrap().abi_encode(), "int72")?,
+            Value::Blob(I256::try_from(-5i64).unwrap().to_be_bytes::<32>().to_vec())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hashing_functions() -> rusqlite::Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register(&conn)?;
+        let transfer = "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+        let (hash, topic, named_topic, selector, null): (
+            Vec<u8>,
+            Vec<u8>,
+            Vec<u8>,
+            Vec<u8>,
+            Option<Vec<u8>>,
+        ) = conn.query_row(
+            "SELECT keccak256('Transfer(address,address,uint256)'), \
+                    event_topic('Transfer(address,address,uint256)'), \
+                    event_topic('event Transfer(address indexed from, address indexed to, uint value)'), \
+                    selector('transfer(address,uint256)'), \
+                    keccak256(NULL)",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )?;
+        assert_eq!(hex::encode(&hash), transfer);
+        assert_eq!(topic, hash);
+        assert_eq!(named_topic, hash);
+        assert_eq!(hex::encode(selector), "a9059cbb");
+        assert_eq!(null, None);
+        assert!(
+            conn.query_row("SELECT selector('transfer(address')", [], |row| {
+                row.get::<_, Vec<u8>>(0)
+            })
+            .is_err()
+        );
+
+        // The constant call doesn't keep the planner off the index.
+        conn.execute_batch("CREATE TABLE t (topic0 BLOB); CREATE INDEX t_topic0 ON t (topic0);")?;
+        let plan: String = conn.query_row(
+            "EXPLAIN QUERY PLAN SELECT * FROM t \
+             WHERE topic0 = event_topic('Transfer(address,address,uint256)')",
+            [],
+            |row| row.get(3),
+        )?;
+        assert!(plan.contains("USING COVERING INDEX t_topic0"), "{plan}");
+
+        Ok(())
+    }
+}
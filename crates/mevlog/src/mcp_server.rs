@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -43,6 +44,10 @@ struct QueryParams {
     native_token_price: Option<f64>,
     #[schemars(description = "Maximum number of rows the query may return (errors when exceeded)")]
     max_rows: Option<usize>,
+    #[schemars(
+        description = "Values bound to the query's :name placeholders, keyed by name without the colon (e.g. {\"from\": \"0x1111...1111\"} for WHERE from_address = :from). 0x-prefixed 20-byte addresses and 32-byte hashes bind as BLOBs, plain integers as INTEGER, anything else as TEXT"
+    )]
+    params: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Clone, Copy, Default, Deserialize, JsonSchema)]
@@ -83,6 +88,42 @@ struct UploadQueryParams {
     native_token_price: Option<f64>,
     #[schemars(description = "Maximum number of rows the query may return (errors when exceeded)")]
     max_rows: Option<usize>,
+    #[schemars(
+        description = "Values bound to the query's :name placeholders, keyed by name without the colon (e.g. {\"from\": \"0x1111...1111\"} for WHERE from_address = :from). 0x-prefixed 20-byte addresses and 32-byte hashes bind as BLOBs, plain integers as INTEGER, anything else as TEXT"
+    )]
+    params: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct SavedQueryParams {
+    #[schemars(description = "Name of the saved query, as listed by `list_saved_queries`")]
+    name: String,
+    #[schemars(
+        description = "Values for the saved query's declared params, keyed by name; override the declared defaults. Params without a default are required"
+    )]
+    params: Option<BTreeMap<String, String>>,
+    #[schemars(
+        description = "Native token price in USD (e.g. 3500.0 for ETH); also feeds the {NATIVE_TOKEN_PRICE()} macro and convert_usd(wei, price)"
+    )]
+    native_token_price: Option<f64>,
+    #[schemars(description = "Maximum number of rows the query may return (errors when exceeded)")]
+    max_rows: Option<usize>,
+}
+
+/// SQL a `query` subprocess runs: inline or from the saved query library.
+enum QuerySource {
+    Sql(String),
+    Saved(String),
+}
+
+impl QuerySource {
+    fn into_cli_args(self) -> [String; 2] {
+        match self {
+            QuerySource::Sql(sql) => ["--sql".to_string(), sql],
+            QuerySource::Saved(name) => ["--saved".to_string(), name],
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -108,17 +149,36 @@ impl MevlogMcpServer {
     #[tool(
         description = r#"Run a read-only SQL query against the local store of indexed Ethereum transactions.
 
-This is the only tool. It runs `sql` over the per-chain SQLite store as-is (read-only; no indexing or fetching of new blocks) and returns a JSON `QueryResponse` envelope (`result`, `duration`, `chain`, `query` — `query.sql` echoes the fully-substituted SQL that produced `result`). The store is populated out-of-band by the operator (e.g. `mevlog index --live`); this tool never writes to it.
+This is the main tool. It runs `sql` over the per-chain SQLite store as-is (read-only; no indexing or fetching of new blocks) and returns a JSON `QueryResponse` envelope (`result`, `duration`, `chain`, `query` — `query.sql` echoes the fully-substituted SQL that produced `result`). The store is populated out-of-band by the operator (e.g. `mevlog index --live`); this tool never writes to it.
 
-SCHEMA — three tables (exact column names):
-  • transactions(block_number, tx_index, tx_hash, nonce, from_address, to_address, value, gas_limit, gas_used, effective_gas_price, gas_price, max_fee_per_gas, max_priority_fee_per_gas, transaction_type, success, coinbase_transfer, signature_hash, signature)
+SCHEMA — eleven tables (exact column names):
+  • transactions(block_number, tx_index, tx_hash, nonce, from_address, to_address, value, gas_limit, gas_used, effective_gas_price, gas_price, max_fee_per_gas, max_priority_fee_per_gas, transaction_type, success, coinbase_transfer, signature_hash, signature, blob_gas_used, blob_gas_price, max_fee_per_blob_gas, input)
       signature = human-readable method signature TEXT (e.g. 'transfer(address,uint256)'), signature_hash = 4-byte selector BLOB. There is NO `method` column.
+      effective_gas_price = price per gas actually paid. blob_* columns are NULL for non-blob txs; blob cost = blob_gas_used * blob_gas_price. input = full calldata BLOB (NULL when calldata storage is disabled).
   • logs(block_number, tx_index, log_index, address, topic0, topic1, topic2, topic3, data, erc20_amount, signature)
       erc20_amount = decoded ERC20 Transfer amount as a 32-byte big-endian BLOB (NULL for non-transfer logs). signature = human-readable event signature TEXT.
-  • blocks(block_number, block_hash, miner, gas_used, timestamp, base_fee_per_gas)
+  • blocks(block_number, block_hash, parent_hash, miner, gas_used, timestamp, base_fee_per_gas, blob_gas_used, excess_blob_gas, gas_limit, extra_data, size, state_root, transactions_root, difficulty, mix_hash, parent_beacon_block_root, balance_changes_traced, traces_traced, rpc_details_fetched)
+      extra_data = builder tag BLOB (CAST(extra_data AS TEXT) to read it). difficulty = 32-byte u256 BLOB; mix_hash = prevrandao post-merge. Header columns may be NULL for older rows.
+  • access_list_entries(block_number, tx_index, address, storage_key)
+      EIP-2930 access lists, one row per storage key (storage_key NULL for an address listed without keys). Join to transactions on (block_number, tx_index).
+  • authorizations(block_number, tx_index, chain_id, address, nonce, authority)
+      EIP-7702 authorization tuples of type-4 txs. address = delegation target, authority = recovered signer (NULL if the signature is invalid).
+  • withdrawals(block_number, withdrawal_index, validator_index, address, amount_gwei)
+      EIP-4895 beacon withdrawals. address = recipient, amount_gwei = INTEGER gwei (multiply by 1e9 for wei).
+      access_list_entries, authorizations and withdrawals are only populated when the store was indexed with the `rpc` fetcher.
+  • balance_changes(block_number, tx_index, address, token, delta)
+      Net per-tx balance change of an account. token = ERC20 contract (NULL for native ETH). delta = signed 32-byte two's-complement BLOB — read it with i256_to_dec / i256_sum, never the u256 helpers. Only populated for blocks traced with --balance-changes.
+  • traces(block_number, tx_index, trace_address, depth, call_type, from_address, to_address, value, gas_used, selector, signature, success)
+      Every call frame of a tx (root included, depth 0). trace_address = dot-separated path TEXT ('' for the root). call_type = CALL/STATICCALL/DELEGATECALL/CALLCODE/CREATE/CREATE2/SELFDESTRUCT. selector = 4-byte BLOB, value = u256 BLOB. Only populated for blocks traced with --traces.
+  • contracts(address, block_number, tx_index, deployer, create_type, code_hash)
+      Contract deployments. deployer = tx sender for top-level creates, factory contract for internal ones. create_type = CREATE/CREATE2. code_hash = keccak256 of the runtime code (NULL unless the block was traced). Internal deployments only appear for blocks traced with --traces.
+  • nft_transfers(block_number, tx_index, log_index, batch_index, contract, standard, from_address, to_address, token_id, amount)
+      Decoded ERC721 Transfer and ERC1155 TransferSingle/TransferBatch logs, one row per token (batch_index = position in a TransferBatch, 0 otherwise). standard = 'ERC721'/'ERC1155'. token_id and amount are u256 BLOBs; amount is 1 for ERC721. Mints have a zero from_address, burns a zero to_address.
+  • tokens(address, symbol, name, decimals)
+      ERC20 metadata resolved via eth_call for every token seen in indexed Transfer logs, only when `token_metadata = true` is set in the config. Columns are NULL when the token doesn't implement them. Prefer the token_symbol / token_decimals helpers below.
 
 RULES:
-  • Address/hash columns are BLOBs, emitted as 0x-hex. In predicates they MUST be blob literals: WHERE from_address = X'1111...1111'.
+  • Address/hash columns are BLOBs, emitted as 0x-hex. In predicates they MUST be blob literals (WHERE from_address = X'1111...1111') or bound params: WHERE from_address = :from with params={"from": "0x1111...1111"}. Every :name placeholder needs a params entry and vice versa.
   • `success` is 0/1 (SQLite has no boolean).
   • Plain SQL SUM() cannot total U256 BLOB columns (value, erc20_amount, gas cost) — use the helper functions below.
   • Never ORDER BY a u256_to_dec()/format_ether()/format_gwei()/format_usd() result — they return TEXT that SQLite sorts lexicographically (so '9' > '10'). Sort on the underlying BLOB/numeric expression and apply the display helper only in the projection.
@@ -127,7 +187,14 @@ U256 / display helper functions:
   • u256_sum(col)            aggregate sum of 32-byte BLOB column → 0x-hex BLOB
   • u256_mul(a,b) / u256_add(a,b)   exact 256-bit scalar math → BLOB (e.g. u256_mul(gas_used, effective_gas_price) = tx cost)
   • u256_to_dec(col)         BLOB → full-precision decimal string
-  • erc20_to_real(amount, decimals)   amount / 10^decimals → REAL (approx f64), e.g. erc20_to_real(erc20_amount, 6) for USDC
+  • i256_sum(col) / i256_to_dec(col)   signed counterparts for balance_changes.delta
+  • erc20_to_real(amount, decimals)   amount / 10^decimals → REAL (approx f64), e.g. erc20_to_real(erc20_amount, token_decimals(address)); never hardcode decimals
+  • token_symbol(addr) / token_decimals(addr)   ERC20 symbol TEXT / decimals INTEGER from the tokens table (NULL if unknown)
+  • abi_decode(data, 'uint256,address,bytes')   decode ABI data (log data, calldata minus selector) → JSON array TEXT; ints as decimal strings, addresses/bytes as 0x-hex. NULL if it doesn't decode
+  • abi_decode_at(data, 'uint256,address,string', idx)   decode field idx (0-based) of data laid out as the listed types (list every field up to idx) → native value (INTEGER for int8..int64/uint8..uint56, u256/i256 BLOB for wider ints; BLOB for address/bytes; TEXT for string; JSON for arrays)
+  • topic_address(topic)   indexed address topic → 20-byte address BLOB, e.g. topic_address(topic1) = from_address
+  • event_topic('Transfer(address,address,uint256)') / selector('transfer(address,uint256)')   32-byte topic0 / 4-byte selector BLOB, e.g. WHERE topic0 = event_topic('Transfer(address,address,uint256)'); keccak256(text_or_blob) → 32-byte BLOB
+  • eth_call / eth_balance are disabled here (they need the CLI's --rpc-calls); use the tables instead
   • format_ether(col) / format_gwei(col)   wei → ETH / gwei display strings
   • convert_usd(wei, price)   wei → USD amount (REAL) at the given native-token price
   • format_usd(amount)   amount → '$'-prefixed USD display string (single arg, NOT format_usd(col, price))
@@ -135,6 +202,7 @@ U256 / display helper functions:
 
 MACROS (must be brace-wrapped; resolved over RPC only when present):
   • {LATEST_BLOCK()}        → current latest block number, e.g. WHERE block_number > {LATEST_BLOCK()} - 100
+  • {BLOCK_AT("2025-01-01T00:00Z")} → first block at or after a time (RFC 3339, date or duration like "1h"), e.g. WHERE block_number >= {BLOCK_AT("24h")}
   • {NATIVE_TOKEN_PRICE()}  → native token USD price (from native_token_price param or Chainlink oracle)
   • {RESOLVE_ENS("name.eth")} → resolved address as a X'..' blob literal (Ethereum mainnet only)
 
@@ -153,7 +221,12 @@ EXAMPLES:
     async fn query(&self, params: Parameters<QueryParams>) -> Result<CallToolResult, McpError> {
         let p = params.0;
         debug!("MCP query request");
-        let args = self.query_cli_args(p.sql, p.max_rows, p.native_token_price);
+        let args = self.query_cli_args(
+            QuerySource::Sql(p.sql),
+            p.params,
+            p.max_rows,
+            p.native_token_price,
+        );
         let output = self.run_mevlog_cmd("json", &args).await?;
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
@@ -163,7 +236,7 @@ EXAMPLES:
 
 The upload is PUBLIC and effectively permanent - anyone with the CID can fetch it. Use it to share or persist query results, not as a substitute for `query`.
 
-Accepts the same `sql` / `native_token_price` / `max_rows` as `query` (same schema, U256 helpers and {MACRO()} reference - see the `query` tool description), plus `format`:
+Accepts the same `sql` / `params` / `native_token_price` / `max_rows` as `query` (same schema, U256 helpers and {MACRO()} reference - see the `query` tool description), plus `format`:
   • "json" (default) - uploads the QueryResponse envelope; returns {"cid", "gateway_url", "pinata_gateway_url", "filename"} (pinata_gateway_url is the account's dedicated Pinata gateway, which serves the file immediately; null when unknown or on the kubo backend)
   • "html" - uploads a self-contained static results page (no JavaScript); returns a short text receipt with the same cid / gateway / filename fields
 and an optional `description` (max 960 characters) that is echoed as the envelope's `description` field ("json") or used as the page title ("html").
@@ -177,7 +250,12 @@ The uploaded object is named mevlog-<content-hash>.<ext>, so identical results m
         let p = params.0;
         let format = p.format.unwrap_or_default();
         debug!(format = format.as_cli_arg(), "MCP upload_query request");
-        let mut args = self.query_cli_args(p.sql, p.max_rows, p.native_token_price);
+        let mut args = self.query_cli_args(
+            QuerySource::Sql(p.sql),
+            p.params,
+            p.max_rows,
+            p.native_token_price,
+        );
         args.push("--ipfs".to_string());
         if let Some(desc) = p.description {
             let len = desc.chars().count();
@@ -196,6 +274,35 @@ The uploaded object is named mevlog-<content-hash>.<ext>, so identical results m
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
+    #[tool(
+        description = r#"List the operator's saved query library (~/.mevlog/queries/ on the server). Returns a JSON array of {"name", "description", "blocks", "params": [{"name", "default"}], "sql"}. Run one with `run_saved_query`."#
+    )]
+    async fn list_saved_queries(&self) -> Result<CallToolResult, McpError> {
+        debug!("MCP list_saved_queries request");
+        let args = vec!["queries".to_string(), "list".to_string()];
+        let output = self.run_mevlog_cmd("json", &args).await?;
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = r#"Run a query from the saved query library by name (see `list_saved_queries`), exactly as `mevlog query --saved <name>` does, and return the same JSON `QueryResponse` envelope as `query`. Like `query`, it reads the local store as-is, so a saved query's default block range is not indexed. `params` supplies values for the query's declared params; the saved description is echoed as the envelope's `description`."#
+    )]
+    async fn run_saved_query(
+        &self,
+        params: Parameters<SavedQueryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        debug!(name = p.name.as_str(), "MCP run_saved_query request");
+        let args = self.query_cli_args(
+            QuerySource::Saved(p.name),
+            p.params,
+            p.max_rows,
+            p.native_token_price,
+        );
+        let output = self.run_mevlog_cmd("json", &args).await?;
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
     #[tool(
         description = r#"Show read-only stats for the local per-chain transactions database (indexed block range, row counts, file size) for the server's configured chain."#
     )]
@@ -212,13 +319,14 @@ The uploaded object is named mevlog-<content-hash>.<ext>, so identical results m
 }
 
 impl MevlogMcpServer {
-    /// CLI args shared by the `query` and `upload_query` tools. Always
-    /// --skip-index: these tools are read-only and never fetch or write
-    /// blocks. The local store is populated out-of-band by the operator
-    /// (e.g. `mevlog index --live`).
+    /// CLI args shared by the `query`, `upload_query` and `run_saved_query`
+    /// tools. Always `--skip-index`: these tools are read-only and never fetch
+    /// or write blocks. The local store is populated out-of-band by the
+    /// operator (e.g. `mevlog index --live`).
     fn query_cli_args(
         &self,
-        sql: String,
+        source: QuerySource,
+        params: Option<BTreeMap<String, String>>,
         max_rows: Option<usize>,
         native_token_price: Option<f64>,
     ) -> Vec<String> {
@@ -227,9 +335,12 @@ impl MevlogMcpServer {
             "--skip-index".to_string(),
             "--timeout-ms".to_string(),
             self.timeout.as_millis().to_string(),
-            "--sql".to_string(),
-            sql,
         ];
+        args.extend(source.into_cli_args());
+        for (name, value) in params.unwrap_or_default() {
+            args.push("--param".to_string());
+            args.push(format!("{name}={value}"));
+        }
         if let Some(max_rows) = max_rows {
             args.push("--max-rows".to_string());
             args.push(max_rows.to_string());
@@ -343,7 +454,7 @@ impl ServerHandler for MevlogMcpServer {
                 Implementation::new("mevlog", env!("CARGO_PKG_VERSION")),
             )
             .with_instructions(
-                "mevlog MCP server. Exposes three tools: `query` runs read-only SQL against a local store of indexed Ethereum transactions (no indexing or writes), `upload_query` runs the same SQL but uploads the rendered JSON/HTML result to IPFS and returns a CID + gateway URL, and `db_info` reports the local store's indexed block range, row counts and file size.",
+                "mevlog MCP server. Exposes five tools: `query` runs read-only SQL against a local store of indexed Ethereum transactions (no indexing or writes), `upload_query` runs the same SQL but uploads the rendered JSON/HTML result to IPFS and returns a CID + gateway URL, `list_saved_queries` and `run_saved_query` list and run the operator's saved query library, and `db_info` reports the local store's indexed block range, row counts and file size.",
             )
     }
 }
@@ -457,8 +568,10 @@ pub async fn run_mcp_server(
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use crate::mcp_server::{
-        UploadFormat, UploadQueryParams, bearer_token, check_auth, constant_time_eq,
+        QuerySource, UploadFormat, UploadQueryParams, bearer_token, check_auth, constant_time_eq,
     };
 
     #[test]
@@ -547,7 +660,13 @@ mod tests {
             std::time::Duration::from_millis(30000),
         );
 
-        let mut args = server.query_cli_args("SELECT 1".to_string(), Some(10), Some(3500.0));
+        let params = BTreeMap::from([("block".to_string(), "100".to_string())]);
+        let mut args = server.query_cli_args(
+            QuerySource::Sql("SELECT :block".to_string()),
+            Some(params),
+            Some(10),
+            Some(3500.0),
+        );
         args.push("--ipfs".to_string());
 
         assert_eq!(
@@ -560,7 +679,9 @@ mod tests {
                 "--timeout-ms".to_string(),
                 "30000".to_string(),
                 "--sql".to_string(),
-                "SELECT 1".to_string(),
+                "SELECT :block".to_string(),
+                "--param".to_string(),
+                "block=100".to_string(),
                 "--max-rows".to_string(),
                 "10".to_string(),
                 "--native-token-price".to_string(),
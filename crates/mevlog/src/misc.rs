@@ -4,12 +4,20 @@ pub mod config;
 pub mod data_fetch;
 pub mod ens_utils;
 pub mod ipfs;
+#[cfg(test)]
+pub(crate) mod mock_node;
+pub mod native_transfers;
 pub mod parquet_utils;
 pub mod revm_tracing;
 pub mod rpc_capability;
+pub mod rpc_details;
+pub mod rpc_fetch;
+pub mod rpc_pool;
 pub mod rpc_tracing;
 pub mod rpc_urls;
+pub mod saved_queries;
 pub mod shared_init;
 pub mod sql_macros;
+pub mod token_metadata;
 pub mod tx_tracing;
 pub mod utils;
@@ -1,7 +1,64 @@
-use alloy::providers::Provider;
-use eyre::{Result, eyre};
+use alloy::{eips::BlockNumberOrTag, providers::Provider};
+use chrono::{DateTime, NaiveDate};
+use eyre::{Result, bail, eyre};
+use sqlx::SqlitePool;
 
-#[derive(Debug, PartialEq)]
+use crate::db::txs::models::block::Block;
+
+/// Chain head a block range or `{LATEST_BLOCK()}` is resolved against. `safe`
+/// and `finalized` trail `latest` but are not expected to (or cannot) reorg.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockTag {
+    #[default]
+    Latest,
+    Safe,
+    Finalized,
+}
+
+impl BlockTag {
+    /// Parses a block-range token (`latest`, `safe`, `finalized`).
+    pub fn parse(input: &str) -> Option<Self> {
+        match input {
+            "latest" => Some(Self::Latest),
+            "safe" => Some(Self::Safe),
+            "finalized" => Some(Self::Finalized),
+            _ => None,
+        }
+    }
+}
+
+impl From<BlockTag> for BlockNumberOrTag {
+    fn from(tag: BlockTag) -> Self {
+        match tag {
+            BlockTag::Latest => Self::Latest,
+            BlockTag::Safe => Self::Safe,
+            BlockTag::Finalized => Self::Finalized,
+        }
+    }
+}
+
+impl std::fmt::Display for BlockTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Latest => write!(f, "latest"),
+            Self::Safe => write!(f, "safe"),
+            Self::Finalized => write!(f, "finalized"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct BlocksRange {
     pub from: u64,
     pub to: u64,
@@ -19,38 +76,46 @@ impl BlocksRange {
     pub async fn from_str(
         input: &str,
         provider: &impl Provider,
+        txs: &SqlitePool,
         latest_offset: Option<u64>,
     ) -> Result<Self> {
-        let parts: Vec<&str> = input.split(':').collect();
+        let parts = split_range(input);
+        if let Some(range) =
+            Self::from_time_parts(input, &parts, provider, txs, latest_offset).await?
+        {
+            return Ok(range);
+        }
 
         let result: Result<Self> = match parts.as_slice() {
-            ["latest"] => {
-                let latest_block = get_latest_block(provider, latest_offset).await?;
-
-                Ok(BlocksRange {
-                    from: latest_block,
-                    to: latest_block,
-                })
-            }
-            [single] => {
-                let block = single
-                    .parse::<u64>()
-                    .map_err(|_| eyre!("Invalid block number: '{}'", single))?;
+            [single] => match BlockTag::parse(single) {
+                Some(tag) => {
+                    let head_block = get_head_block(provider, tag, latest_offset).await?;
 
-                let latest_block = get_latest_block(provider, latest_offset).await?;
-                if block > latest_block {
-                    eyre::bail!(
-                        "Block number '{}' exceeds latest block '{}'",
-                        block,
-                        latest_block
-                    )
+                    Ok(BlocksRange {
+                        from: head_block,
+                        to: head_block,
+                    })
                 }
+                None => {
+                    let block = single
+                        .parse::<u64>()
+                        .map_err(|_| eyre!("Invalid block number: '{}'", single))?;
 
-                Ok(BlocksRange {
-                    from: block,
-                    to: block,
-                })
-            }
+                    let latest_block = get_latest_block(provider, latest_offset).await?;
+                    if block > latest_block {
+                        eyre::bail!(
+                            "Block number '{}' exceeds latest block '{}'",
+                            block,
+                            latest_block
+                        )
+                    }
+
+                    Ok(BlocksRange {
+                        from: block,
+                        to: block,
+                    })
+                }
+            },
             [from, to]
                 if from.chars().all(|c| c.is_numeric())
                     && to.chars().all(|c| c.is_numeric())
@@ -78,14 +143,15 @@ impl BlocksRange {
 
                 Ok(BlocksRange { from, to })
             }
-            [from, to] if *to == "latest" || to.is_empty() => {
+            [from, to] if to.is_empty() || BlockTag::parse(to).is_some() => {
                 let num_blocks = from
                     .parse::<u64>()
                     .map_err(|_| eyre!("Invalid negative block range: '{}'", from))?;
 
-                let latest_block = get_latest_block(provider, latest_offset).await?;
-                let from = latest_block.saturating_sub(num_blocks - 1);
-                let to = latest_block;
+                let tag = BlockTag::parse(to).unwrap_or_default();
+                let head_block = get_head_block(provider, tag, latest_offset).await?;
+                let from = head_block.saturating_sub(num_blocks - 1);
+                let to = head_block;
 
                 Ok(BlocksRange { from, to })
             }
@@ -95,18 +161,378 @@ impl BlocksRange {
 
         result
     }
+
+    /// Resolves a range whose start is a point in time: `T` is the first block
+    /// at or after `T`, `T1:T2` the blocks from `T1` up to (excluding) `T2`,
+    /// and `T:`, `T:latest`, `T:safe`, `T:finalized` end at that head. Returns
+    /// `None` when the start is not a time point.
+    async fn from_time_parts(
+        input: &str,
+        parts: &[&str],
+        provider: &impl Provider,
+        txs: &SqlitePool,
+        latest_offset: Option<u64>,
+    ) -> Result<Option<Self>> {
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        let Some((start, start_ts)) = parts
+            .first()
+            .and_then(|start| Some((start, parse_time_point(start, now)?)))
+        else {
+            return Ok(None);
+        };
+
+        let latest_block = get_latest_block(provider, latest_offset).await?;
+        let from = block_at_timestamp(start_ts, latest_block, provider, txs)
+            .await?
+            .ok_or_else(|| {
+                eyre!(
+                    "Start time '{}' is after latest block '{}'",
+                    start,
+                    latest_block
+                )
+            })?;
+
+        let to = match parts {
+            [_] => Some(from),
+            [_, end] if end.is_empty() => Some(latest_block),
+            [_, end] => match (BlockTag::parse(end), parse_time_point(end, now)) {
+                (Some(tag), _) => Some(get_head_block(provider, tag, latest_offset).await?),
+                (None, Some(end_ts)) => {
+                    match block_at_timestamp(end_ts, latest_block, provider, txs).await? {
+                        Some(end_block) => end_block.checked_sub(1),
+                        None => Some(latest_block),
+                    }
+                }
+                (None, None) => bail!("Invalid end of time range: '{}'", end),
+            },
+            _ => bail!("Invalid block range format: '{}'", input),
+        };
+
+        match to {
+            Some(to) if from <= to => Ok(Some(BlocksRange { from, to })),
+            _ => bail!("No blocks in time range '{}'", input),
+        }
+    }
+}
+
+impl std::fmt::Display for BlocksRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.from == self.to {
+            write!(f, "{}", self.from)
+        } else {
+            write!(f, "{}:{}", self.from, self.to)
+        }
+    }
+}
+
+/// A `--blocks` value of one or more comma-separated segments, each parsed as
+/// a [`BlocksRange`], e.g. `100,200:210,latest`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockSelection {
+    /// Resolved segments, in input order.
+    pub segments: Vec<BlocksRange>,
+}
+
+impl BlockSelection {
+    pub async fn from_str(
+        input: &str,
+        provider: &impl Provider,
+        txs: &SqlitePool,
+        latest_offset: Option<u64>,
+    ) -> Result<Self> {
+        let mut segments = vec![];
+        for segment in input.split(',') {
+            let segment = segment.trim();
+            if segment.is_empty() {
+                bail!("Empty segment in block selection: '{}'", input)
+            }
+            segments.push(BlocksRange::from_str(segment, provider, txs, latest_offset).await?);
+        }
+
+        Ok(BlockSelection { segments })
+    }
+
+    /// Segments sorted ascending, with overlapping and adjacent ones merged.
+    pub fn merged(&self) -> Vec<BlocksRange> {
+        let mut sorted = self.segments.clone();
+        sorted.sort_by_key(|segment| (segment.from, segment.to));
+
+        let mut merged: Vec<BlocksRange> = vec![];
+        for segment in sorted {
+            match merged.last_mut() {
+                Some(last) if segment.from <= last.to.saturating_add(1) => {
+                    last.to = last.to.max(segment.to)
+                }
+                _ => merged.push(segment),
+            }
+        }
+
+        merged
+    }
+
+    /// Number of distinct blocks selected; overlapping segments count once.
+    pub fn size(&self) -> u64 {
+        self.merged()
+            .iter()
+            .map(|segment| segment.to.saturating_sub(segment.from) + 1)
+            .sum()
+    }
+
+    /// Lowest selected block.
+    pub fn from(&self) -> u64 {
+        self.segments
+            .iter()
+            .map(|s| s.from)
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Highest selected block.
+    pub fn to(&self) -> u64 {
+        self.segments.iter().map(|s| s.to).max().unwrap_or_default()
+    }
+}
+
+impl From<BlocksRange> for BlockSelection {
+    fn from(range: BlocksRange) -> Self {
+        BlockSelection {
+            segments: vec![range],
+        }
+    }
+}
+
+/// Resolved segments in input order, e.g. `100,200:210,22030899`.
+impl std::fmt::Display for BlockSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let segments: Vec<String> = self.segments.iter().map(ToString::to_string).collect();
+        write!(f, "{}", segments.join(","))
+    }
+}
+
+/// Splits a `--blocks` value into its start and optional end. Timestamps carry
+/// colons of their own, so for those the separator is the colon that leaves a
+/// time point on the left and a valid range end on the right.
+fn split_range(input: &str) -> Vec<&str> {
+    if parse_time_point(input, 0).is_some() {
+        return vec![input];
+    }
+    let parts: Vec<&str> = input.split(':').collect();
+    if parts.len() <= 2 {
+        return parts;
+    }
+
+    input
+        .match_indices(':')
+        .map(|(idx, _)| (&input[..idx], &input[idx + 1..]))
+        .find(|(from, to)| {
+            parse_time_point(from, 0).is_some()
+                && (to.is_empty()
+                    || BlockTag::parse(to).is_some()
+                    || parse_time_point(to, 0).is_some())
+        })
+        .map(|(from, to)| vec![from, to])
+        .unwrap_or(parts)
+}
+
+/// Parses a point in time into unix seconds. Accepts RFC 3339 timestamps
+/// (seconds optional, e.g. `2025-01-01T00:00Z`), bare UTC dates
+/// (`2025-01-01`) and durations before `now` (`90s`, `30m`, `1h`, `7d`, `2w`).
+pub(crate) fn parse_time_point(input: &str, now: u64) -> Option<u64> {
+    if let Some(secs) = parse_duration_secs(input) {
+        return Some(now.saturating_sub(secs));
+    }
+
+    let with_offset = match input.strip_suffix('Z') {
+        Some(rest) => format!("{rest}+00:00"),
+        None => input.to_string(),
+    };
+    let timestamp = if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        time.timestamp()
+    } else if let Ok(time) = DateTime::parse_from_str(&with_offset, "%Y-%m-%dT%H:%M%:z") {
+        time.timestamp()
+    } else {
+        NaiveDate::parse_from_str(input, "%Y-%m-%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)?
+            .and_utc()
+            .timestamp()
+    };
+
+    u64::try_from(timestamp).ok()
+}
+
+fn parse_duration_secs(input: &str) -> Option<u64> {
+    if !input.is_ascii() || input.len() < 2 {
+        return None;
+    }
+    let (count, unit) = input.split_at(input.len() - 1);
+    if !count.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return None,
+    };
+
+    count.parse::<u64>().ok()?.checked_mul(unit_secs)
+}
+
+/// The first block at or below `head` stamped at or after `timestamp`, or
+/// `None` if even `head` is older. Binary-searches block timestamps over
+/// `eth_getBlockByNumber`, starting from the bounds already indexed `blocks`
+/// rows pin down, so a window that is indexed resolves without RPC.
+pub(crate) async fn block_at_timestamp(
+    timestamp: u64,
+    head: u64,
+    provider: &impl Provider,
+    txs: &SqlitePool,
+) -> Result<Option<u64>> {
+    let (before, at_or_after) = Block::timestamp_bounds(timestamp, head, txs).await?;
+
+    let mut low = before.map_or(0, |block| block + 1);
+    let mut high = match at_or_after {
+        Some(block) => block,
+        None if low > head => return Ok(None),
+        None => {
+            if block_timestamp(head, provider).await? < timestamp {
+                return Ok(None);
+            }
+            head
+        }
+    };
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if block_timestamp(mid, provider).await? >= timestamp {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    Ok(Some(high))
+}
+
+async fn block_timestamp(block: u64, provider: &impl Provider) -> Result<u64> {
+    Ok(provider
+        .get_block_by_number(BlockNumberOrTag::Number(block))
+        .await
+        .map_err(|e| eyre!("Failed to fetch block {}: {}", block, e))?
+        .ok_or_else(|| eyre!("RPC returned no block {}", block))?
+        .header
+        .timestamp)
 }
 
 pub(crate) async fn get_latest_block(
     provider: &impl Provider,
     latest_offset: Option<u64>,
 ) -> Result<u64> {
-    let mut latest_block = provider
-        .get_block_number()
-        .await
-        .map_err(eyre::Report::from)?;
+    get_head_block(provider, BlockTag::Latest, latest_offset).await
+}
+
+/// Resolves the block number of the given chain head, minus `latest_offset`.
+/// `safe`/`finalized` need one `eth_getBlockByNumber` call; chains whose RPC
+/// does not track them return an error.
+pub async fn get_head_block(
+    provider: &impl Provider,
+    tag: BlockTag,
+    latest_offset: Option<u64>,
+) -> Result<u64> {
+    let mut head_block = match tag {
+        BlockTag::Latest => provider
+            .get_block_number()
+            .await
+            .map_err(eyre::Report::from)?,
+        BlockTag::Safe | BlockTag::Finalized => {
+            provider
+                .get_block_by_number(tag.into())
+                .await
+                .map_err(|e| eyre!("Failed to fetch the '{}' block: {}", tag, e))?
+                .ok_or_else(|| eyre!("RPC returned no '{}' block", tag))?
+                .header
+                .number
+        }
+    };
     if let Some(offset) = latest_offset {
-        latest_block = latest_block.saturating_sub(offset);
+        head_block = head_block.saturating_sub(offset);
+    }
+    Ok(head_block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_tag_parse_roundtrips_display() {
+        for tag in [BlockTag::Latest, BlockTag::Safe, BlockTag::Finalized] {
+            assert_eq!(BlockTag::parse(&tag.to_string()), Some(tag));
+        }
+        assert_eq!(BlockTag::parse("pending"), None);
+        assert_eq!(BlockTag::parse("100"), None);
+    }
+
+    #[test]
+    fn test_parse_time_point() {
+        let now = 1_735_700_000;
+        assert_eq!(
+            parse_time_point("2025-01-01T00:00Z", now),
+            Some(1_735_689_600)
+        );
+        assert_eq!(
+            parse_time_point("2025-01-01T00:00:30Z", now),
+            Some(1_735_689_630)
+        );
+        assert_eq!(
+            parse_time_point("2025-01-01T02:00+02:00", now),
+            Some(1_735_689_600)
+        );
+        assert_eq!(parse_time_point("2025-01-01", now), Some(1_735_689_600));
+        assert_eq!(parse_time_point("1h", now), Some(now - 3_600));
+        assert_eq!(parse_time_point("2d", now), Some(now - 172_800));
+
+        assert_eq!(parse_time_point("100", now), None);
+        assert_eq!(parse_time_point("latest", now), None);
+        assert_eq!(parse_time_point("h", now), None);
+        assert_eq!(parse_time_point("-1h", now), None);
+    }
+
+    #[test]
+    fn test_block_selection_merges_overlapping_segments() {
+        let range = |from, to| BlocksRange { from, to };
+        let selection = BlockSelection {
+            segments: vec![
+                range(200, 210),
+                range(100, 100),
+                range(205, 220),
+                range(221, 221),
+            ],
+        };
+
+        assert_eq!(selection.merged(), vec![range(100, 100), range(200, 221)]);
+        assert_eq!(selection.size(), 23);
+        assert_eq!((selection.from(), selection.to()), (100, 221));
+        assert_eq!(selection.to_string(), "200:210,100,205:220,221");
+    }
+
+    #[test]
+    fn test_split_range_keeps_timestamp_colons() {
+        assert_eq!(split_range("100:200"), vec!["100", "200"]);
+        assert_eq!(split_range("50:"), vec!["50", ""]);
+        assert_eq!(split_range("1h:latest"), vec!["1h", "latest"]);
+        assert_eq!(split_range("2025-01-01T00:00Z"), vec!["2025-01-01T00:00Z"]);
+        assert_eq!(
+            split_range("2025-01-01T00:00Z:2025-01-02T00:00:00Z"),
+            vec!["2025-01-01T00:00Z", "2025-01-02T00:00:00Z"]
+        );
+        assert_eq!(
+            split_range("2025-01-01T00:00Z:safe"),
+            vec!["2025-01-01T00:00Z", "safe"]
+        );
+        assert_eq!(split_range("1:2:3"), vec!["1", "2", "3"]);
     }
-    Ok(latest_block)
 }
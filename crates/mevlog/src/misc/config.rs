@@ -4,10 +4,18 @@ use eyre::{Result, bail};
 use revm::primitives::{Address, FixedBytes};
 use serde::{Deserialize, Serialize};
 
-use crate::misc::shared_init::config_path;
+use crate::misc::shared_init::{BlockFetcher, config_path};
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Config {
+    #[serde(default)]
+    fetcher: Option<BlockFetcher>,
+    #[serde(default)]
+    store_input: Option<bool>,
+    #[serde(default)]
+    rpc_details: Option<bool>,
+    #[serde(default)]
+    token_metadata: Option<bool>,
     #[serde(default)]
     chains: HashMap<String, ChainConfig>,
     #[serde(default)]
@@ -140,6 +148,15 @@ const RESERVED_TABLE_NAMES: &[&str] = &[
     "transactions",
     "blocks",
     "logs",
+    "access_list_entries",
+    "authorizations",
+    "withdrawals",
+    "balance_changes",
+    "traces",
+    "contracts",
+    "nft_transfers",
+    "tokens",
+    "log_filter_coverage",
     "custom_tables",
     "_sqlx_migrations",
 ];
@@ -375,6 +392,32 @@ impl Config {
     fn default_config_template() -> &'static str {
         r#"# mevlog configuration file
 #
+# Block data fetcher used when indexing: "cryo" (default; requires the
+# external `cryo` binary) or "rpc" (built-in JSON-RPC fetcher). The
+# --fetcher flag overrides this. Must stay above any [section].
+#
+# fetcher = "rpc"
+#
+# Store full tx calldata in the `transactions.input` column (default: true).
+# Calldata is the largest per-tx field; set to false to save disk. The
+# --skip-input flag has the same effect for a single run.
+#
+# store_input = false
+#
+# Fetch the receipt fees, header fields, access lists, authorizations and
+# withdrawals that cryo output and `import-parquet` datasets lack (default:
+# false). Costs one eth_getBlockByNumber plus one eth_getBlockReceipts per
+# block. The --rpc-details flag has the same effect for a single run.
+#
+# rpc_details = true
+#
+# Resolve symbol(), name() and decimals() of every ERC20 seen in indexed
+# Transfer logs into the `tokens` table read by token_symbol/token_decimals
+# (default: false). Costs three eth_calls per new token. The
+# --token-metadata flag has the same effect for a single run.
+#
+# token_metadata = true
+#
 # Configure custom RPC endpoints for each chain by chain ID.
 # Uncomment and modify the examples below as needed.
 #
@@ -442,6 +485,22 @@ impl Config {
     pub fn ipfs(&self) -> Option<&IpfsConfig> {
         self.ipfs.as_ref()
     }
+
+    pub fn fetcher(&self) -> Option<BlockFetcher> {
+        self.fetcher
+    }
+
+    pub fn store_input(&self) -> bool {
+        self.store_input.unwrap_or(true)
+    }
+
+    pub fn rpc_details(&self) -> bool {
+        self.rpc_details.unwrap_or(false)
+    }
+
+    pub fn token_metadata(&self) -> bool {
+        self.token_metadata.unwrap_or(false)
+    }
 }
 
 #[cfg(test)]
@@ -468,6 +527,50 @@ rpc_url = "https://example.com"
         assert!(config.get_chain(1).is_some());
     }
 
+    #[test]
+    fn test_parse_fetcher() {
+        let content = r#"
+fetcher = "rpc"
+
+[chains.1]
+rpc_url = "https://example.com"
+"#;
+        let config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.fetcher(), Some(BlockFetcher::Rpc));
+
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.fetcher(), None);
+
+        assert!(toml::from_str::<Config>(r#"fetcher = "curl""#).is_err());
+    }
+
+    #[test]
+    fn test_parse_store_input() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.store_input());
+
+        let config: Config = toml::from_str("store_input = false").unwrap();
+        assert!(!config.store_input());
+    }
+
+    #[test]
+    fn test_parse_rpc_details() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(!config.rpc_details());
+
+        let config: Config = toml::from_str("rpc_details = true").unwrap();
+        assert!(config.rpc_details());
+    }
+
+    #[test]
+    fn test_parse_token_metadata() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(!config.token_metadata());
+
+        let config: Config = toml::from_str("token_metadata = true").unwrap();
+        assert!(config.token_metadata());
+    }
+
     const SWAP_TOPIC0: &str = "0xd78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822";
 
     fn swaps_toml(columns: &str) -> String {
@@ -1,18 +1,41 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use eyre::Result;
 use sqlx::SqlitePool;
 use tokio::process::Command;
 use tracing::warn;
 
-use crate::db::txs::models::{block::Block, log::Log, transaction::Transaction};
-use crate::misc::shared_init::CryoOpts;
+use crate::db::txs::models::{
+    access_list_entry::AccessListEntry, authorization::Authorization, block::Block, log::Log,
+    transaction::Transaction, withdrawal::Withdrawal,
+};
+use crate::misc::{
+    rpc_fetch,
+    shared_init::{BlockFetcher, CryoOpts},
+};
 use crate::models::evm_chain::EVMChain;
 
+#[derive(Default)]
 pub struct BatchedBlockData {
     pub txs_by_block: HashMap<u64, Vec<Transaction>>,
     pub logs_by_block: HashMap<u64, Vec<Log>>,
     pub blocks_by_block: HashMap<u64, Block>,
+    /// Only filled by the native RPC fetcher; cryo datasets carry no access
+    /// lists, authorization lists or withdrawals. Blocks saved without them
+    /// are backfilled by [`backfill_rpc_details`] with `--rpc-details`.
+    ///
+    /// [`backfill_rpc_details`]: crate::misc::rpc_details::backfill_rpc_details
+    pub access_list_entries_by_block: HashMap<u64, Vec<AccessListEntry>>,
+    pub authorizations_by_block: HashMap<u64, Vec<Authorization>>,
+    pub withdrawals_by_block: HashMap<u64, Vec<Withdrawal>>,
+    /// Whether the fields above were fetched, so the saved blocks can be
+    /// marked [`BlockMarker::RpcDetails`].
+    ///
+    /// [`BlockMarker::RpcDetails`]: crate::db::txs::models::block::BlockMarker::RpcDetails
+    pub rpc_details: bool,
 }
 
 fn cryo_cache_dir(chain: &EVMChain) -> PathBuf {
@@ -35,17 +58,28 @@ pub struct CachedRange {
 }
 
 fn scan_cached_ranges(chain: &EVMChain, data_type: &str) -> Vec<CachedRange> {
-    let cache_dir = cryo_cache_dir(chain);
-    let chain_name = chain.cryo_cache_dir_name();
+    scan_parquet_ranges(
+        &cryo_cache_dir(chain),
+        &chain.cryo_cache_dir_name(),
+        data_type,
+    )
+}
 
-    if !cache_dir.exists() {
+/// Lists the `<chain_name>__<data_type>__A_to_B.parquet` files in `dir`
+/// (cryo's output naming), sorted by start block.
+pub(crate) fn scan_parquet_ranges(
+    dir: &Path,
+    chain_name: &str,
+    data_type: &str,
+) -> Vec<CachedRange> {
+    if !dir.exists() {
         return vec![];
     }
 
     let prefix = format!("{}__{}", chain_name, data_type);
     let mut ranges = vec![];
 
-    if let Ok(entries) = std::fs::read_dir(&cache_dir) {
+    if let Ok(entries) = std::fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if let Some(filename) = path.file_name().and_then(|f| f.to_str())
@@ -88,21 +122,7 @@ fn analyze_coverage(
     start_block: u64,
     end_block: u64,
 ) -> CoverageAnalysis {
-    let mut covered = vec![false; (end_block - start_block + 1) as usize];
-
-    for range in cached_ranges {
-        if range.end < start_block || range.start > end_block {
-            continue;
-        }
-
-        let cover_start = range.start.max(start_block);
-        let cover_end = range.end.min(end_block);
-
-        for block in cover_start..=cover_end {
-            let idx = (block - start_block) as usize;
-            covered[idx] = true;
-        }
-    }
+    let covered = coverage_mask(cached_ranges, start_block, end_block);
 
     let mut missing_ranges = vec![];
     let mut gap_start: Option<u64> = None;
@@ -126,6 +146,32 @@ fn analyze_coverage(
     CoverageAnalysis { missing_ranges }
 }
 
+/// Per-block flags for `[start_block, end_block]`: `true` where some file in
+/// `cached_ranges` covers the block (index `block - start_block`).
+pub(crate) fn coverage_mask(
+    cached_ranges: &[CachedRange],
+    start_block: u64,
+    end_block: u64,
+) -> Vec<bool> {
+    let mut covered = vec![false; (end_block - start_block + 1) as usize];
+
+    for range in cached_ranges {
+        if range.end < start_block || range.start > end_block {
+            continue;
+        }
+
+        let cover_start = range.start.max(start_block);
+        let cover_end = range.end.min(end_block);
+
+        for block in cover_start..=cover_end {
+            let idx = (block - start_block) as usize;
+            covered[idx] = true;
+        }
+    }
+
+    covered
+}
+
 fn collect_files_for_range(
     cached_ranges: &[CachedRange],
     start_block: u64,
@@ -138,6 +184,23 @@ fn collect_files_for_range(
         .collect()
 }
 
+/// Optional cryo columns mevlog stores on top of a data type's defaults.
+/// Block rows are parsed by column name, so extra columns are safe to add.
+/// `extra_data` is a default column; cryo has no `difficulty`, `mix_hash` or
+/// `parent_beacon_block_root`, so those stay `NULL` for cryo-indexed blocks.
+fn cryo_include_columns(data_type: &str) -> &'static [&'static str] {
+    match data_type {
+        "blocks" => &[
+            "parent_hash",
+            "gas_limit",
+            "size",
+            "state_root",
+            "transactions_root",
+        ],
+        _ => &[],
+    }
+}
+
 async fn run_cryo_batch(
     data_type: &str,
     start_block: u64,
@@ -147,24 +210,31 @@ async fn run_cryo_batch(
     cryo_opts: &CryoOpts,
 ) -> Result<()> {
     let range = format!("{}:{}", start_block, end_block + 1);
-    let cmd = Command::new("cryo")
-        .args([
-            data_type,
-            "-b",
-            &range,
-            "--rpc",
-            rpc_url,
-            "--output-dir",
-            cryo_cache_dir(chain).display().to_string().as_str(),
-            "--requests-per-second",
-            &cryo_opts.cryo_requests_per_second.to_string(),
-            "--max-concurrent-requests",
-            &cryo_opts.cryo_max_concurrent_requests.to_string(),
-            "--max-retries",
-            &cryo_opts.cryo_max_retries.to_string(),
-            "--initial-backoff",
-            &cryo_opts.cryo_initial_backoff.to_string(),
-        ])
+    let mut command = Command::new("cryo");
+    command.args([
+        data_type,
+        "-b",
+        &range,
+        "--rpc",
+        rpc_url,
+        "--output-dir",
+        cryo_cache_dir(chain).display().to_string().as_str(),
+        "--requests-per-second",
+        &cryo_opts.cryo_requests_per_second.to_string(),
+        "--max-concurrent-requests",
+        &cryo_opts.cryo_max_concurrent_requests.to_string(),
+        "--max-retries",
+        &cryo_opts.cryo_max_retries.to_string(),
+        "--initial-backoff",
+        &cryo_opts.cryo_initial_backoff.to_string(),
+    ]);
+
+    let include_columns = cryo_include_columns(data_type);
+    if !include_columns.is_empty() {
+        command.arg("--include-columns").args(include_columns);
+    }
+
+    let cmd = command
         // Reap the cryo child if this future is dropped (a query timeout, or a
         // sibling batch erroring out of `buffer_unordered` and tearing down the
         // stream); otherwise orphaned cryo processes keep hitting RPCs and
@@ -198,10 +268,16 @@ pub(crate) async fn fetch_blocks_batch(
     chain: &EVMChain,
     sqlite: &SqlitePool,
     cryo_opts: &CryoOpts,
+    fetcher: BlockFetcher,
 ) -> Result<BatchedBlockData> {
+    if fetcher == BlockFetcher::Rpc {
+        return rpc_fetch::fetch_blocks_batch(start_block, end_block, rpc_url, sqlite, cryo_opts)
+            .await;
+    }
+
     if which::which("cryo").is_err() {
         eyre::bail!(
-            "'cryo' command not found in PATH. Please install it by running 'cargo install cryo_cli' or visit https://github.com/paradigmxyz/cryo"
+            "'cryo' command not found in PATH. Please install it by running 'cargo install cryo_cli' or visit https://github.com/paradigmxyz/cryo, or pass '--fetcher rpc' to use the built-in JSON-RPC fetcher"
         );
     }
 
@@ -226,25 +302,54 @@ pub(crate) async fn fetch_blocks_batch(
         run_cryo_batch("blocks", *gap_start, *gap_end, rpc_url, chain, cryo_opts).await?;
     }
 
-    let tx_ranges = scan_cached_ranges(chain, "transactions");
-    let tx_files = collect_files_for_range(&tx_ranges, start_block, end_block);
-
-    let log_ranges = scan_cached_ranges(chain, "logs");
-    let log_files = collect_files_for_range(&log_ranges, start_block, end_block);
+    parse_parquet_batch(
+        &scan_cached_ranges(chain, "transactions"),
+        &scan_cached_ranges(chain, "logs"),
+        &scan_cached_ranges(chain, "blocks"),
+        start_block,
+        end_block,
+        sqlite,
+    )
+    .await
+}
 
-    let block_ranges = scan_cached_ranges(chain, "blocks");
-    let block_files = collect_files_for_range(&block_ranges, start_block, end_block);
+/// Parses the `transactions`, `logs` and `blocks` parquet files overlapping
+/// `[start_block, end_block]` into per-block rows. Shared by the cryo fetcher
+/// (over its cache dir) and `import-parquet` (over an external dataset).
+pub(crate) async fn parse_parquet_batch(
+    tx_ranges: &[CachedRange],
+    log_ranges: &[CachedRange],
+    block_ranges: &[CachedRange],
+    start_block: u64,
+    end_block: u64,
+    sqlite: &SqlitePool,
+) -> Result<BatchedBlockData> {
+    let tx_files = collect_files_for_range(tx_ranges, start_block, end_block);
+    let log_files = collect_files_for_range(log_ranges, start_block, end_block);
+    let block_files = collect_files_for_range(block_ranges, start_block, end_block);
 
-    let txs_by_block =
+    let mut txs_by_block =
         parse_batch_txs_from_files(&tx_files, start_block, end_block, sqlite).await?;
     let logs_by_block =
         parse_batch_logs_from_files(&log_files, start_block, end_block, sqlite).await?;
     let blocks_by_block = parse_batch_blocks_from_files(&block_files, start_block, end_block)?;
 
+    // cryo's txs carry no receipt `effectiveGasPrice`; derive it from the
+    // block base fee the same way the receipt does.
+    for (block_number, txs) in txs_by_block.iter_mut() {
+        let base_fee_per_gas = blocks_by_block
+            .get(block_number)
+            .and_then(|block| block.base_fee_per_gas);
+        for tx in txs {
+            tx.apply_base_fee(base_fee_per_gas);
+        }
+    }
+
     Ok(BatchedBlockData {
         txs_by_block,
         logs_by_block,
         blocks_by_block,
+        ..Default::default()
     })
 }
 
@@ -291,6 +396,32 @@ pub(crate) async fn prune_indexed_cache(
     Ok(removed)
 }
 
+/// Deletes every cached cryo parquet file overlapping `[from, to]`, indexed or
+/// not. Called after a reorg rollback so re-indexing refetches the canonical
+/// blocks instead of replaying orphaned ones from the cache.
+pub(crate) fn invalidate_cached_range(chain: &EVMChain, from: u64, to: u64) -> u64 {
+    let mut removed = 0;
+
+    for data_type in ["transactions", "logs", "blocks"] {
+        for range in scan_cached_ranges(chain, data_type) {
+            if range.end < from || range.start > to {
+                continue;
+            }
+
+            match std::fs::remove_file(&range.path) {
+                Ok(()) => removed += 1,
+                Err(e) => warn!(
+                    "Failed to remove cached parquet {}: {}",
+                    range.path.display(),
+                    e
+                ),
+            }
+        }
+    }
+
+    removed
+}
+
 async fn parse_batch_txs_from_files(
     files: &[PathBuf],
     start_block: u64,
@@ -307,12 +438,9 @@ async fn parse_batch_txs_from_files(
         for batch_result in reader {
             let batch = batch_result?;
 
-            for row_idx in 0..batch.num_rows() {
-                let (tx, block_number) =
-                    Transaction::from_parquet_row(&batch, row_idx, sqlite).await?;
-
-                if block_number >= start_block && block_number <= end_block {
-                    txs_by_block.entry(block_number).or_default().push(tx);
+            for tx in Transaction::from_parquet_batch(&batch, sqlite).await? {
+                if tx.block_number >= start_block && tx.block_number <= end_block {
+                    txs_by_block.entry(tx.block_number).or_default().push(tx);
                 }
             }
         }
@@ -337,12 +465,9 @@ async fn parse_batch_logs_from_files(
         for batch_result in reader {
             let batch = batch_result?;
 
-            for row_idx in 0..batch.num_rows() {
-                let log = Log::from_parquet_row(&batch, row_idx, sqlite).await?;
-                let block_number = log.block_number;
-
-                if block_number >= start_block && block_number <= end_block {
-                    logs_by_block.entry(block_number).or_default().push(log);
+            for log in Log::from_parquet_batch(&batch, sqlite).await? {
+                if log.block_number >= start_block && log.block_number <= end_block {
+                    logs_by_block.entry(log.block_number).or_default().push(log);
                 }
             }
         }
@@ -366,11 +491,9 @@ fn parse_batch_blocks_from_files(
         for batch_result in reader {
             let batch = batch_result?;
 
-            for row_idx in 0..batch.num_rows() {
-                let (block, block_number) = Block::from_parquet_row(&batch, row_idx)?;
-
-                if block_number >= start_block && block_number <= end_block {
-                    blocks_by_block.insert(block_number, block);
+            for block in Block::from_parquet_batch(&batch)? {
+                if block.block_number >= start_block && block.block_number <= end_block {
+                    blocks_by_block.insert(block.block_number, block);
                 }
             }
         }
@@ -467,4 +590,41 @@ mod tests {
         );
         assert_eq!(parse_block_range_from_filename("invalid_filename"), None);
     }
+
+    #[tokio::test]
+    async fn test_parse_parquet_batch_reads_fixtures() -> Result<()> {
+        let (sigs, _cl) = crate::db::sigs::models::event::test::setup_test_db().await;
+        let fixtures =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cryo/ethereum");
+        let ranges = |data_type: &str| scan_parquet_ranges(&fixtures, "ethereum", data_type);
+
+        let batch = parse_parquet_batch(
+            &ranges("transactions"),
+            &ranges("logs"),
+            &ranges("blocks"),
+            25215353,
+            25215357,
+            &sigs,
+        )
+        .await?;
+
+        assert_eq!(batch.blocks_by_block.len(), 5);
+        let logs: usize = batch.logs_by_block.values().map(Vec::len).sum();
+        assert_eq!(logs, 3868);
+
+        let txs: Vec<&Transaction> = batch.txs_by_block.values().flatten().collect();
+        assert!(!txs.is_empty());
+        assert!(
+            txs.iter()
+                .all(|tx| (25215353..=25215357).contains(&tx.block_number))
+        );
+        // Calls with a selector always get a signature, `?` when unresolved.
+        assert!(
+            txs.iter()
+                .filter(|tx| tx.signature_hash.is_some())
+                .all(|tx| tx.signature.is_some())
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,83 @@
+//! Minimal HTTP JSON-RPC node for tests of code that talks to a provider.
+
+use std::sync::Arc;
+
+use eyre::Result;
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// Answer to a request: its `result`, or its `error` object.
+pub(crate) type MockResponse = std::result::Result<Value, Value>;
+
+/// Serves JSON-RPC on a local port, answering each request with
+/// `handler(method, params)`. Returns the node's URL.
+pub(crate) async fn mock_node<F>(handler: F) -> Result<String>
+where
+    F: Fn(&str, &Value) -> MockResponse + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}", listener.local_addr()?);
+    let handler = Arc::new(handler);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve(stream, handler.clone()));
+        }
+    });
+    Ok(url)
+}
+
+async fn serve<F>(mut stream: TcpStream, handler: Arc<F>) -> Result<()>
+where
+    F: Fn(&str, &Value) -> MockResponse,
+{
+    let mut buf = vec![];
+    loop {
+        let header_end = loop {
+            if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+            let mut chunk = [0; 4096];
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                return Ok(());
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        };
+        let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+        let content_length: usize = headers
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length:"))
+            .map_or(Ok(0), |len| len.trim().parse())?;
+        while buf.len() < header_end + content_length {
+            let mut chunk = [0; 4096];
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                return Ok(());
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        let request: Value = serde_json::from_slice(&buf[header_end..header_end + content_length])?;
+        buf.drain(..header_end + content_length);
+
+        let id = request["id"].clone();
+        let method = request["method"].as_str().unwrap_or_default();
+        let response = match handler(method, &request["params"]) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        }
+        .to_string();
+        stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                     content-length: {}\r\n\r\n{response}",
+                    response.len()
+                )
+                .as_bytes(),
+            )
+            .await?;
+    }
+}
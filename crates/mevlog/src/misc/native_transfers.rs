@@ -0,0 +1,153 @@
+use alloy::rpc::types::trace::{
+    geth::CallFrame,
+    parity::{Action, CallType, TraceOutput, TransactionTrace},
+};
+use revm::primitives::{Address, U256};
+
+/// ETH moved between two accounts by a single call frame of a tx.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+}
+
+/// Value transfers of a geth `callTracer` tree, in execution order.
+///
+/// Only frames that actually move ETH count: `CALL`, `CREATE`/`CREATE2` and
+/// `SELFDESTRUCT`. Delegate, static and callcode frames never change balances
+/// between accounts, and a frame that errored is skipped together with its
+/// whole subtree, since the revert undid every transfer inside it.
+pub(crate) fn call_frame_transfers(root: &CallFrame) -> Vec<NativeTransfer> {
+    let mut result = Vec::new();
+    collect_frame_transfers(root, &mut result);
+    result
+}
+
+fn collect_frame_transfers(frame: &CallFrame, result: &mut Vec<NativeTransfer>) {
+    if frame.error.is_some() {
+        return;
+    }
+
+    let moves_value = matches!(
+        frame.typ.as_str(),
+        "CALL" | "CREATE" | "CREATE2" | "SELFDESTRUCT"
+    );
+    if moves_value
+        && let (Some(to), Some(value)) = (frame.to, frame.value)
+        && !value.is_zero()
+    {
+        result.push(NativeTransfer {
+            from: frame.from,
+            to,
+            value,
+        });
+    }
+
+    for call in &frame.calls {
+        collect_frame_transfers(call, result);
+    }
+}
+
+/// Value transfers of a Revm (parity-style) trace list, with the same rules as
+/// [`call_frame_transfers`]. Parity traces are flat, so reverted subtrees are
+/// found by `trace_address` prefix.
+pub(crate) fn parity_transfers(traces: &[TransactionTrace]) -> Vec<NativeTransfer> {
+    let mut reverted: Vec<&[usize]> = Vec::new();
+    let mut result = Vec::new();
+
+    for trace in traces {
+        if reverted
+            .iter()
+            .any(|prefix| trace.trace_address.starts_with(prefix))
+        {
+            continue;
+        }
+        if trace.error.is_some() {
+            reverted.push(&trace.trace_address);
+            continue;
+        }
+
+        let transfer = match &trace.action {
+            Action::Call(call) if call.call_type == CallType::Call => Some(NativeTransfer {
+                from: call.from,
+                to: call.to,
+                value: call.value,
+            }),
+            Action::Create(create) => match &trace.result {
+                Some(TraceOutput::Create(output)) => Some(NativeTransfer {
+                    from: create.from,
+                    to: output.address,
+                    value: create.value,
+                }),
+                _ => None,
+            },
+            Action::Selfdestruct(selfdestruct) => Some(NativeTransfer {
+                from: selfdestruct.address,
+                to: selfdestruct.refund_address,
+                value: selfdestruct.balance,
+            }),
+            _ => None,
+        };
+
+        if let Some(transfer) = transfer
+            && !transfer.value.is_zero()
+        {
+            result.push(transfer);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(typ: &str, from: u8, to: u8, value: u64, calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame {
+            from: Address::from([from; 20]),
+            to: Some(Address::from([to; 20])),
+            value: Some(U256::from(value)),
+            typ: typ.to_string(),
+            calls,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_call_frame_transfers_skip_reverted_and_non_value_frames() {
+        let mut reverted = frame("CALL", 2, 4, 5, vec![frame("CALL", 4, 5, 1, vec![])]);
+        reverted.error = Some("execution reverted".to_string());
+
+        let root = frame(
+            "CALL",
+            1,
+            2,
+            10,
+            vec![
+                frame("DELEGATECALL", 2, 3, 10, vec![]),
+                frame("CALL", 2, 3, 7, vec![]),
+                frame("STATICCALL", 2, 3, 0, vec![]),
+                reverted,
+            ],
+        );
+
+        let transfers = call_frame_transfers(&root);
+        assert_eq!(
+            transfers,
+            vec![
+                NativeTransfer {
+                    from: Address::from([1; 20]),
+                    to: Address::from([2; 20]),
+                    value: U256::from(10),
+                },
+                NativeTransfer {
+                    from: Address::from([2; 20]),
+                    to: Address::from([3; 20]),
+                    value: U256::from(7),
+                },
+            ]
+        );
+    }
+}
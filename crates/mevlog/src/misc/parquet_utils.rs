@@ -1,78 +1,163 @@
-use arrow::{array::Array, record_batch::RecordBatch};
-
-/// Extract a string value from a parquet column at the specified row index.
-/// Handles multiple Arrow data types and converts them to string representation.
-pub(crate) fn get_parquet_string_value(
-    batch: &RecordBatch,
-    col_idx: usize,
-    row_idx: usize,
-) -> String {
-    let column = batch.column(col_idx);
-    if column.is_null(row_idx) {
-        return String::new();
-    }
+use arrow::{
+    array::{Array, ArrayRef, AsArray, BooleanArray, LargeBinaryArray, StringArray, UInt64Array},
+    compute::cast,
+    datatypes::{DataType, UInt64Type},
+    record_batch::RecordBatch,
+};
+use eyre::{Result, eyre};
+use revm::primitives::U256;
 
-    match column.data_type() {
-        arrow::datatypes::DataType::Utf8 => {
-            let string_array = column
-                .as_any()
-                .downcast_ref::<arrow::array::StringArray>()
-                .unwrap();
-            string_array.value(row_idx).to_string()
-        }
-        arrow::datatypes::DataType::LargeUtf8 => {
-            let string_array = column
-                .as_any()
-                .downcast_ref::<arrow::array::LargeStringArray>()
-                .unwrap();
-            string_array.value(row_idx).to_string()
-        }
-        arrow::datatypes::DataType::LargeBinary => {
-            let binary_array = column
-                .as_any()
-                .downcast_ref::<arrow::array::LargeBinaryArray>()
-                .unwrap();
-            let bytes = binary_array.value(row_idx);
-            format!("0x{}", hex::encode(bytes))
-        }
-        arrow::datatypes::DataType::Int64 => {
-            let int_array = column
-                .as_any()
-                .downcast_ref::<arrow::array::Int64Array>()
-                .unwrap();
-            int_array.value(row_idx).to_string()
-        }
-        arrow::datatypes::DataType::UInt64 => {
-            let uint_array = column
-                .as_any()
-                .downcast_ref::<arrow::array::UInt64Array>()
-                .unwrap();
-            uint_array.value(row_idx).to_string()
-        }
-        arrow::datatypes::DataType::UInt32 => {
-            let uint_array = column
-                .as_any()
-                .downcast_ref::<arrow::array::UInt32Array>()
-                .unwrap();
-            uint_array.value(row_idx).to_string()
-        }
-        arrow::datatypes::DataType::Float64 => {
-            let float_array = column
-                .as_any()
-                .downcast_ref::<arrow::array::Float64Array>()
-                .unwrap();
-            float_array.value(row_idx).to_string()
+// Typed column accessors for cryo parquet batches. Each one decodes a whole
+// column with a single arrow cast kernel, so row parsers read typed values by
+// index instead of formatting every cell to a string.
+
+/// Column `name` cast to `UInt64`, whatever integer width cryo wrote it with.
+/// Nulls stay null; `None` when the file lacks the column.
+pub(crate) fn u64_column(batch: &RecordBatch, name: &str) -> Result<Option<UInt64Array>> {
+    optional_cast(batch, name, &DataType::UInt64)
+        .map(|c| c.map(|c| c.as_primitive::<UInt64Type>().clone()))
+}
+
+/// Column `name` cast to `Boolean`; `None` when the file lacks the column.
+pub(crate) fn bool_column(batch: &RecordBatch, name: &str) -> Result<Option<BooleanArray>> {
+    optional_cast(batch, name, &DataType::Boolean).map(|c| c.map(|c| c.as_boolean().clone()))
+}
+
+/// Column `name` cast to `Utf8`; `None` when the file lacks the column.
+pub(crate) fn string_column(batch: &RecordBatch, name: &str) -> Result<Option<StringArray>> {
+    optional_cast(batch, name, &DataType::Utf8).map(|c| c.map(|c| c.as_string::<i32>().clone()))
+}
+
+/// Binary column `name` (`Binary`, `LargeBinary` or `FixedSizeBinary`) as
+/// `LargeBinary`; `None` when the file lacks the column.
+pub(crate) fn binary_column(batch: &RecordBatch, name: &str) -> Result<Option<LargeBinaryArray>> {
+    optional_cast(batch, name, &DataType::LargeBinary)
+        .map(|c| c.map(|c| c.as_binary::<i64>().clone()))
+}
+
+/// Column `name` decoded to `U256`, one entry per row. cryo writes big
+/// integers as big-endian binary, a decimal string or a plain integer
+/// depending on the field and its `--u256-types`; all three are accepted.
+/// `None` when the file lacks the column.
+pub(crate) fn u256_column(batch: &RecordBatch, name: &str) -> Result<Option<Vec<Option<U256>>>> {
+    let Some(column) = batch.column_by_name(name) else {
+        return Ok(None);
+    };
+
+    let values = match column.data_type() {
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => {
+            let column = cast(column, &DataType::LargeBinary)?;
+            let column = column.as_binary::<i64>();
+            (0..column.len())
+                .map(|row_idx| bytes_at(Some(column), row_idx).and_then(U256::try_from_be_slice))
+                .collect()
         }
-        arrow::datatypes::DataType::Boolean => {
-            let bool_array = column
-                .as_any()
-                .downcast_ref::<arrow::array::BooleanArray>()
-                .unwrap();
-            bool_array.value(row_idx).to_string()
+        DataType::Utf8 | DataType::LargeUtf8 => {
+            let column = cast(column, &DataType::Utf8)?;
+            let column = column.as_string::<i32>();
+            (0..column.len())
+                .map(|row_idx| {
+                    column
+                        .is_valid(row_idx)
+                        .then(|| column.value(row_idx).parse::<U256>())
+                        .transpose()
+                })
+                .collect::<Result<_, _>>()
+                .map_err(|e| eyre!("cryo parquet column '{name}' is not a decimal: {e}"))?
         }
         _ => {
-            // For other types, try to get string representation
-            format!("{:?}", column.slice(row_idx, 1))
+            let column = cast(column, &DataType::UInt64)?;
+            let column = column.as_primitive::<UInt64Type>();
+            (0..column.len())
+                .map(|row_idx| u64_at(column, row_idx).map(U256::from))
+                .collect()
         }
+    };
+
+    Ok(Some(values))
+}
+
+/// Unwraps a column accessor result for a column cryo always writes.
+pub(crate) fn required<T>(column: Option<T>, data_type: &str, name: &str) -> Result<T> {
+    column.ok_or_else(|| eyre!("cryo {data_type} parquet is missing '{name}'"))
+}
+
+/// Value at `row_idx`, `None` when null.
+pub(crate) fn u64_at(column: &UInt64Array, row_idx: usize) -> Option<u64> {
+    column.is_valid(row_idx).then(|| column.value(row_idx))
+}
+
+/// Value at `row_idx`, `None` when null or when the column is absent.
+pub(crate) fn bytes_at(column: Option<&LargeBinaryArray>, row_idx: usize) -> Option<&[u8]> {
+    column
+        .filter(|c| c.is_valid(row_idx))
+        .map(|c| c.value(row_idx))
+}
+
+fn optional_cast(batch: &RecordBatch, name: &str, to: &DataType) -> Result<Option<ArrayRef>> {
+    let Some(column) = batch.column_by_name(name) else {
+        return Ok(None);
+    };
+
+    if column.data_type() == to {
+        return Ok(Some(column.clone()));
+    }
+
+    Ok(Some(cast(column, to).map_err(|e| {
+        eyre!("cryo parquet column '{name}' can't be read as {to}: {e}")
+    })?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{BinaryArray, UInt32Array},
+        datatypes::{Field, Schema},
+    };
+
+    use super::*;
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("block_number", DataType::UInt32, true),
+            Field::new("topic1", DataType::Binary, true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(UInt32Array::from(vec![Some(7), None])),
+                Arc::new(BinaryArray::from(vec![Some(&[0xaa, 0xbb][..]), None])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_columns_are_cast_once_and_keep_nulls() -> Result<()> {
+        let batch = batch();
+
+        let numbers = u64_column(&batch, "block_number")?.unwrap();
+        assert_eq!(u64_at(&numbers, 0), Some(7));
+        assert_eq!(u64_at(&numbers, 1), None);
+
+        let topics = binary_column(&batch, "topic1")?;
+        assert_eq!(bytes_at(topics.as_ref(), 0), Some(&[0xaa, 0xbb][..]));
+        assert_eq!(bytes_at(topics.as_ref(), 1), None);
+
+        assert!(u64_column(&batch, "gas_used")?.is_none());
+        assert!(bytes_at(None, 0).is_none());
+
+        assert_eq!(
+            u256_column(&batch, "topic1")?,
+            Some(vec![Some(U256::from(0xaabbu64)), None])
+        );
+        assert_eq!(
+            u256_column(&batch, "block_number")?,
+            Some(vec![Some(U256::from(7u64)), None])
+        );
+
+        Ok(())
     }
 }
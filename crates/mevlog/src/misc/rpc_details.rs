@@ -0,0 +1,203 @@
+use std::{collections::HashMap, time::Instant};
+
+use alloy::{
+    consensus::BlockHeader,
+    network::{AnyRpcBlock, AnyTransactionReceipt},
+};
+use eyre::{Result, bail};
+use sqlx::SqlitePool;
+use tracing::{info, warn};
+
+use crate::{
+    db::txs::models::{
+        access_list_entry::AccessListEntry,
+        authorization::Authorization,
+        block::{Block, BlockMarker},
+        transaction::ReceiptFees,
+        withdrawal::Withdrawal,
+    },
+    misc::{
+        rpc_fetch::fetch_rpc_blocks,
+        rpc_pool::{BENCH_DURATION, RpcPool},
+        shared_init::{CryoOpts, SharedDeps},
+    },
+};
+
+/// Blocks fetched per round of [`backfill_rpc_details`].
+const BACKFILL_CHUNK_BLOCKS: usize = 100;
+
+/// The rows and fields of a block that cryo datasets don't carry, built from
+/// its `eth_getBlockByNumber` (with full txs) and `eth_getBlockReceipts`
+/// responses.
+struct RpcDetails {
+    header: Block,
+    tx_fees: Vec<ReceiptFees>,
+    access_list_entries: Vec<AccessListEntry>,
+    authorizations: Vec<Authorization>,
+    withdrawals: Vec<Withdrawal>,
+}
+
+impl RpcDetails {
+    fn from_rpc(block: &AnyRpcBlock, receipts: &[AnyTransactionReceipt]) -> Result<Self> {
+        let block_number = block.header.number();
+        let txs: Vec<_> = block.transactions.txns().collect();
+        if txs.len() != receipts.len() {
+            bail!(
+                "Block {} has {} txs but {} receipts",
+                block_number,
+                txs.len(),
+                receipts.len()
+            );
+        }
+
+        let mut details = Self {
+            header: Block::from_rpc(block),
+            tx_fees: vec![],
+            access_list_entries: vec![],
+            authorizations: vec![],
+            withdrawals: Withdrawal::from_rpc(block),
+        };
+
+        for (tx, receipt) in txs.into_iter().zip(receipts) {
+            details.tx_fees.push(ReceiptFees::from_rpc(tx, receipt));
+            details
+                .access_list_entries
+                .extend(AccessListEntry::from_rpc(tx, block_number));
+            details
+                .authorizations
+                .extend(Authorization::from_rpc(tx, block_number));
+        }
+
+        Ok(details)
+    }
+
+    /// Saves the rows and marks the block in one transaction, so an
+    /// interrupted backfill leaves the block to be fetched again.
+    async fn save(&self, conn: &SqlitePool) -> Result<()> {
+        let mut db_tx = conn.begin().await?;
+
+        self.header.update_header(&mut *db_tx).await?;
+        for fees in &self.tx_fees {
+            fees.update(&mut *db_tx).await?;
+        }
+        for entry in &self.access_list_entries {
+            entry.save(&mut *db_tx).await?;
+        }
+        for authorization in &self.authorizations {
+            authorization.save(&mut *db_tx).await?;
+        }
+        for withdrawal in &self.withdrawals {
+            withdrawal.save(&mut *db_tx).await?;
+        }
+        Block::mark(
+            BlockMarker::RpcDetails,
+            self.header.block_number,
+            &mut *db_tx,
+        )
+        .await?;
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// Fills in the receipt fees, header fields, access lists, authorizations and
+/// withdrawals of every indexed block in `from..=to` that was saved without
+/// them: blocks from the cryo fetcher or `import-parquet`, whose datasets carry
+/// no receipts, tx bodies beyond calldata or withdrawals. Opt-in via
+/// [`CryoOpts::rpc_details`], since each block costs one
+/// `eth_getBlockByNumber` and one `eth_getBlockReceipts`, paced by the
+/// [`CryoOpts`] knobs like the native fetcher.
+///
+/// Chunks are fetched from the `--rpc-url` endpoints with the same failover as
+/// indexing (see [`RpcPool`]). A chunk that fails on all of them is left
+/// unmarked and retried on the next run over its blocks.
+pub(crate) async fn backfill_rpc_details(
+    from: u64,
+    to: u64,
+    deps: &SharedDeps,
+    cryo_opts: &CryoOpts,
+) -> Result<()> {
+    let unmarked = Block::unmarked(BlockMarker::RpcDetails, from, to, &deps.txs).await?;
+    if unmarked.is_empty() {
+        return Ok(());
+    }
+
+    info!(
+        "Fetching RPC-only block details for {} blocks",
+        unmarked.len()
+    );
+
+    let mut pool = RpcPool::new(&deps.rpc_urls);
+    for chunk in unmarked.chunks(BACKFILL_CHUNK_BLOCKS) {
+        let Some(fetched) = fetch_chunk(chunk, &mut pool, cryo_opts).await else {
+            continue;
+        };
+
+        let stored: HashMap<u64, Block> = Block::query_where(
+            &format!(
+                "block_number BETWEEN {} AND {}",
+                chunk[0],
+                chunk[chunk.len() - 1]
+            ),
+            &deps.txs,
+        )
+        .await?
+        .into_iter()
+        .map(|block| (block.block_number, block))
+        .collect();
+
+        for (block, receipts) in &fetched {
+            let block_number = block.header.number();
+            if stored
+                .get(&block_number)
+                .is_none_or(|stored| stored.block_hash != block.header.hash)
+            {
+                // Reorged since it was indexed; details of the new block would
+                // not match the stored txs.
+                warn!("Block {block_number} changed since it was indexed, skipping its details");
+                continue;
+            }
+            match RpcDetails::from_rpc(block, receipts) {
+                Ok(details) => details.save(&deps.txs).await?,
+                Err(e) => warn!("Skipping details of block {block_number}: {e}"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetches `chunk` from the first idle endpoint that serves it, or `None` once
+/// it failed on every endpoint that isn't benched.
+async fn fetch_chunk(
+    chunk: &[u64],
+    pool: &mut RpcPool,
+    cryo_opts: &CryoOpts,
+) -> Option<Vec<(AnyRpcBlock, Vec<AnyTransactionReceipt>)>> {
+    for endpoint in pool.idle(Instant::now()) {
+        pool.start(endpoint);
+        match fetch_rpc_blocks(chunk.iter().copied(), pool.rpc_url(endpoint), cryo_opts).await {
+            Ok(fetched) => {
+                pool.record_success(endpoint);
+                return Some(fetched);
+            }
+            Err(e) => {
+                if pool.record_failure(endpoint, Instant::now()) {
+                    warn!(
+                        "Benching RPC endpoint {} for {}s after repeated failures",
+                        pool.label(endpoint),
+                        BENCH_DURATION.as_secs()
+                    );
+                }
+                warn!(
+                    "Fetching details of blocks {}-{} from RPC endpoint {} failed: {e}",
+                    chunk[0],
+                    chunk[chunk.len() - 1],
+                    pool.label(endpoint)
+                );
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,489 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::Duration,
+};
+
+use alloy::{
+    consensus::Transaction as TxTrait,
+    eips::{BlockId, BlockNumberOrTag},
+    network::{AnyNetwork, AnyRpcBlock, AnyRpcTransaction, AnyTransactionReceipt},
+    primitives::{TxHash, TxKind},
+    providers::{Provider, RootProvider},
+    rpc::{client::RpcClient, types::Log as RpcLog},
+    transports::{
+        TransportError,
+        layers::{RateLimitRetryPolicy, RetryBackoffLayer, RetryPolicy},
+    },
+};
+use eyre::Result;
+use futures_util::{StreamExt, TryStreamExt, stream};
+use sqlx::SqlitePool;
+use tokio::{sync::Mutex, time::Instant};
+
+use crate::db::{
+    sigs::models::{event::Event, method::Method},
+    txs::models::{
+        access_list_entry::AccessListEntry, authorization::Authorization, block::Block, log::Log,
+        transaction::Transaction, withdrawal::Withdrawal,
+    },
+};
+use crate::misc::{data_fetch::BatchedBlockData, shared_init::CryoOpts};
+
+/// Compute units per second budget handed to the retry layer. Request pacing
+/// is done by [`RequestLimiter`]; the layer only uses this to size its
+/// rate-limit backoff.
+const RETRY_COMPUTE_UNITS_PER_SECOND: u64 = 100;
+
+/// Lowercased error message fragments with which providers reject an
+/// `eth_getLogs` call for matching too many logs or spanning too many blocks.
+const TOO_MANY_RESULTS_HINTS: [&str; 8] = [
+    "query returned more than",
+    "response size",
+    "too many results",
+    "too many logs",
+    "block range",
+    "range is too large",
+    "range too large",
+    "is limited to",
+];
+
+/// Spaces requests evenly so the fetcher never exceeds
+/// `--cryo-requests-per-second`, regardless of concurrency.
+pub(crate) struct RequestLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RequestLimiter {
+    pub(crate) fn new(requests_per_second: u64) -> Self {
+        let interval = if requests_per_second == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(1.0 / requests_per_second as f64)
+        };
+
+        Self {
+            interval,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub(crate) async fn acquire(&self) {
+        let mut next = self.next.lock().await;
+        let now = Instant::now();
+        if *next > now {
+            tokio::time::sleep_until(*next).await;
+        }
+        *next = (*next).max(now) + self.interval;
+    }
+}
+
+/// Native alternative to cryo: pulls `start_block..=end_block` over JSON-RPC
+/// (one `eth_getBlockByNumber` with full txs plus one `eth_getBlockReceipts`
+/// per non-empty block) and builds the same [`BatchedBlockData`] the parquet
+/// path produces, without touching the cryo cache.
+///
+/// Honors the [`CryoOpts`] knobs: requests are paced to
+/// `cryo_requests_per_second`, at most `cryo_max_concurrent_requests` blocks are
+/// in flight, and provider errors are retried `cryo_max_retries` times starting
+/// from a `cryo_initial_backoff` ms backoff.
+pub(crate) async fn fetch_blocks_batch(
+    start_block: u64,
+    end_block: u64,
+    rpc_url: &str,
+    sqlite: &SqlitePool,
+    cryo_opts: &CryoOpts,
+) -> Result<BatchedBlockData> {
+    let fetched = fetch_rpc_blocks(start_block..=end_block, rpc_url, cryo_opts).await?;
+
+    let (selectors, topics) = resolve_signatures(
+        fetched
+            .iter()
+            .flat_map(|(rpc_block, receipts)| rpc_block.transactions.txns().zip(receipts)),
+        sqlite,
+    )
+    .await?;
+
+    let mut txs_by_block: HashMap<u64, Vec<Transaction>> = HashMap::new();
+    let mut logs_by_block: HashMap<u64, Vec<Log>> = HashMap::new();
+    let mut blocks_by_block: HashMap<u64, Block> = HashMap::new();
+    let mut access_list_entries_by_block: HashMap<u64, Vec<AccessListEntry>> = HashMap::new();
+    let mut authorizations_by_block: HashMap<u64, Vec<Authorization>> = HashMap::new();
+    let mut withdrawals_by_block: HashMap<u64, Vec<Withdrawal>> = HashMap::new();
+
+    for (rpc_block, receipts) in fetched {
+        let block = Block::from_rpc(&rpc_block);
+        let block_number = block.block_number;
+
+        let rpc_txs: Vec<_> = rpc_block.transactions.txns().collect();
+        if rpc_txs.len() != receipts.len() {
+            eyre::bail!(
+                "Block {} has {} txs but {} receipts",
+                block_number,
+                rpc_txs.len(),
+                receipts.len()
+            );
+        }
+
+        for (rpc_tx, receipt) in rpc_txs.into_iter().zip(&receipts) {
+            let tx = Transaction::from_rpc(rpc_tx, receipt, block_number, &selectors);
+            txs_by_block.entry(block_number).or_default().push(tx);
+
+            access_list_entries_by_block
+                .entry(block_number)
+                .or_default()
+                .extend(AccessListEntry::from_rpc(rpc_tx, block_number));
+            authorizations_by_block
+                .entry(block_number)
+                .or_default()
+                .extend(Authorization::from_rpc(rpc_tx, block_number));
+
+            for rpc_log in receipt.inner.inner.logs() {
+                let log = Log::from_rpc(rpc_log, block_number, &topics)?;
+                logs_by_block.entry(block_number).or_default().push(log);
+            }
+        }
+
+        withdrawals_by_block.insert(block_number, Withdrawal::from_rpc(&rpc_block));
+        blocks_by_block.insert(block_number, block);
+    }
+
+    Ok(BatchedBlockData {
+        txs_by_block,
+        logs_by_block,
+        blocks_by_block,
+        access_list_entries_by_block,
+        authorizations_by_block,
+        withdrawals_by_block,
+        rpc_details: true,
+    })
+}
+
+/// Each of `block_numbers` with full txs, and its receipts. Paced and bounded
+/// as described on [`fetch_blocks_batch`], in no particular order.
+pub(crate) async fn fetch_rpc_blocks(
+    block_numbers: impl IntoIterator<Item = u64>,
+    rpc_url: &str,
+    cryo_opts: &CryoOpts,
+) -> Result<Vec<(AnyRpcBlock, Vec<AnyTransactionReceipt>)>> {
+    let provider = fetch_provider(rpc_url, cryo_opts)?;
+    let limiter = RequestLimiter::new(cryo_opts.cryo_requests_per_second);
+    let concurrency = cryo_opts.cryo_max_concurrent_requests.max(1) as usize;
+
+    stream::iter(block_numbers)
+        .map(|block_number| fetch_block(block_number, &provider, &limiter))
+        .buffer_unordered(concurrency)
+        .try_collect()
+        .await
+}
+
+/// Provider retrying provider errors `cryo_max_retries` times starting from a
+/// `cryo_initial_backoff` ms backoff. Callers pace requests with a
+/// [`RequestLimiter`].
+pub(crate) fn fetch_provider(
+    rpc_url: &str,
+    cryo_opts: &CryoOpts,
+) -> Result<RootProvider<AnyNetwork>> {
+    let retry_layer = RetryBackoffLayer::new(
+        cryo_opts.cryo_max_retries as u32,
+        cryo_opts.cryo_initial_backoff,
+        RETRY_COMPUTE_UNITS_PER_SECOND,
+    );
+    let client = RpcClient::builder()
+        .layer(retry_layer)
+        .http(rpc_url.parse()?);
+
+    Ok(RootProvider::new(client))
+}
+
+/// Whether an `eth_getLogs` error asks for a narrower block range rather than
+/// reporting a failure.
+pub(crate) fn is_too_many_results(error: &TransportError) -> bool {
+    error.as_error_resp().is_some_and(|resp| {
+        let message = resp.message.to_lowercase();
+        TOO_MANY_RESULTS_HINTS
+            .iter()
+            .any(|hint| message.contains(hint))
+    })
+}
+
+/// The default rate-limit retries, except for "too many results" errors, which
+/// some providers report with the same `-32005` code as rate limits. Retrying
+/// those can't succeed; `index --logs-only` splits the block range instead.
+#[derive(Debug, Clone, Default)]
+struct LogsRetryPolicy(RateLimitRetryPolicy);
+
+impl RetryPolicy for LogsRetryPolicy {
+    fn should_retry(&self, error: &TransportError) -> bool {
+        !is_too_many_results(error) && self.0.should_retry(error)
+    }
+
+    fn backoff_hint(&self, error: &TransportError) -> Option<Duration> {
+        self.0.backoff_hint(error)
+    }
+}
+
+/// Provider for `index --logs-only`, retrying as [`fetch_blocks_batch`] does
+/// but surfacing "too many results" errors right away.
+pub(crate) fn logs_provider(
+    rpc_url: &str,
+    cryo_opts: &CryoOpts,
+) -> Result<RootProvider<AnyNetwork>> {
+    let retry_layer = RetryBackoffLayer::new_with_policy(
+        cryo_opts.cryo_max_retries as u32,
+        cryo_opts.cryo_initial_backoff,
+        RETRY_COMPUTE_UNITS_PER_SECOND,
+        LogsRetryPolicy::default(),
+    );
+    let client = RpcClient::builder()
+        .layer(retry_layer)
+        .http(rpc_url.parse()?);
+
+    Ok(RootProvider::new(client))
+}
+
+/// Fetches the txs that emitted `logs` (an `eth_getLogs` response) and builds
+/// their [`BatchedBlockData`]: each tx with its receipt, all of its logs (not
+/// only the matching ones) and its access list entries and authorizations,
+/// plus a header row for every block involved. The blocks' other txs and
+/// their withdrawals are left out.
+///
+/// Paced and bounded like [`fetch_blocks_batch`]: one `eth_getBlockByNumber`
+/// (without txs) per block, and one `eth_getTransactionByHash` plus one
+/// `eth_getTransactionReceipt` per tx.
+pub(crate) async fn fetch_log_txs_batch(
+    logs: &[RpcLog],
+    provider: &RootProvider<AnyNetwork>,
+    sqlite: &SqlitePool,
+    cryo_opts: &CryoOpts,
+) -> Result<BatchedBlockData> {
+    let mut block_numbers = BTreeSet::new();
+    let mut tx_hashes = BTreeSet::new();
+    for log in logs {
+        let (Some(block_number), Some(tx_hash)) = (log.block_number, log.transaction_hash) else {
+            eyre::bail!("eth_getLogs returned a log without a block number or tx hash");
+        };
+        block_numbers.insert(block_number);
+        tx_hashes.insert(tx_hash);
+    }
+
+    let limiter = RequestLimiter::new(cryo_opts.cryo_requests_per_second);
+    let concurrency = cryo_opts.cryo_max_concurrent_requests.max(1) as usize;
+
+    let rpc_blocks: Vec<AnyRpcBlock> = stream::iter(block_numbers)
+        .map(|block_number| {
+            let limiter = &limiter;
+            async move {
+                limiter.acquire().await;
+                provider
+                    .get_block_by_number(BlockNumberOrTag::Number(block_number))
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("Block {} not found", block_number))
+            }
+        })
+        .buffer_unordered(concurrency)
+        .try_collect()
+        .await?;
+    let fetched: Vec<(AnyRpcTransaction, AnyTransactionReceipt)> = stream::iter(tx_hashes)
+        .map(|tx_hash| fetch_tx(tx_hash, provider, &limiter))
+        .buffer_unordered(concurrency)
+        .try_collect()
+        .await?;
+
+    let mut batch_data = BatchedBlockData {
+        txs_by_block: HashMap::new(),
+        logs_by_block: HashMap::new(),
+        blocks_by_block: HashMap::new(),
+        access_list_entries_by_block: HashMap::new(),
+        authorizations_by_block: HashMap::new(),
+        withdrawals_by_block: HashMap::new(),
+        // Saved as logs-only blocks, which are never backfilled.
+        rpc_details: false,
+    };
+
+    for rpc_block in &rpc_blocks {
+        let block = Block::from_rpc(rpc_block);
+        batch_data.blocks_by_block.insert(block.block_number, block);
+    }
+
+    let (selectors, topics) = resolve_signatures(
+        fetched.iter().map(|(rpc_tx, receipt)| (rpc_tx, receipt)),
+        sqlite,
+    )
+    .await?;
+
+    for (rpc_tx, receipt) in &fetched {
+        let Some(block_number) = receipt.block_number else {
+            eyre::bail!(
+                "Receipt of tx {} has no block number",
+                receipt.transaction_hash
+            );
+        };
+        if !batch_data.blocks_by_block.contains_key(&block_number) {
+            // The tx moved since eth_getLogs answered; the block is rescanned
+            // once the chain settles.
+            eyre::bail!(
+                "Tx {} is now in block {}, not in a block its logs were fetched from",
+                receipt.transaction_hash,
+                block_number
+            );
+        }
+
+        let tx = Transaction::from_rpc(rpc_tx, receipt, block_number, &selectors);
+        batch_data
+            .txs_by_block
+            .entry(block_number)
+            .or_default()
+            .push(tx);
+        batch_data
+            .access_list_entries_by_block
+            .entry(block_number)
+            .or_default()
+            .extend(AccessListEntry::from_rpc(rpc_tx, block_number));
+        batch_data
+            .authorizations_by_block
+            .entry(block_number)
+            .or_default()
+            .extend(Authorization::from_rpc(rpc_tx, block_number));
+
+        for rpc_log in receipt.inner.inner.logs() {
+            let log = Log::from_rpc(rpc_log, block_number, &topics)?;
+            batch_data
+                .logs_by_block
+                .entry(block_number)
+                .or_default()
+                .push(log);
+        }
+    }
+
+    Ok(batch_data)
+}
+
+/// Signatures of the selectors and `topic0`s of `txs` and their receipts'
+/// logs, resolved with one [`Method::find_by_selectors`] and one
+/// [`Event::find_by_topics`] lookup as the parquet path does, instead of one
+/// sigs DB query per row.
+async fn resolve_signatures<'a>(
+    txs: impl Iterator<Item = (&'a AnyRpcTransaction, &'a AnyTransactionReceipt)>,
+    sqlite: &SqlitePool,
+) -> Result<(HashMap<[u8; 4], String>, HashMap<[u8; 32], String>)> {
+    let mut selectors = vec![];
+    let mut topic0s = vec![];
+    for (rpc_tx, receipt) in txs {
+        if let (TxKind::Call(_), Some(selector)) =
+            (TxTrait::kind(rpc_tx), TxTrait::input(rpc_tx).get(..4))
+        {
+            selectors.push(<[u8; 4]>::try_from(selector)?);
+        }
+        topic0s.extend(
+            receipt
+                .inner
+                .inner
+                .logs()
+                .iter()
+                .filter_map(|rpc_log| rpc_log.topics().first())
+                .map(|topic0| topic0.0),
+        );
+    }
+
+    Ok((
+        Method::find_by_selectors(&selectors, sqlite).await?,
+        Event::find_by_topics(&topic0s, sqlite).await?,
+    ))
+}
+
+async fn fetch_tx(
+    tx_hash: TxHash,
+    provider: &impl Provider<AnyNetwork>,
+    limiter: &RequestLimiter,
+) -> Result<(AnyRpcTransaction, AnyTransactionReceipt)> {
+    limiter.acquire().await;
+    let tx = provider
+        .get_transaction_by_hash(tx_hash)
+        .await?
+        .ok_or_else(|| eyre::eyre!("Tx {} not found", tx_hash))?;
+
+    limiter.acquire().await;
+    let receipt = provider
+        .get_transaction_receipt(tx_hash)
+        .await?
+        .ok_or_else(|| eyre::eyre!("Receipt of tx {} not found", tx_hash))?;
+
+    Ok((tx, receipt))
+}
+
+async fn fetch_block(
+    block_number: u64,
+    provider: &impl Provider<AnyNetwork>,
+    limiter: &RequestLimiter,
+) -> Result<(AnyRpcBlock, Vec<AnyTransactionReceipt>)> {
+    limiter.acquire().await;
+    let block = provider
+        .get_block_by_number(BlockNumberOrTag::Number(block_number))
+        .full()
+        .await?
+        .ok_or_else(|| eyre::eyre!("Block {} not found", block_number))?;
+
+    // Empty blocks have no receipts; skip the round trip.
+    if block.transactions.is_empty() {
+        return Ok((block, vec![]));
+    }
+
+    limiter.acquire().await;
+    let receipts = provider
+        .get_block_receipts(BlockId::number(block_number))
+        .await?
+        .ok_or_else(|| eyre::eyre!("Receipts for block {} not found", block_number))?;
+
+    Ok((block, receipts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_limiter_spaces_requests() {
+        let limiter = RequestLimiter::new(20);
+        let started = Instant::now();
+
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+
+        // The first request goes out immediately, the next four wait 50ms each.
+        assert!(started.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_is_too_many_results() {
+        let error = |message: &str| {
+            TransportError::ErrorResp(alloy::rpc::json_rpc::ErrorPayload {
+                code: -32005,
+                message: message.to_string().into(),
+                data: None,
+            })
+        };
+
+        assert!(is_too_many_results(&error(
+            "query returned more than 10000 results"
+        )));
+        assert!(is_too_many_results(&error(
+            "Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range"
+        )));
+        assert!(!is_too_many_results(&error("daily request count exceeded")));
+        assert!(!is_too_many_results(&error("too many requests")));
+    }
+
+    #[tokio::test]
+    async fn test_request_limiter_unlimited() {
+        let limiter = RequestLimiter::new(0);
+        let started = Instant::now();
+
+        for _ in 0..100 {
+            limiter.acquire().await;
+        }
+
+        assert!(started.elapsed() < Duration::from_millis(100));
+    }
+}
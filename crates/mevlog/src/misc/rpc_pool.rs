@@ -0,0 +1,174 @@
+use std::time::{Duration, Instant};
+
+use crate::models::json::index_response::RpcEndpointJson;
+
+/// Consecutive chunk failures after which an endpoint is benched.
+const BENCH_AFTER_FAILURES: u32 = 3;
+/// How long a benched endpoint is given no chunks.
+pub(crate) const BENCH_DURATION: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+struct Endpoint {
+    rpc_url: String,
+    busy: bool,
+    ok_chunks: u64,
+    failed_chunks: u64,
+    consecutive_failures: u32,
+    benched_until: Option<Instant>,
+}
+
+/// Health of the `--rpc-url` endpoints indexing fetches chunks from. Each
+/// endpoint serves one chunk at a time and is handed the next one as soon as
+/// it is done, so faster endpoints end up taking more chunks.
+#[derive(Debug)]
+pub(crate) struct RpcPool {
+    endpoints: Vec<Endpoint>,
+}
+
+impl RpcPool {
+    pub(crate) fn new(rpc_urls: &[String]) -> Self {
+        let endpoints = rpc_urls
+            .iter()
+            .map(|rpc_url| Endpoint {
+                rpc_url: rpc_url.clone(),
+                busy: false,
+                ok_chunks: 0,
+                failed_chunks: 0,
+                consecutive_failures: 0,
+                benched_until: None,
+            })
+            .collect();
+
+        Self { endpoints }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub(crate) fn rpc_url(&self, idx: usize) -> &str {
+        &self.endpoints[idx].rpc_url
+    }
+
+    /// Endpoints that are neither fetching nor benched at `now`.
+    pub(crate) fn idle(&self, now: Instant) -> Vec<usize> {
+        self.endpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, endpoint)| {
+                !endpoint.busy && endpoint.benched_until.is_none_or(|until| until <= now)
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    pub(crate) fn start(&mut self, idx: usize) {
+        self.endpoints[idx].busy = true;
+    }
+
+    pub(crate) fn record_success(&mut self, idx: usize) {
+        let endpoint = &mut self.endpoints[idx];
+        endpoint.busy = false;
+        endpoint.ok_chunks += 1;
+        endpoint.consecutive_failures = 0;
+        endpoint.benched_until = None;
+    }
+
+    /// Records a failed chunk. Returns `true` when this failure benched the
+    /// endpoint.
+    pub(crate) fn record_failure(&mut self, idx: usize, now: Instant) -> bool {
+        let endpoint = &mut self.endpoints[idx];
+        endpoint.busy = false;
+        endpoint.failed_chunks += 1;
+        endpoint.consecutive_failures += 1;
+
+        if endpoint
+            .consecutive_failures
+            .is_multiple_of(BENCH_AFTER_FAILURES)
+        {
+            endpoint.benched_until = Some(now + BENCH_DURATION);
+            return true;
+        }
+        false
+    }
+
+    /// Earliest moment a currently benched endpoint takes chunks again.
+    pub(crate) fn next_unbenched(&self, now: Instant) -> Option<Instant> {
+        self.endpoints
+            .iter()
+            .filter_map(|endpoint| endpoint.benched_until)
+            .filter(|&until| until > now)
+            .min()
+    }
+
+    /// Host (and port) of an endpoint, for logs and reports. The rest of the
+    /// URL is left out since RPC URL paths and queries often embed API keys.
+    pub(crate) fn label(&self, idx: usize) -> String {
+        url::Url::parse(&self.endpoints[idx].rpc_url)
+            .ok()
+            .and_then(|url| {
+                let host = url.host_str()?.to_string();
+                Some(match url.port() {
+                    Some(port) => format!("{host}:{port}"),
+                    None => host,
+                })
+            })
+            .unwrap_or_else(|| format!("#{idx}"))
+    }
+
+    /// Per-endpoint chunk counts, in `--rpc-url` order.
+    pub(crate) fn report(&self) -> Vec<RpcEndpointJson> {
+        self.endpoints
+            .iter()
+            .enumerate()
+            .map(|(idx, endpoint)| RpcEndpointJson {
+                endpoint: self.label(idx),
+                ok_chunks: endpoint.ok_chunks,
+                failed_chunks: endpoint.failed_chunks,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_is_benched_after_repeated_failures() {
+        let mut pool = RpcPool::new(&[
+            "https://eth.example.com/v2/secret".to_string(),
+            "http://localhost:8545".to_string(),
+        ]);
+        let now = Instant::now();
+
+        pool.start(0);
+        assert_eq!(pool.idle(now), vec![1]);
+
+        assert!(!pool.record_failure(0, now));
+        pool.record_success(0);
+        assert!(!pool.record_failure(0, now));
+        assert!(!pool.record_failure(0, now));
+        assert!(pool.record_failure(0, now));
+
+        assert_eq!(pool.idle(now), vec![1]);
+        assert_eq!(pool.next_unbenched(now), Some(now + BENCH_DURATION));
+        assert_eq!(pool.idle(now + BENCH_DURATION), vec![0, 1]);
+
+        assert_eq!(
+            pool.report(),
+            vec![
+                RpcEndpointJson {
+                    endpoint: "eth.example.com".to_string(),
+                    ok_chunks: 1,
+                    failed_chunks: 4,
+                },
+                RpcEndpointJson {
+                    endpoint: "localhost:8545".to_string(),
+                    ok_chunks: 0,
+                    failed_chunks: 0,
+                },
+            ]
+        );
+    }
+}
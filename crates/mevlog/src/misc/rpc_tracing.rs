@@ -4,14 +4,15 @@ use std::{
 };
 
 use alloy::{
+    eips::BlockNumberOrTag,
     primitives::{B256, TxHash},
     providers::ext::DebugApi,
     rpc::types::trace::geth::{
         CallFrame, DiffMode, GethDebugBuiltInTracerType, GethDebugTracerType,
-        GethDebugTracingOptions, GethTrace, PreStateConfig, PreStateFrame,
+        GethDebugTracingOptions, GethTrace, PreStateConfig, PreStateFrame, TraceResult,
     },
 };
-use eyre::Result;
+use eyre::{Result, eyre};
 use revm::primitives::{Address, FixedBytes};
 use tracing::{debug, info, warn};
 
@@ -52,6 +53,37 @@ pub(crate) async fn rpc_tx_calls(
     Ok(all_calls)
 }
 
+/// Root `callTracer` frame of every tx in `block_number`, in index order, from
+/// a single `debug_traceBlockByNumber` call. Fails if any tx's trace failed.
+#[hotpath::measure(log = true, future = true)]
+pub(crate) async fn rpc_block_call_frames(
+    block_number: u64,
+    provider: &Arc<GenericProvider>,
+) -> Result<Vec<CallFrame>> {
+    let tracing_opts = GethDebugTracingOptions::default().with_tracer(
+        GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer),
+    );
+    let results = provider
+        .debug_trace_block_by_number(BlockNumberOrTag::Number(block_number), tracing_opts)
+        .await?;
+
+    results
+        .into_iter()
+        .map(|result| match result {
+            TraceResult::Success {
+                result: GethTrace::CallTracer(frame),
+                ..
+            } => Ok(frame),
+            TraceResult::Success { .. } => Err(eyre!(
+                "debug_traceBlockByNumber returned a non-callTracer frame for block {block_number}"
+            )),
+            TraceResult::Error { error, tx_hash } => Err(eyre!(
+                "Error tracing tx {tx_hash:?} in block {block_number}: {error}"
+            )),
+        })
+        .collect()
+}
+
 #[hotpath::measure(log = true, future = true)]
 pub(crate) async fn rpc_affected_addresses(
     tx_hash: TxHash,
@@ -0,0 +1,324 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Result, bail, eyre};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::txs::raw_query::SqlParam, misc::shared_init::config_path,
+    models::json::query_response::MAX_QUERY_DESC_CHARS,
+};
+
+/// A `.sql` file from the saved query library (`~/.mevlog/queries/`), run
+/// with `query --saved <name>`. Its leading `--` comment lines may carry a
+/// header:
+///
+/// ```sql
+/// -- description: USDC transfers of an account
+/// -- blocks: 1h:latest
+/// -- param: account
+/// -- param: token = 0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48
+/// SELECT ... WHERE address = :token AND topic1 = :account
+/// ```
+///
+/// Other comment lines are kept as part of the SQL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedQuery {
+    /// File stem, e.g. `usdc-transfers` for `usdc-transfers.sql`.
+    pub name: String,
+    pub description: Option<String>,
+    /// Block selection used when `--blocks` is not given (ignored with
+    /// `--skip-index`).
+    pub blocks: Option<String>,
+    pub params: Vec<SavedQueryParam>,
+    pub sql: String,
+}
+
+/// A `:name` placeholder a saved query declares, with an optional default.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedQueryParam {
+    pub name: String,
+    pub default: Option<String>,
+}
+
+pub fn saved_queries_dir() -> PathBuf {
+    config_path().join("queries")
+}
+
+/// `^[a-z0-9_-]+$`, so a name can't escape the library directory.
+fn valid_query_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+impl SavedQuery {
+    /// Loads `<name>.sql` from [`saved_queries_dir`].
+    pub fn load(name: &str) -> Result<Self> {
+        Self::load_from(&saved_queries_dir(), name)
+    }
+
+    /// Every query in [`saved_queries_dir`], sorted by name. A missing
+    /// directory is an empty library, and a file that fails to load is
+    /// skipped with a warning so one bad query doesn't hide the others.
+    pub fn list() -> Result<Vec<Self>> {
+        Self::list_in(&saved_queries_dir())
+    }
+
+    fn load_from(dir: &Path, name: &str) -> Result<Self> {
+        if !valid_query_name(name) {
+            bail!(
+                "invalid saved query name '{name}', expected lowercase letters, digits, '_' and '-'"
+            );
+        }
+        let path = dir.join(format!("{name}.sql"));
+        if !path.exists() {
+            bail!("saved query '{name}' not found at {}", path.display());
+        }
+        let content = fs::read_to_string(&path)?;
+        Self::parse(name, &content).map_err(|e| e.wrap_err(format!("in {}", path.display())))
+    }
+
+    fn list_in(dir: &Path) -> Result<Vec<Self>> {
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut names = vec![];
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "sql")
+                && let Some(stem) = path.file_stem().and_then(|stem| stem.to_str())
+                && valid_query_name(stem)
+            {
+                names.push(stem.to_string());
+            }
+        }
+        names.sort();
+
+        let mut queries = vec![];
+        for name in &names {
+            match Self::load_from(dir, name) {
+                Ok(query) => queries.push(query),
+                Err(e) => tracing::warn!(
+                    "skipping saved query {}: {e:#}",
+                    dir.join(format!("{name}.sql")).display()
+                ),
+            }
+        }
+        Ok(queries)
+    }
+
+    fn parse(name: &str, content: &str) -> Result<Self> {
+        let mut description: Vec<&str> = vec![];
+        let mut blocks = None;
+        let mut params: Vec<SavedQueryParam> = vec![];
+        let mut sql_lines = vec![];
+        let mut in_header = true;
+
+        for line in content.lines() {
+            let header_entry = line
+                .trim()
+                .strip_prefix("--")
+                .and_then(|comment| comment.trim().split_once(':'))
+                .map(|(key, value)| (key.trim(), value.trim()));
+
+            match header_entry {
+                Some(("description", value)) if in_header => description.push(value),
+                Some(("blocks", value)) if in_header => {
+                    if blocks.is_some() {
+                        bail!("saved query '{name}' declares 'blocks' more than once");
+                    }
+                    blocks = Some(value.to_string());
+                }
+                Some(("param", value)) if in_header => {
+                    let (param_name, default) = match value.split_once('=') {
+                        Some((param_name, default)) => {
+                            (param_name.trim(), Some(default.trim().to_string()))
+                        }
+                        None => (value, None),
+                    };
+                    let param_name = param_name.strip_prefix(':').unwrap_or(param_name);
+                    if params.iter().any(|p| p.name == param_name) {
+                        bail!("saved query '{name}' declares param '{param_name}' more than once");
+                    }
+                    let param = SavedQueryParam {
+                        name: param_name.to_string(),
+                        default,
+                    };
+                    // Validates the name, and the default as a bind value.
+                    format!("{}={}", param.name, param.default.as_deref().unwrap_or(""))
+                        .parse::<SqlParam>()?;
+                    params.push(param);
+                }
+                _ => {
+                    if !line.trim().is_empty() && !line.trim().starts_with("--") {
+                        in_header = false;
+                    }
+                    sql_lines.push(line);
+                }
+            }
+        }
+
+        let sql = sql_lines.join("\n").trim().to_string();
+        if sql.is_empty() {
+            bail!("saved query '{name}' has no SQL");
+        }
+        let description = Some(description.join(" ")).filter(|d| !d.is_empty());
+        if let Some(description) = &description {
+            let len = description.chars().count();
+            if len > MAX_QUERY_DESC_CHARS {
+                bail!(
+                    "saved query '{name}' description is {len} characters, max is {MAX_QUERY_DESC_CHARS}"
+                );
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            description,
+            blocks,
+            params,
+            sql,
+        })
+    }
+
+    /// Bind values for the declared params: `given` wins over a declared
+    /// default. Errors on a declared param left without a value, or a `given`
+    /// one the query doesn't declare.
+    pub fn bind(&self, given: &[SqlParam]) -> Result<Vec<SqlParam>> {
+        if let Some(unknown) = given
+            .iter()
+            .find(|g| !self.params.iter().any(|p| p.name == g.name))
+        {
+            bail!(
+                "saved query '{}' has no param '{}'",
+                self.name,
+                unknown.name
+            );
+        }
+
+        self.params
+            .iter()
+            .map(|param| {
+                if let Some(given) = given.iter().find(|g| g.name == param.name) {
+                    return Ok(given.clone());
+                }
+                let default = param.default.as_deref().ok_or_else(|| {
+                    eyre!(
+                        "saved query '{}' requires --param {}=VALUE",
+                        self.name,
+                        param.name
+                    )
+                })?;
+                format!("{}={default}", param.name).parse()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::txs::raw_query::SqlParamValue;
+
+    const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+
+    fn sample() -> String {
+        format!(
+            "-- description: USDC transfers\n\
+             -- description: of an account\n\
+             -- blocks: 1h:latest\n\
+             -- param: account\n\
+             -- param: token = {USDC}\n\
+             -- Sender is topic1.\n\
+             SELECT tx_hash FROM logs\n\
+             -- param: not_a_header\n\
+             WHERE address = :token AND topic1 = :account\n"
+        )
+    }
+
+    #[test]
+    fn test_parse_header_and_keep_sql_comments() -> Result<()> {
+        let query = SavedQuery::parse("usdc-transfers", &sample())?;
+
+        assert_eq!(
+            query.description.as_deref(),
+            Some("USDC transfers of an account")
+        );
+        assert_eq!(query.blocks.as_deref(), Some("1h:latest"));
+        assert_eq!(
+            query.params,
+            vec![
+                SavedQueryParam {
+                    name: "account".to_string(),
+                    default: None,
+                },
+                SavedQueryParam {
+                    name: "token".to_string(),
+                    default: Some(USDC.to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            query.sql,
+            "-- Sender is topic1.\nSELECT tx_hash FROM logs\n-- param: not_a_header\n\
+             WHERE address = :token AND topic1 = :account"
+        );
+
+        assert!(SavedQuery::parse("empty", "-- blocks: latest\n").is_err());
+        assert!(SavedQuery::parse("bad", "-- param: 1x\nSELECT 1").is_err());
+        assert!(SavedQuery::parse("bad", "-- param: a = 0x12\nSELECT 1").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bind_uses_given_values_then_defaults() -> Result<()> {
+        let query = SavedQuery::parse("usdc-transfers", &sample())?;
+
+        assert!(query.bind(&[]).is_err());
+        assert!(query.bind(&["other=1".parse()?]).is_err());
+
+        let account = format!("account=0x{}", "11".repeat(20));
+        let params = query.bind(&[account.parse()?])?;
+        assert_eq!(params[0].to_string(), account);
+        assert_eq!(params[1].to_string(), format!("token={USDC}"));
+        assert!(matches!(params[1].value, SqlParamValue::Blob(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_skips_malformed_files() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("mevlog-queries-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("usdc-transfers.sql"), sample())?;
+        fs::write(
+            dir.join("broken.sql"),
+            "-- blocks: 1h:latest\n-- blocks: 2h:latest\nSELECT 1\n",
+        )?;
+
+        let names: Vec<String> = SavedQuery::list_in(&dir)?
+            .into_iter()
+            .map(|query| query.name)
+            .collect();
+        assert_eq!(names, vec!["usdc-transfers".to_string()]);
+        // Loading it by name still reports the error.
+        assert!(SavedQuery::load_from(&dir, "broken").is_err());
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_names_escaping_the_library() {
+        assert!(valid_query_name("usdc-transfers_2"));
+        assert!(!valid_query_name("../config"));
+        assert!(!valid_query_name("Top"));
+        assert!(!valid_query_name(""));
+    }
+}
@@ -268,28 +268,52 @@ const DEFAULT_CRYO_INITIAL_BACKOFF_MS: u64 = 1000;
 pub struct CryoOpts {
     #[arg(
         long,
-        help = "Max RPC requests per second for cryo block fetching",
+        help = "Block data fetcher used when indexing ('cryo' or 'rpc'). Defaults to the `fetcher` config key, then 'cryo'"
+    )]
+    pub fetcher: Option<BlockFetcher>,
+
+    #[arg(
+        long,
+        help = "Don't store tx calldata in the transactions.input column (saves disk). Same as `store_input = false` in the config"
+    )]
+    pub skip_input: bool,
+
+    #[arg(
+        long,
+        help = "Fetch the receipt fees, header fields, access lists, authorizations and withdrawals missing from blocks indexed with cryo or import-parquet (one eth_getBlockByNumber + eth_getBlockReceipts per block). Same as `rpc_details = true` in the config"
+    )]
+    pub rpc_details: bool,
+
+    #[arg(
+        long,
+        help = "Resolve symbol, name and decimals of newly seen ERC20s via eth_call (three per token) for token_symbol/token_decimals. Same as `token_metadata = true` in the config"
+    )]
+    pub token_metadata: bool,
+
+    #[arg(
+        long,
+        help = "Max RPC requests per second for block fetching",
         default_value_t = DEFAULT_CRYO_REQUESTS_PER_SECOND
     )]
     pub cryo_requests_per_second: u64,
 
     #[arg(
         long,
-        help = "Max concurrent RPC requests for cryo block fetching",
+        help = "Max concurrent RPC requests for block fetching",
         default_value_t = DEFAULT_CRYO_MAX_CONCURRENT_REQUESTS
     )]
     pub cryo_max_concurrent_requests: u64,
 
     #[arg(
         long,
-        help = "Max retries for cryo RPC provider errors",
+        help = "Max retries for block fetching RPC provider errors",
         default_value_t = DEFAULT_CRYO_MAX_RETRIES
     )]
     pub cryo_max_retries: u64,
 
     #[arg(
         long,
-        help = "Initial retry backoff in milliseconds for cryo RPC provider errors",
+        help = "Initial retry backoff in milliseconds for block fetching RPC provider errors",
         default_value_t = DEFAULT_CRYO_INITIAL_BACKOFF_MS
     )]
     pub cryo_initial_backoff: u64,
@@ -298,6 +322,10 @@ pub struct CryoOpts {
 impl Default for CryoOpts {
     fn default() -> Self {
         Self {
+            fetcher: None,
+            skip_input: false,
+            rpc_details: false,
+            token_metadata: false,
             cryo_requests_per_second: DEFAULT_CRYO_REQUESTS_PER_SECOND,
             cryo_max_concurrent_requests: DEFAULT_CRYO_MAX_CONCURRENT_REQUESTS,
             cryo_max_retries: DEFAULT_CRYO_MAX_RETRIES,
@@ -306,6 +334,67 @@ impl Default for CryoOpts {
     }
 }
 
+impl CryoOpts {
+    /// Fetcher selected by `--fetcher`, falling back to the `fetcher` config key
+    /// and then to `cryo`.
+    pub(crate) fn block_fetcher(&self) -> Result<BlockFetcher> {
+        if let Some(fetcher) = self.fetcher {
+            return Ok(fetcher);
+        }
+        Ok(Config::load()?.fetcher().unwrap_or_default())
+    }
+
+    /// Whether indexing persists tx calldata: off with `--skip-input`,
+    /// otherwise per the `store_input` config key (default on).
+    pub(crate) fn store_input(&self) -> Result<bool> {
+        if self.skip_input {
+            return Ok(false);
+        }
+        Ok(Config::load()?.store_input())
+    }
+
+    /// Whether indexing backfills the RPC-only fields of blocks from cryo or
+    /// `import-parquet`: on with `--rpc-details`, otherwise per the
+    /// `rpc_details` config key (default off).
+    pub(crate) fn rpc_details(&self) -> Result<bool> {
+        if self.rpc_details {
+            return Ok(true);
+        }
+        Ok(Config::load()?.rpc_details())
+    }
+
+    /// Whether indexing resolves the metadata of newly seen ERC20s: on with
+    /// `--token-metadata`, otherwise per the `token_metadata` config key
+    /// (default off).
+    pub(crate) fn token_metadata(&self) -> Result<bool> {
+        if self.token_metadata {
+            return Ok(true);
+        }
+        Ok(Config::load()?.token_metadata())
+    }
+}
+
+/// Source of block, transaction and log data for indexing.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockFetcher {
+    /// Shells out to the external `cryo` binary and parses its parquet output.
+    #[default]
+    Cryo,
+    /// Built-in JSON-RPC fetcher (`eth_getBlockByNumber` + `eth_getBlockReceipts`).
+    Rpc,
+}
+
 #[derive(Debug, Clone, clap::ValueEnum, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TraceMode {
@@ -1,25 +1,33 @@
 use std::sync::Arc;
 
-use alloy::providers::Provider;
 use eyre::{Result, bail, eyre};
+use sqlx::SqlitePool;
 
 use crate::{
     GenericProvider,
-    misc::ens_utils::{ens_addr_lookup, ensure_ens_supported},
+    misc::{
+        args_parsing::{BlockTag, block_at_timestamp, get_head_block, parse_time_point},
+        ens_utils::{ens_addr_lookup, ensure_ens_supported},
+    },
 };
 
 /// The `query` command's `--sql` macros, each wrapped in braces. The plain-token
-/// macros are constants; the `RESOLVE_ENS` token carries an argument, so its
-/// grammar lives in [`extract_ens_names`] / [`ens_macro_token`].
+/// macros are constants; the `RESOLVE_ENS` and `BLOCK_AT` tokens carry a string
+/// argument, so their grammar lives in [`extract_string_args`] /
+/// [`string_macro_token`].
 pub(crate) const LATEST_BLOCK_MACRO: &str = "{LATEST_BLOCK()}";
 pub(crate) const NATIVE_TOKEN_PRICE_MACRO: &str = "{NATIVE_TOKEN_PRICE()}";
 const ENS_MACRO_OPEN: &str = "{RESOLVE_ENS(\"";
-const ENS_MACRO_CLOSE: &str = "\")}";
+const BLOCK_AT_MACRO_OPEN: &str = "{BLOCK_AT(\"";
+const STRING_MACRO_CLOSE: &str = "\")}";
 
 /// Expands the macro tokens supported in `--sql` into concrete literals, fetching
 /// each value only when its token is present:
-/// - `{LATEST_BLOCK()}` -> the chain's current latest block number. Resolved from
-///   `latest_block` when provided (no RPC), otherwise fetched via one RPC call.
+/// - `{LATEST_BLOCK()}` -> the block number of the chain's `head` (`latest`,
+///   `safe` or `finalized`). Resolved from `latest_block` when provided (no
+///   RPC), otherwise fetched via one RPC call.
+/// - `{BLOCK_AT("2025-01-01T00:00Z")}` -> the first block at or after that time
+///   (a timestamp, date or duration like `1h`), searched up to the same head.
 /// - `{NATIVE_TOKEN_PRICE()}` -> the native token's USD price; errors if no price
 ///   is available rather than silently producing wrong USD figures.
 /// - `{RESOLVE_ENS("name.eth")}` -> the resolved address as a `X'..'` blob literal
@@ -30,15 +38,31 @@ pub(crate) async fn substitute_sql_macros(
     chain_id: u64,
     native_token_price: Option<f64>,
     latest_block: Option<u64>,
+    head: BlockTag,
+    txs: &SqlitePool,
 ) -> Result<String> {
     let mut out = sql.to_string();
 
-    if out.contains(LATEST_BLOCK_MACRO) {
+    let block_at_args = extract_block_at_args(&out)?;
+    if out.contains(LATEST_BLOCK_MACRO) || !block_at_args.is_empty() {
         let latest = match latest_block {
             Some(latest) => latest,
-            None => provider.get_block_number().await?,
+            None => get_head_block(provider, head, None).await?,
         };
         out = out.replace(LATEST_BLOCK_MACRO, &latest.to_string());
+
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        for arg in block_at_args {
+            let timestamp = parse_time_point(&arg, now)
+                .ok_or_else(|| eyre!("BLOCK_AT argument {arg:?} is not a time"))?;
+            let block = block_at_timestamp(timestamp, latest, provider, txs)
+                .await?
+                .ok_or_else(|| eyre!("BLOCK_AT({arg:?}) is after the head block {latest}"))?;
+            out = out.replace(
+                &string_macro_token(BLOCK_AT_MACRO_OPEN, &arg),
+                &block.to_string(),
+            );
+        }
     }
 
     if out.contains(NATIVE_TOKEN_PRICE_MACRO) {
@@ -59,7 +83,7 @@ pub(crate) async fn substitute_sql_macros(
                 .await?
                 .ok_or_else(|| eyre!("ENS name {name:?} did not resolve to an address"))?;
             out = out.replace(
-                &ens_macro_token(&name),
+                &string_macro_token(ENS_MACRO_OPEN, &name),
                 &format!("X'{}'", hex::encode(addr)),
             );
         }
@@ -72,32 +96,52 @@ pub(crate) async fn substitute_sql_macros(
 /// deduplicated. Each name must end with `.eth`; anything else is rejected so a
 /// typo doesn't silently fall through to an unresolved token.
 pub(crate) fn extract_ens_names(sql: &str) -> Result<Vec<String>> {
-    let mut names = Vec::new();
+    let names = extract_string_args(sql, ENS_MACRO_OPEN)?;
+    if let Some(name) = names.iter().find(|name| !name.ends_with(".eth")) {
+        bail!("RESOLVE_ENS argument {name:?} must be an ENS name ending in .eth");
+    }
+
+    Ok(names)
+}
+
+/// Extracts the times from every `{BLOCK_AT("...")}` token in `sql`,
+/// deduplicated, rejecting anything [`parse_time_point`] doesn't accept.
+pub(crate) fn extract_block_at_args(sql: &str) -> Result<Vec<String>> {
+    let args = extract_string_args(sql, BLOCK_AT_MACRO_OPEN)?;
+    if let Some(arg) = args.iter().find(|arg| parse_time_point(arg, 0).is_none()) {
+        bail!(
+            "BLOCK_AT argument {arg:?} must be a timestamp (e.g. 2025-01-01T00:00Z), \
+             a date (2025-01-01) or a duration (1h)"
+        );
+    }
+
+    Ok(args)
+}
+
+/// Extracts the argument of every `open` + `")}` token in `sql`, deduplicated.
+fn extract_string_args(sql: &str, open: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
     let mut rest = sql;
-    while let Some(start) = rest.find(ENS_MACRO_OPEN) {
-        let after = &rest[start + ENS_MACRO_OPEN.len()..];
+    while let Some(start) = rest.find(open) {
+        let after = &rest[start + open.len()..];
         let end = after
-            .find(ENS_MACRO_CLOSE)
-            .ok_or_else(|| eyre!("unterminated {{RESOLVE_ENS(\"...\")}} token in --sql"))?;
-        let name = &after[..end];
-
-        if !name.ends_with(".eth") {
-            bail!("RESOLVE_ENS argument {name:?} must be an ENS name ending in .eth");
-        }
+            .find(STRING_MACRO_CLOSE)
+            .ok_or_else(|| eyre!("unterminated {open}...\")}} token in --sql"))?;
+        let arg = &after[..end];
 
-        if !names.contains(&name.to_string()) {
-            names.push(name.to_string());
+        if !args.contains(&arg.to_string()) {
+            args.push(arg.to_string());
         }
-        rest = &after[end + ENS_MACRO_CLOSE.len()..];
+        rest = &after[end + STRING_MACRO_CLOSE.len()..];
     }
 
-    Ok(names)
+    Ok(args)
 }
 
-/// Builds the `{RESOLVE_ENS("name")}` token for `name`, so substitution uses the
-/// same grammar that [`extract_ens_names`] parses.
-pub(crate) fn ens_macro_token(name: &str) -> String {
-    format!("{ENS_MACRO_OPEN}{name}{ENS_MACRO_CLOSE}")
+/// Builds the `open` + `arg` + `")}` token, so substitution uses the same
+/// grammar that [`extract_string_args`] parses.
+fn string_macro_token(open: &str, arg: &str) -> String {
+    format!("{open}{arg}{STRING_MACRO_CLOSE}")
 }
 
 #[cfg(test)]
@@ -119,9 +163,25 @@ mod test {
 
     #[test]
     fn ens_macro_token_roundtrips_with_extract() -> Result<()> {
-        let token = ens_macro_token("vitalik.eth");
+        let token = string_macro_token(ENS_MACRO_OPEN, "vitalik.eth");
         assert_eq!(token, "{RESOLVE_ENS(\"vitalik.eth\")}");
         assert_eq!(extract_ens_names(&token)?, vec!["vitalik.eth".to_string()]);
         Ok(())
     }
+
+    #[test]
+    fn extract_block_at_args_requires_a_time() -> Result<()> {
+        let args = extract_block_at_args(
+            "SELECT * FROM blocks WHERE block_number >= {BLOCK_AT(\"2025-01-01T00:00Z\")} \
+             AND block_number < {BLOCK_AT(\"1h\")}",
+        )?;
+        assert_eq!(
+            args,
+            vec!["2025-01-01T00:00Z".to_string(), "1h".to_string()]
+        );
+
+        assert!(extract_block_at_args("SELECT {BLOCK_AT(\"yesterday\")}").is_err());
+        assert!(extract_block_at_args("SELECT {BLOCK_AT(\"1h\"}").is_err());
+        Ok(())
+    }
 }
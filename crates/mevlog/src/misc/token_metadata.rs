@@ -0,0 +1,195 @@
+use std::path::PathBuf;
+
+use alloy::{
+    network::AnyNetwork,
+    primitives::Bytes,
+    providers::RootProvider,
+    sol,
+    sol_types::{SolCall, SolValue},
+    transports::TransportResult,
+};
+use eyre::Result;
+use futures_util::{StreamExt, stream};
+use revm::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::txs::models::token::Token,
+    misc::{
+        rpc_fetch::{RequestLimiter, fetch_provider},
+        shared_init::{CryoOpts, SharedDeps},
+    },
+};
+
+sol! {
+    #[sol(rpc)]
+    contract IERC20Metadata {
+        function name() external view returns (string);
+        function symbol() external view returns (string);
+        function decimals() external view returns (uint8);
+    }
+}
+
+/// On-disk record of a token's metadata, keyed by chain and address, so a
+/// purged or recreated txs DB doesn't repeat the `eth_call`s.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedToken {
+    symbol: Option<String>,
+    name: Option<String>,
+    decimals: Option<u8>,
+}
+
+fn token_cache_dir() -> PathBuf {
+    home::home_dir()
+        .unwrap()
+        .join(".mevlog")
+        .join(".token-cache")
+}
+
+fn token_cache_key(chain_id: u64, address: Address) -> String {
+    format!("{chain_id}-{address:#x}")
+}
+
+/// Resolves the metadata of every ERC20 that emitted a `Transfer` in
+/// `from..=to` and has no `tokens` row yet. Opt-in via
+/// [`CryoOpts::token_metadata`], since each token costs three `eth_call`s,
+/// paced and retried per the [`CryoOpts`] knobs like block fetching. Tokens
+/// whose calls fail on the transport are left out and retried on the next run
+/// with it enabled.
+pub(crate) async fn backfill_tokens(
+    from: u64,
+    to: u64,
+    deps: &SharedDeps,
+    cryo_opts: &CryoOpts,
+) -> Result<()> {
+    let unknown = Token::unknown_erc20_addresses(from, to, &deps.txs).await?;
+    if unknown.is_empty() {
+        return Ok(());
+    }
+
+    let chain_id = deps.chain.chain_id;
+    let provider = fetch_provider(&deps.rpc_url, cryo_opts)?;
+    let limiter = RequestLimiter::new(cryo_opts.cryo_requests_per_second);
+    let concurrency = cryo_opts.cryo_max_concurrent_requests.max(1) as usize;
+
+    let results: Vec<(Address, Result<Token>)> = stream::iter(unknown)
+        .map(|address| {
+            let (provider, limiter) = (&provider, &limiter);
+            async move {
+                (
+                    address,
+                    fetch_token(address, chain_id, provider, limiter).await,
+                )
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    let mut tokens = Vec::with_capacity(results.len());
+    for (address, result) in results {
+        match result {
+            Ok(token) => tokens.push(token),
+            Err(e) => tracing::warn!("token metadata failed for {address}: {e}"),
+        }
+    }
+    tracing::info!("Resolved metadata of {} new token(s)", tokens.len());
+
+    Token::save_batch(&tokens, &deps.txs).await
+}
+
+/// A token's `symbol()`, `name()` and `decimals()`, from the on-disk cache
+/// or via `eth_call`. A call that reverts or returns garbage leaves its field
+/// `None`; only transport errors fail.
+async fn fetch_token(
+    address: Address,
+    chain_id: u64,
+    provider: &RootProvider<AnyNetwork>,
+    limiter: &RequestLimiter,
+) -> Result<Token> {
+    let key = token_cache_key(chain_id, address);
+    if let Ok(bytes) = cacache::read(&token_cache_dir(), &key).await
+        && let Ok(cached) = serde_json::from_slice::<CachedToken>(&bytes)
+    {
+        return Ok(Token {
+            address,
+            symbol: cached.symbol,
+            name: cached.name,
+            decimals: cached.decimals,
+        });
+    }
+
+    let erc20 = IERC20Metadata::new(address, provider);
+    limiter.acquire().await;
+    let symbol = call_output(erc20.symbol().call_raw().await)?.and_then(|out| decode_text(&out));
+    limiter.acquire().await;
+    let name = call_output(erc20.name().call_raw().await)?.and_then(|out| decode_text(&out));
+    limiter.acquire().await;
+    let decimals = call_output(erc20.decimals().call_raw().await)?.and_then(|out| {
+        // Some tokens return a full uint256; anything that fits a u8 is valid.
+        U256::abi_decode(&out)
+            .ok()
+            .and_then(|d| u8::try_from(d).ok())
+    });
+
+    let cached = CachedToken {
+        symbol,
+        name,
+        decimals,
+    };
+    if let Err(e) = cacache::write(&token_cache_dir(), &key, serde_json::to_vec(&cached)?).await {
+        tracing::error!("Error writing token cache: {}", e);
+    }
+
+    Ok(Token {
+        address,
+        symbol: cached.symbol,
+        name: cached.name,
+        decimals: cached.decimals,
+    })
+}
+
+/// Return data of a metadata call: `None` when the node answered with an
+/// error (e.g. a revert), `Err` when the call never got an answer.
+fn call_output(result: TransportResult<Bytes>) -> Result<Option<Bytes>> {
+    match result {
+        Ok(out) => Ok(Some(out)),
+        Err(e) if e.as_error_resp().is_some() => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Decodes a `string` return value, falling back to the `bytes32` that some
+/// early tokens (e.g. MKR) return instead.
+fn decode_text(out: &[u8]) -> Option<String> {
+    if let Ok(text) = IERC20Metadata::symbolCall::abi_decode_returns(out) {
+        return Some(text).filter(|text| !text.is_empty());
+    }
+    let word: [u8; 32] = out.try_into().ok()?;
+    let end = word.iter().position(|&b| b == 0).unwrap_or(32);
+    String::from_utf8(word[..end].to_vec())
+        .ok()
+        .filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use revm::primitives::B256;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_text_handles_string_and_bytes32() {
+        let string = "USDC".to_string().abi_encode();
+        assert_eq!(decode_text(&string).as_deref(), Some("USDC"));
+
+        let mut word = [0u8; 32];
+        word[..3].copy_from_slice(b"MKR");
+        assert_eq!(
+            decode_text(B256::from(word).as_slice()).as_deref(),
+            Some("MKR")
+        );
+
+        assert_eq!(decode_text(&[]), None);
+    }
+}
@@ -1,8 +1,17 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
 
 use alloy::{
-    eips::BlockNumberOrTag, network::ReceiptResponse, primitives::TxHash, providers::Provider,
-    rpc::types::trace::parity::Action,
+    eips::BlockNumberOrTag,
+    network::ReceiptResponse,
+    primitives::TxHash,
+    providers::Provider,
+    rpc::types::trace::{
+        geth::CallFrame,
+        parity::{Action, TransactionTrace},
+    },
 };
 use eyre::Result;
 use revm::primitives::{Address, TxKind, U256};
@@ -10,14 +19,25 @@ use sqlx::SqlitePool;
 
 use crate::{
     GenericProvider,
-    db::txs::models::transaction::{Transaction, extract_signature},
+    db::txs::models::{
+        balance_change::BalanceChange,
+        block::{Block, BlockMarker},
+        contract::Contract,
+        log::Log,
+        trace::Trace,
+        transaction::{Transaction, extract_signature},
+    },
     misc::{
         coinbase_bribe::{TraceData, find_coinbase_transfer},
+        native_transfers::{NativeTransfer, call_frame_transfers, parity_transfers},
         revm_tracing::{
             backfill_revm, revm_affected_addresses_for_tx, revm_block_traced_calls,
             revm_calls_for_tx, revm_state_diff_for_tx,
         },
-        rpc_tracing::{backfill_rpc, rpc_affected_addresses, rpc_tx_calls, rpc_tx_state_diff},
+        rpc_tracing::{
+            backfill_rpc, rpc_affected_addresses, rpc_block_call_frames, rpc_tx_calls,
+            rpc_tx_state_diff,
+        },
         shared_init::TraceMode,
         utils::{ETH_TRANSFER, wei_to_eth},
     },
@@ -255,3 +275,199 @@ pub(crate) async fn backfill_coinbase_transfers(
 
     Ok(())
 }
+
+/// A block's call traces, traced once and shared by every per-block backfill
+/// of [`backfill_block_traces`].
+enum BlockTrace {
+    /// Root `callTracer` frame of every tx, in index order.
+    Rpc(Vec<CallFrame>),
+    /// Parity traces of every stored tx, by hash.
+    Revm(HashMap<TxHash, Vec<TransactionTrace>>),
+}
+
+impl BlockTrace {
+    /// Traces `block_txs`, the stored txs of `block_number`: with a single
+    /// `debug_traceBlockByNumber` call in RPC mode, or by replaying the block
+    /// locally in Revm mode.
+    async fn fetch(
+        block_number: u64,
+        block_txs: &[Transaction],
+        mode: &TraceMode,
+        provider: &Arc<GenericProvider>,
+        chain: &EVMChain,
+        rpc_url: &str,
+    ) -> Result<Self> {
+        if block_txs.is_empty() {
+            return Ok(match mode {
+                TraceMode::RPC => Self::Rpc(vec![]),
+                TraceMode::Revm => Self::Revm(HashMap::new()),
+            });
+        }
+
+        match mode {
+            TraceMode::RPC => Ok(Self::Rpc(
+                rpc_block_call_frames(block_number, provider).await?,
+            )),
+            TraceMode::Revm => {
+                let targets: HashSet<TxHash> = block_txs.iter().map(|tx| tx.tx_hash).collect();
+                let (_, traced) =
+                    revm_block_traced_calls(block_number, &targets, provider, rpc_url, chain, None)
+                        .await?;
+                Ok(Self::Revm(traced))
+            }
+        }
+    }
+
+    fn native_transfers(&self, tx: &Transaction) -> Vec<NativeTransfer> {
+        match self {
+            Self::Rpc(roots) => roots
+                .get(tx.tx_index as usize)
+                .map(call_frame_transfers)
+                .unwrap_or_default(),
+            Self::Revm(traced) => traced
+                .get(&tx.tx_hash)
+                .map(Vec::as_slice)
+                .map(parity_transfers)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Unresolved [`Trace`] rows of every tx, and the [`Contract`]s its creates
+    /// deployed.
+    fn traces(&self, block_number: u64, block_txs: &[Transaction]) -> (Vec<Trace>, Vec<Contract>) {
+        let mut traces = vec![];
+        let mut contracts = vec![];
+        match self {
+            Self::Rpc(roots) => {
+                for (tx_index, root) in roots.iter().enumerate() {
+                    traces.extend(Trace::from_call_frame(root, block_number, tx_index as u64));
+                    contracts.extend(Contract::from_call_frame(
+                        root,
+                        block_number,
+                        tx_index as u64,
+                    ));
+                }
+            }
+            Self::Revm(traced) => {
+                for tx in block_txs {
+                    let calls = traced
+                        .get(&tx.tx_hash)
+                        .map(Vec::as_slice)
+                        .unwrap_or_default();
+                    traces.extend(Trace::from_parity(calls, block_number, tx.tx_index));
+                    contracts.extend(Contract::from_parity(calls, block_number, tx.tx_index));
+                }
+            }
+        }
+
+        (traces, contracts)
+    }
+}
+
+/// Records the per-block rows of each of `markers` (`BalanceChanges` and/or
+/// `Traces`) for every indexed block in `from..=to` not yet marked with it.
+/// A block is traced once for all of its missing markers.
+///
+/// `balance_changes` get their ERC20 deltas from the stored `Transfer` logs and
+/// their native ETH deltas from each tx's traced value transfers. `traces` get
+/// every call frame, along with the internal deployments found into
+/// `contracts`.
+///
+/// Rows are saved in the same transaction that marks the block, so a failed
+/// trace leaves the block to be retried on the next run. Like
+/// [`backfill_coinbase_transfers`], it covers blocks indexed earlier without
+/// tracing.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn backfill_block_traces(
+    from: u64,
+    to: u64,
+    markers: &[BlockMarker],
+    mode: &TraceMode,
+    provider: &Arc<GenericProvider>,
+    chain: &EVMChain,
+    rpc_url: &str,
+    txs: &sqlx::SqlitePool,
+    sqlite: &sqlx::SqlitePool,
+) -> Result<()> {
+    let mut untraced: BTreeMap<u64, Vec<BlockMarker>> = BTreeMap::new();
+    for &marker in markers {
+        if !matches!(marker, BlockMarker::BalanceChanges | BlockMarker::Traces) {
+            eyre::bail!("{marker:?} is not recorded by tracing blocks");
+        }
+        for block_number in Block::unmarked(marker, from, to, txs).await? {
+            untraced.entry(block_number).or_default().push(marker);
+        }
+    }
+    if untraced.is_empty() {
+        return Ok(());
+    }
+
+    let total = untraced.len();
+    tracing::info!("Tracing {total} blocks ({mode})");
+
+    for (n, (block_number, block_markers)) in untraced.into_iter().enumerate() {
+        let block_txs =
+            Transaction::query_where(&format!("block_number = {block_number}"), txs).await?;
+        let trace =
+            match BlockTrace::fetch(block_number, &block_txs, mode, provider, chain, rpc_url).await
+            {
+                Ok(trace) => trace,
+                Err(e) => {
+                    tracing::warn!("tracing block {block_number} failed: {e}");
+                    continue;
+                }
+            };
+
+        for marker in block_markers {
+            match marker {
+                BlockMarker::BalanceChanges => {
+                    let changes =
+                        block_balance_changes(block_number, &block_txs, &trace, txs).await?;
+                    BalanceChange::save_block(block_number, &changes, txs).await?;
+                }
+                BlockMarker::Traces => {
+                    let (mut traces, contracts) = trace.traces(block_number, &block_txs);
+                    Trace::resolve_signatures(&mut traces, sqlite).await?;
+                    // Saved before the block is marked traced, so an interrupted
+                    // run re-traces the block and upserts them again.
+                    Contract::save_batch(&contracts, txs).await?;
+                    Trace::save_block(block_number, &traces, txs).await?;
+                }
+                BlockMarker::RpcDetails => unreachable!(),
+            }
+        }
+        tracing::info!("Committed traced block {}/{total} ({block_number})", n + 1);
+    }
+
+    Ok(())
+}
+
+/// Nets the balance changes of every stored tx of `block_number`.
+async fn block_balance_changes(
+    block_number: u64,
+    block_txs: &[Transaction],
+    trace: &BlockTrace,
+    txs: &sqlx::SqlitePool,
+) -> Result<Vec<BalanceChange>> {
+    if block_txs.is_empty() {
+        return Ok(vec![]);
+    }
+    let logs = Log::query_where(
+        &format!("block_number = {block_number} AND erc20_amount IS NOT NULL"),
+        txs,
+    )
+    .await?;
+
+    Ok(block_txs
+        .iter()
+        .flat_map(|tx| {
+            let tx_logs: Vec<Log> = logs
+                .iter()
+                .filter(|log| log.tx_index == tx.tx_index)
+                .cloned()
+                .collect();
+            let transfers = trace.native_transfers(tx);
+            BalanceChange::from_tx(block_number, tx.tx_index, &tx_logs, &transfers)
+        })
+        .collect())
+}
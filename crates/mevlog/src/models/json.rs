@@ -1,5 +1,7 @@
 pub mod block_json;
 pub mod db_info_response;
+pub mod export_response;
+pub mod import_response;
 pub mod index_response;
 pub mod log_json;
 pub mod purge_response;
@@ -1,4 +1,4 @@
-use revm::primitives::{Address, FixedBytes};
+use revm::primitives::{Address, Bytes, FixedBytes};
 use serde::{Deserialize, Serialize};
 
 /// JSON representation of a single block's metadata; the deserialization contract
@@ -12,12 +12,27 @@ pub struct BlockJson {
     /// Fee recipient (cryo `author`).
     pub miner: Address,
     pub gas_used: u64,
+    /// `None` (like the header fields below) for blocks indexed before the
+    /// column existed.
+    pub gas_limit: Option<u64>,
     /// Unix timestamp (seconds).
     pub timestamp: u64,
     /// `None` for pre-EIP-1559 blocks.
     pub base_fee_per_gas: Option<u64>,
     /// Base fee formatted in gwei, `None` when there is no base fee.
     pub display_base_fee_per_gas: Option<String>,
+    /// Free-form header bytes (builder tag).
+    pub extra_data: Option<Bytes>,
+    /// Block size in bytes.
+    pub size: Option<u64>,
+    pub state_root: Option<FixedBytes<32>>,
+    pub transactions_root: Option<FixedBytes<32>>,
+    /// Decimal string; `None` for `cryo`-indexed blocks.
+    pub difficulty: Option<String>,
+    /// `prevrandao` after the merge; `None` for `cryo`-indexed blocks.
+    pub mix_hash: Option<FixedBytes<32>>,
+    /// `None` for pre-Cancun and `cryo`-indexed blocks.
+    pub parent_beacon_block_root: Option<FixedBytes<32>>,
     /// Number of indexed transactions in the block.
     pub txs_count: u64,
 }
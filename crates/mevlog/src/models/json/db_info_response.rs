@@ -14,8 +14,15 @@ pub struct DbInfoResponse {
     pub db_size_bytes: u64,
     pub wal_size_bytes: u64,
     pub blocks: u64,
+    /// Blocks saved by `index --logs-only`, not included in `blocks` or the
+    /// block range.
+    pub logs_only_blocks: u64,
     pub transactions: u64,
     pub logs: u64,
+    pub withdrawals: u64,
+    pub transactions_with_input: u64,
+    pub input_size: String,
+    pub input_bytes: u64,
     pub min_block: Option<u64>,
     pub max_block: Option<u64>,
     pub min_block_timestamp: Option<u64>,
@@ -42,8 +49,13 @@ impl DbInfoResponse {
             db_size_bytes,
             wal_size_bytes,
             blocks: stats.blocks,
+            logs_only_blocks: stats.logs_only_blocks,
             transactions: stats.transactions,
             logs: stats.logs,
+            withdrawals: stats.withdrawals,
+            transactions_with_input: stats.transactions_with_input,
+            input_size: format_size(stats.input_bytes),
+            input_bytes: stats.input_bytes,
             min_block: stats.min_block,
             max_block: stats.max_block,
             min_block_timestamp: stats.min_block_timestamp,
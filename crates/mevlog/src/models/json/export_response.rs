@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::txs::export::{ExportFormat, ExportedTable},
+    models::json::query_response::format_duration,
+};
+
+/// Status envelope emitted by the `export` command after writing the local txs
+/// DB's `from..=to` range to partitioned files under `out_dir`. Per-table row
+/// and file counts are listed in `tables`; empty partitions produce no file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportResponse {
+    pub out_dir: String,
+    pub format: ExportFormat,
+    pub chain_id: u64,
+    pub from: u64,
+    pub to: u64,
+    pub tables: Vec<ExportedTable>,
+    pub total_rows: u64,
+    pub total_files: u64,
+    pub duration: String,
+}
+
+impl ExportResponse {
+    pub fn new(
+        out_dir: String,
+        format: ExportFormat,
+        chain_id: u64,
+        from: u64,
+        to: u64,
+        tables: Vec<ExportedTable>,
+        duration_ns: u64,
+    ) -> Self {
+        Self {
+            out_dir,
+            format,
+            chain_id,
+            from,
+            to,
+            total_rows: tables.iter().map(|t| t.rows).sum(),
+            total_files: tables.iter().map(|t| t.files).sum(),
+            tables,
+            duration: format_duration(duration_ns),
+        }
+    }
+}
+
+/// Serializes an `ExportResponse` as JSON (pretty when requested).
+pub fn serialize_export_response(
+    resp: &ExportResponse,
+    pretty: bool,
+) -> serde_json::Result<String> {
+    if pretty {
+        serde_json::to_string_pretty(resp)
+    } else {
+        serde_json::to_string(resp)
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChainInfoNoRpcsJson, db::txs::import::ImportStats,
+    models::json::query_response::format_duration,
+};
+
+/// Status envelope emitted by the `import-parquet` command after loading a
+/// directory of cryo parquet files into the local txs DB. `from`/`to` span the
+/// dataset's `blocks` files; blocks already indexed count as skipped.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportResponse {
+    pub dir: String,
+    pub files: u64,
+    pub from: u64,
+    pub to: u64,
+    pub imported_blocks: u64,
+    pub skipped_blocks: u64,
+    pub incomplete_blocks: u64,
+    pub duration: String,
+    pub chain: ChainInfoNoRpcsJson,
+}
+
+impl ImportResponse {
+    pub fn new(
+        dir: String,
+        stats: ImportStats,
+        duration_ns: u64,
+        chain: ChainInfoNoRpcsJson,
+    ) -> Self {
+        Self {
+            dir,
+            files: stats.files,
+            from: stats.from,
+            to: stats.to,
+            imported_blocks: stats.imported_blocks,
+            skipped_blocks: stats.skipped_blocks,
+            incomplete_blocks: stats.incomplete_blocks,
+            duration: format_duration(duration_ns),
+            chain,
+        }
+    }
+}
+
+/// Serializes an `ImportResponse` as JSON (pretty when requested).
+pub fn serialize_import_response(
+    resp: &ImportResponse,
+    pretty: bool,
+) -> serde_json::Result<String> {
+    if pretty {
+        serde_json::to_string_pretty(resp)
+    } else {
+        serde_json::to_string(resp)
+    }
+}
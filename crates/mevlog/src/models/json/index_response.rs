@@ -6,17 +6,55 @@ use crate::{ChainInfoNoRpcsJson, models::json::query_response::format_duration};
 /// into the local txs DB. Reports block counts and timing only (no rows).
 #[derive(Debug, Serialize, Deserialize)]
 pub struct IndexResponse {
+    /// Resolved block selection, one comma-separated segment per input segment.
     pub blocks: String,
+    /// Lowest selected block.
     pub from: u64,
+    /// Highest selected block.
     pub to: u64,
     pub total_blocks: u64,
     pub new_blocks: u64,
     pub cached_blocks: u64,
     pub duration: String,
     pub chain: ChainInfoNoRpcsJson,
+    /// Chunks each `--rpc-url` endpoint fetched and failed, in flag order.
+    #[serde(default)]
+    pub endpoints: Vec<RpcEndpointJson>,
+    /// Set by `--logs-only` runs, whose block counts are blocks scanned with
+    /// `eth_getLogs` rather than blocks indexed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logs_only: Option<LogsOnlyJson>,
+}
+
+/// Fetch outcome of one RPC endpoint over an index run. A failed chunk is
+/// retried on another endpoint, so failures don't imply missing blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcEndpointJson {
+    /// Host (and port) of the endpoint; the rest of the URL is left out as it
+    /// may hold an API key.
+    pub endpoint: String,
+    pub ok_chunks: u64,
+    pub failed_chunks: u64,
+}
+
+/// Matches of an `index --logs-only` run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogsOnlyJson {
+    /// Canonical form of the log filter, which keys its scanned-range
+    /// bookkeeping.
+    pub filter: String,
+    pub matched_logs: u64,
+    /// Txs that emitted a matching log, saved with all their logs.
+    pub matched_txs: u64,
+    /// Blocks with a matching log, saved flagged `logs_only`.
+    pub matched_blocks: u64,
+    /// `eth_getLogs` calls made, including those rejected for matching too
+    /// many logs.
+    pub get_logs_calls: u64,
 }
 
 impl IndexResponse {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         blocks: String,
         from: u64,
@@ -25,6 +63,7 @@ impl IndexResponse {
         new_blocks: u64,
         duration_ns: u64,
         chain: ChainInfoNoRpcsJson,
+        endpoints: Vec<RpcEndpointJson>,
     ) -> Self {
         Self {
             blocks,
@@ -35,6 +74,8 @@ impl IndexResponse {
             cached_blocks,
             duration: format_duration(duration_ns),
             chain,
+            endpoints,
+            logs_only: None,
         }
     }
 }
@@ -15,6 +15,14 @@ pub struct PurgeResponse {
     pub purged_blocks: u64,
     pub purged_transactions: u64,
     pub purged_logs: u64,
+    pub purged_access_list_entries: u64,
+    pub purged_authorizations: u64,
+    pub purged_withdrawals: u64,
+    pub purged_balance_changes: u64,
+    pub purged_traces: u64,
+    pub purged_contracts: u64,
+    pub purged_nft_transfers: u64,
+    pub purged_input_bytes: u64,
     pub duration: String,
 }
 
@@ -28,6 +36,14 @@ impl PurgeResponse {
             purged_blocks: stats.purged_blocks,
             purged_transactions: stats.purged_transactions,
             purged_logs: stats.purged_logs,
+            purged_access_list_entries: stats.purged_access_list_entries,
+            purged_authorizations: stats.purged_authorizations,
+            purged_withdrawals: stats.purged_withdrawals,
+            purged_balance_changes: stats.purged_balance_changes,
+            purged_traces: stats.purged_traces,
+            purged_contracts: stats.purged_contracts,
+            purged_nft_transfers: stats.purged_nft_transfers,
+            purged_input_bytes: stats.purged_input_bytes,
             duration: format_duration(duration_ns),
         }
     }
@@ -5,7 +5,10 @@ use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 
-use crate::{ChainInfoNoRpcsJson, misc::shared_init::TraceMode};
+use crate::{
+    ChainInfoNoRpcsJson,
+    misc::{args_parsing::BlockTag, shared_init::TraceMode},
+};
 
 /// Maximum length (in characters) of the user-provided `--desc` query
 /// description.
@@ -66,6 +69,7 @@ pub struct HtmlMeta<'a> {
     pub chain_id: u64,
     pub blocks: Option<&'a str>,
     pub latest_block: Option<u64>,
+    pub latest_block_tag: Option<BlockTag>,
     pub sql: Option<&'a str>,
     pub description: Option<&'a str>,
     pub row_count: usize,
@@ -184,7 +188,10 @@ pub fn rows_to_html(columns: &[String], rows: &[Value], meta: &HtmlMeta) -> Stri
 
     let latest_block_segment = meta
         .latest_block
-        .map(|n| format!("latest block <b>{n}</b><span class=\"sep\">\u{b7}</span>"))
+        .map(|n| {
+            let head = meta.latest_block_tag.unwrap_or_default();
+            format!("{head} block <b>{n}</b><span class=\"sep\">\u{b7}</span>")
+        })
         .unwrap_or_default();
 
     let sql_details = meta
@@ -335,12 +342,16 @@ details.sql pre {{
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QueryParams {
+    /// Resolved block selection, e.g. `100,200:210`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub blocks: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sql: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub evm_trace: Option<TraceMode>,
+    /// Bound `--param` values, as `name=value`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<String>,
 }
 
 /// In-process result of a SQL-backed command, produced by the `cmds` layer.
@@ -358,6 +369,9 @@ pub struct QueryOutcome {
     /// is. `None` when it was never resolved (`--skip-index` without an
     /// explicit latest block).
     pub latest_block: Option<u64>,
+    /// Head `latest_block` was resolved against. `None` when it was passed in
+    /// explicitly (e.g. the backend's indexed head) or never resolved.
+    pub latest_block_tag: Option<BlockTag>,
     pub duration_ns: u64,
     pub chain: ChainInfoNoRpcsJson,
     pub query: QueryParams,
@@ -399,6 +413,10 @@ pub struct QueryResponse {
     /// (`--skip-index` without an explicit latest block).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub latest_block: Option<u64>,
+    /// Head (`latest`, `safe` or `finalized`) `latest_block` was resolved
+    /// against; absent when it was given explicitly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_block_tag: Option<BlockTag>,
     pub duration: String,
     /// UTC render time (RFC 3339); absent in envelopes predating the field.
     #[serde(default)]
@@ -418,6 +436,7 @@ pub fn serialize_query_response(
     cached_blocks: u64,
     new_blocks: u64,
     latest_block: Option<u64>,
+    latest_block_tag: Option<BlockTag>,
     query: QueryParams,
     description: Option<String>,
 ) -> serde_json::Result<String> {
@@ -428,6 +447,7 @@ pub fn serialize_query_response(
         cached_blocks,
         new_blocks,
         latest_block,
+        latest_block_tag,
         duration: format_duration(duration_ns),
         generated_at: generated_at_utc(),
         chain,
@@ -530,6 +550,7 @@ mod test {
             blocks: Some("100:101".to_string()),
             sql: Some("SELECT * FROM transactions".to_string()),
             evm_trace: None,
+            params: vec![],
         }
     }
 
@@ -539,6 +560,7 @@ mod test {
             chain_id: 1,
             blocks: Some("100:101"),
             latest_block: Some(102),
+            latest_block_tag: None,
             sql: Some("SELECT * FROM transactions"),
             description: None,
             row_count: 2,
@@ -564,6 +586,13 @@ mod test {
         assert!(!html.contains("<script"));
         assert!(html.contains("latest block <b>102</b>"));
 
+        let meta = HtmlMeta {
+            latest_block_tag: Some(BlockTag::Finalized),
+            ..sample_meta()
+        };
+        let html = rows_to_html(&sample_columns(), &sample_rows(), &meta);
+        assert!(html.contains("finalized block <b>102</b>"));
+
         let meta = HtmlMeta {
             latest_block: None,
             ..sample_meta()
@@ -650,6 +679,7 @@ mod test {
             0,
             0,
             Some(102),
+            Some(BlockTag::Safe),
             sample_query(),
             Some("weekly USDC report".to_string()),
         )
@@ -657,6 +687,8 @@ mod test {
         let parsed: QueryResponse = serde_json::from_str(&body).unwrap();
         assert_eq!(parsed.description.as_deref(), Some("weekly USDC report"));
         assert_eq!(parsed.latest_block, Some(102));
+        assert_eq!(parsed.latest_block_tag, Some(BlockTag::Safe));
+        assert!(body.contains(r#""latest_block_tag":"safe""#));
         // RFC 3339 UTC stamp, e.g. 2026-07-11T13:19:35Z.
         assert!(parsed.generated_at.ends_with('Z') && parsed.generated_at.contains('T'));
 
@@ -668,6 +700,7 @@ mod test {
             0,
             0,
             None,
+            None,
             sample_query(),
             None,
         )
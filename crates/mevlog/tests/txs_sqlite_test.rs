@@ -13,9 +13,9 @@ pub mod tests {
             models::{log::Log, transaction::Transaction},
         },
         models::json::{
-            block_json::BlockJson, db_info_response::DbInfoResponse, log_json::LogJson,
-            purge_response::PurgeResponse, query_response::QueryResponse,
-            transaction_json::TransactionJson,
+            block_json::BlockJson, db_info_response::DbInfoResponse,
+            import_response::ImportResponse, log_json::LogJson, purge_response::PurgeResponse,
+            query_response::QueryResponse, transaction_json::TransactionJson,
         },
     };
     use uuid::Uuid;
@@ -175,6 +175,20 @@ pub mod tests {
             .expect("failed to execute CLI")
     }
 
+    fn run_import_parquet(rpc_url: &str, tmp_dir: &Path) -> Output {
+        Command::new("cargo")
+            .env("RUST_LOG", "off")
+            .args(["run", "--bin", "mevlog", "--", "import-parquet"])
+            .args(["--dir", &fixtures_dir().to_string_lossy()])
+            .args(["--chain-id", &CHAIN_ID.to_string()])
+            .args(["--rpc-url", rpc_url])
+            .arg("--skip-verify-chain-id")
+            .args(["--txs-db-dir", &tmp_dir.to_string_lossy()])
+            .args(["--format", "json"])
+            .output()
+            .expect("failed to execute CLI")
+    }
+
     #[tokio::test]
     async fn test_db_info_and_purge_removes_indexed_data() -> Result<()> {
         let rpc_url = std::env::var("ETH_RPC_URL").expect("ETH_RPC_URL must be set");
@@ -272,6 +286,10 @@ pub mod tests {
         assert_eq!(resp.blocks, 0, "blocks should be empty after purge");
         assert_eq!(resp.transactions, 0, "txs should be empty after purge");
         assert_eq!(resp.logs, 0, "logs should be empty after purge");
+        assert_eq!(
+            resp.withdrawals, 0,
+            "withdrawals should be empty after purge"
+        );
         assert_eq!(resp.min_block, None, "min_block should be null after purge");
         assert_eq!(resp.max_block, None, "max_block should be null after purge");
         assert_eq!(resp.min_block_time, None, "min_block_time should be null");
@@ -706,6 +724,67 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_import_parquet_loads_fixtures_and_skips_indexed_blocks() -> Result<()> {
+        let rpc_url = std::env::var("ETH_RPC_URL").expect("ETH_RPC_URL must be set");
+
+        let tmp_dir = std::env::temp_dir().join(format!("mevlog-sqlite-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&tmp_dir)?;
+
+        let total_blocks = TO_BLOCK - FROM_BLOCK + 1;
+
+        let first = run_import_parquet(&rpc_url, &tmp_dir);
+        assert!(
+            first.status.success(),
+            "first import failed: stdout={}, stderr={}",
+            String::from_utf8_lossy(&first.stdout),
+            String::from_utf8_lossy(&first.stderr),
+        );
+        let first_json: ImportResponse = serde_json::from_slice(&first.stdout)?;
+        assert_eq!(first_json.files, 3, "files mismatch");
+        assert_eq!(first_json.from, FROM_BLOCK, "from mismatch");
+        assert_eq!(first_json.to, TO_BLOCK, "to mismatch");
+        assert_eq!(first_json.imported_blocks, total_blocks, "first imported");
+        assert_eq!(first_json.skipped_blocks, 0, "first skipped");
+        assert_eq!(first_json.incomplete_blocks, 0, "first incomplete");
+
+        let db_path = tmp_dir.join(txs::db_file_name(txs::SCHEMA_VERSION, CHAIN_ID));
+        let conn = txs::conn(
+            Some(db_path.to_string_lossy().into_owned()),
+            CHAIN_ID,
+            false,
+        )
+        .await?;
+
+        let blocks_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM blocks")
+            .fetch_one(&conn)
+            .await?;
+        let logs_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM logs")
+            .fetch_one(&conn)
+            .await?;
+        let resolved: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM transactions WHERE signature = ?")
+                .bind(TX_SIGNATURE)
+                .fetch_one(&conn)
+                .await?;
+        assert_eq!(blocks_count as u64, total_blocks, "blocks count");
+        assert_eq!(logs_count, 3868, "logs count");
+        assert!(resolved > 0, "tx signatures should be resolved");
+
+        let second = run_import_parquet(&rpc_url, &tmp_dir);
+        assert!(
+            second.status.success(),
+            "second import failed: {}",
+            String::from_utf8_lossy(&second.stderr),
+        );
+        let second_json: ImportResponse = serde_json::from_slice(&second.stdout)?;
+        assert_eq!(second_json.imported_blocks, 0, "second imported");
+        assert_eq!(second_json.skipped_blocks, total_blocks, "second skipped");
+
+        fs::remove_dir_all(&tmp_dir).ok();
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_tx_command_returns_exact_payload() -> Result<()> {
         let rpc_url = std::env::var("ETH_RPC_URL").expect("ETH_RPC_URL must be set");